@@ -0,0 +1,158 @@
+//! Attribute macros for [criterion-decimal-throughput](https://docs.rs/criterion-decimal-throughput).
+//!
+//! Use them through the `macros` feature of the main crate, which re-exports them.
+
+#![warn(missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{meta::ParseNestedMeta, parse_macro_input, Expr, FnArg, ItemFn, LitStr, Pat};
+
+/// Declare a benchmark with the decimal measurement and throughput configured.
+///
+/// See the documentation of `criterion_decimal_throughput::decimal_bench` for details.
+#[proc_macro_attribute]
+pub fn decimal_bench(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut config = Config::default();
+    let parser = syn::meta::parser(|meta| config.parse(meta));
+    parse_macro_input!(args with parser);
+    let function = parse_macro_input!(item as ItemFn);
+
+    expand(config, function)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct Config {
+    throughput: Option<(Kind, Expr)>,
+    input: Option<Expr>,
+    group: Option<LitStr>,
+    id: Option<LitStr>,
+}
+
+enum Kind {
+    Bytes,
+    Elements,
+}
+
+impl Config {
+    fn parse(&mut self, meta: ParseNestedMeta<'_>) -> syn::Result<()> {
+        let kind = if meta.path.is_ident("bytes") {
+            Some(Kind::Bytes)
+        } else if meta.path.is_ident("elements") {
+            Some(Kind::Elements)
+        } else {
+            None
+        };
+
+        if let Some(kind) = kind {
+            if self.throughput.is_some() {
+                return Err(
+                    meta.error("throughput is already set, use only one of `bytes` and `elements`")
+                );
+            }
+            let expr = meta.value()?.parse::<LitStr>()?.parse()?;
+            self.throughput = Some((kind, expr));
+        } else if meta.path.is_ident("input") {
+            self.input = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+        } else if meta.path.is_ident("group") {
+            self.group = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("id") {
+            self.id = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("expected `bytes`, `elements`, `input`, `group`, or `id`"));
+        }
+        Ok(())
+    }
+}
+
+fn expand(config: Config, function: ItemFn) -> syn::Result<TokenStream2> {
+    let (kind, throughput) = config.throughput.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &function.sig,
+            "missing throughput, add `bytes = \"...\"` or `elements = \"...\"`",
+        )
+    })?;
+    let variant = match kind {
+        Kind::Bytes => quote!(Bytes),
+        Kind::Elements => quote!(Elements),
+    };
+
+    let vis = &function.vis;
+    let name = &function.sig.ident;
+    let routine = format_ident!("__{}_routine", name);
+    let mut inner = function.clone();
+    inner.sig.ident = routine.clone();
+    inner.vis = syn::Visibility::Inherited;
+
+    let name_str = LitStr::new(&name.to_string(), name.span());
+    let group = config.group.unwrap_or_else(|| name_str.clone());
+    let id = config.id.unwrap_or(name_str);
+    let krate = quote!(::criterion_decimal_throughput);
+
+    let bench = match function.sig.inputs.len() {
+        1 => {
+            if let Some(input) = &config.input {
+                return Err(syn::Error::new_spanned(
+                    input,
+                    "`input` requires the function to take the input as its second argument",
+                ));
+            }
+            quote! {
+                __group.throughput(#krate::__criterion::Throughput::#variant((#throughput) as u64));
+                __group.bench_function(#id, #routine);
+            }
+        }
+        2 => {
+            let input = config.input.ok_or_else(|| {
+                syn::Error::new_spanned(
+                    &function.sig.inputs,
+                    "missing input, add `input = \"...\"` with an expression creating it",
+                )
+            })?;
+            let (pat, ty) = match &function.sig.inputs[1] {
+                FnArg::Typed(arg) => match &*arg.pat {
+                    Pat::Ident(ident) => (&ident.ident, &arg.ty),
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            &arg.pat,
+                            "the input argument has to be a plain identifier",
+                        ))
+                    }
+                },
+                FnArg::Receiver(receiver) => {
+                    return Err(syn::Error::new_spanned(
+                        receiver,
+                        "expected a free function",
+                    ))
+                }
+            };
+            quote! {
+                let __input = #input;
+                let __throughput = {
+                    #[allow(unused_variables)]
+                    let #pat: #ty = &__input;
+                    #krate::__criterion::Throughput::#variant((#throughput) as u64)
+                };
+                __group.throughput(__throughput);
+                __group.bench_with_input(#id, &__input, |b, i| #routine(b, i));
+            }
+        }
+        _ => return Err(syn::Error::new_spanned(
+            &function.sig.inputs,
+            "expected a function taking a `&mut Bencher` and optionally a reference to the input",
+        )),
+    };
+
+    Ok(quote! {
+        #vis fn #name(c: &mut #krate::Criterion) {
+            #inner
+
+            let mut __group = c.benchmark_group(#group);
+            #bench
+            __group.finish();
+        }
+    })
+}
@@ -0,0 +1,198 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::RefCell,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+static ALLOCATED: AtomicU64 = AtomicU64::new(0);
+
+/// Global allocator counting the bytes allocated by the process, delegating to another allocator.
+///
+/// Install it with `#[global_allocator]` in the bench binary and enable
+/// [`DecimalByteMeasurement::with_allocation_stats`](crate::DecimalByteMeasurement::with_allocation_stats)
+/// to report the allocation rate of benchmarks, e.g. `2.0000 GB/s (12.000 MB allocated/s, 0.0060 B allocated/B)`.
+///
+/// Every allocation counts its size, and a reallocation counts by how much it grew the allocation.
+/// Deallocations are not subtracted, so the count is the total allocated, not the memory in use.
+/// Allocations of all threads are counted.
+///
+/// Requires the `alloc_stats` feature.
+///
+/// # Example
+///
+/// ```
+/// use criterion_decimal_throughput::{CountingAllocator, DecimalByteMeasurement};
+/// use std::alloc::System;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: CountingAllocator = CountingAllocator::new(System);
+///
+/// pub fn my_custom_config() -> criterion::Criterion<DecimalByteMeasurement> {
+///     let measurement = DecimalByteMeasurement::new().with_allocation_stats(true);
+///     criterion::Criterion::default().with_measurement(measurement)
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CountingAllocator<A = System> {
+    allocator: A,
+}
+
+impl<A> CountingAllocator<A> {
+    /// Count the allocations made with the given allocator.
+    pub const fn new(allocator: A) -> Self {
+        CountingAllocator { allocator }
+    }
+}
+
+/// Total number of bytes allocated with a [`CountingAllocator`] since the start of the process.
+///
+/// Always zero if no [`CountingAllocator`] is installed.
+pub fn allocated_bytes() -> u64 {
+    ALLOCATED.load(Ordering::Relaxed)
+}
+
+fn count(bytes: usize) {
+    ALLOCATED.fetch_add(bytes as u64, Ordering::Relaxed);
+}
+
+// SAFETY: All calls are delegated to the wrapped allocator unchanged.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        self.allocator.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        self.allocator.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.allocator.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count(new_size.saturating_sub(layout.size()));
+        self.allocator.realloc(ptr, layout, new_size)
+    }
+}
+
+thread_local! {
+    static SAMPLE_START: RefCell<u64> = const { RefCell::new(0) };
+    static SAMPLES: RefCell<Option<AllocatedBytes>> = const { RefCell::new(None) };
+}
+
+/// Bytes allocated in the samples of the current benchmark.
+#[derive(Debug, Clone, Copy)]
+struct AllocatedBytes {
+    total_bytes: u64,
+    total_nanos: f64,
+    reported: bool,
+}
+
+/// Called when a measurement starts. Drops the record of a benchmark that was already reported.
+pub(crate) fn start_sample() {
+    SAMPLES.with(|s| {
+        let mut samples = s.borrow_mut();
+        if matches!(*samples, Some(record) if record.reported) {
+            *samples = None;
+        }
+    });
+    SAMPLE_START.with(|s| *s.borrow_mut() = allocated_bytes());
+}
+
+/// Called when a measurement of `nanos` nanoseconds ends.
+pub(crate) fn end_sample(nanos: f64) {
+    let start = SAMPLE_START.with(|s| *s.borrow());
+    record_sample(allocated_bytes().saturating_sub(start), nanos);
+}
+
+fn record_sample(bytes: u64, nanos: f64) {
+    SAMPLES.with(|s| {
+        let mut samples = s.borrow_mut();
+        let record = samples.get_or_insert(AllocatedBytes {
+            total_bytes: 0,
+            total_nanos: 0.0,
+            reported: false,
+        });
+        record.total_bytes += bytes;
+        record.total_nanos += nanos;
+    })
+}
+
+/// Bytes allocated per second in the benchmark being reported, if it was measured
+/// by the measurement with the given id.
+pub(crate) fn allocation_rate(measurement: u64) -> Option<f64> {
+    if !crate::context::measured_by(measurement) {
+        return None;
+    }
+    SAMPLES.with(|s| {
+        let mut samples = s.borrow_mut();
+        let record = samples.as_mut()?;
+        record.reported = true;
+        if record.total_nanos <= 0.0 {
+            return None;
+        }
+        Some(record.total_bytes as f64 / record.total_nanos * 1e9)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::DecimalByteMeasurement;
+    use criterion::{measurement::ValueFormatter, Throughput};
+
+    #[test]
+    fn counting_allocator_counts_allocations_and_growth() {
+        let allocator = CountingAllocator::new(System);
+        let layout = Layout::from_size_align(100, 8).unwrap();
+        let before = allocated_bytes();
+
+        unsafe {
+            let ptr = allocator.alloc(layout);
+            let ptr = allocator.realloc(ptr, layout, 250);
+            let shrunk = Layout::from_size_align(250, 8).unwrap();
+            let ptr = allocator.realloc(ptr, shrunk, 50);
+            allocator.dealloc(ptr, Layout::from_size_align(50, 8).unwrap());
+        }
+
+        assert_eq!(allocated_bytes() - before, 250);
+    }
+
+    #[test]
+    fn allocation_rate_over_samples() {
+        let measurement = crate::context::new_measurement();
+        crate::context::start_measurement(measurement);
+        record_sample(1_000, 500.0);
+        record_sample(3_000, 1_500.0);
+
+        assert_eq!(allocation_rate(measurement), Some(2e9));
+    }
+
+    #[test]
+    fn format_throughput_with_allocation_stats() {
+        let measurement = DecimalByteMeasurement::new().with_allocation_stats(true);
+        crate::context::start_measurement(measurement.id);
+        record_sample(12_000, 1_000_000.0);
+
+        let result = measurement.format_throughput(&Throughput::Bytes(2_000_000), 1_000_000.0);
+
+        assert_eq!(
+            result,
+            "2.0000 GB/s (12.000 MB allocated/s, 0.0060 B allocated/B)"
+        );
+    }
+
+    #[test]
+    fn allocations_apply_to_their_measurement_only() {
+        let measured = DecimalByteMeasurement::new().with_allocation_stats(true);
+        crate::context::start_measurement(measured.id);
+        record_sample(12_000, 1_000_000.0);
+
+        let other = DecimalByteMeasurement::new().with_allocation_stats(true);
+        let result = other.format_throughput(&Throughput::Bytes(2_000_000), 1_000_000.0);
+
+        assert_eq!(result, "2.0000 GB/s");
+    }
+}
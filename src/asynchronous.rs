@@ -0,0 +1,79 @@
+use crate::{context, DecimalByteMeasurement};
+use criterion::{async_executor::AsyncExecutor, measurement::Measurement, AsyncBencher};
+use std::future::Future;
+
+/// Extension trait for [`criterion::AsyncBencher`] for asynchronous routines that report
+/// how many bytes they processed, e.g. read from a `Stream` or an `AsyncRead`.
+///
+/// The reported throughput uses the average number of bytes over all iterations
+/// instead of a guessed constant, see [measured bytes](crate#measured-bytes) for where it
+/// replaces the configured [`criterion::Throughput::Bytes`].
+///
+/// Requires the `async` feature, and one of the `async_*` features for an executor.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "async_futures")]
+/// # {
+/// use criterion::{async_executor::FuturesExecutor, Throughput};
+/// use criterion_decimal_throughput::{AsyncThroughputExt, Criterion};
+///
+/// async fn read_all() -> Vec<u8> {
+///     vec![0_u8; 1_000] // Read from a stream here...
+/// }
+///
+/// fn example_bench(c: &mut Criterion) {
+///     let mut group = c.benchmark_group("read");
+///     // Typical number of bytes read, replaced by the observed number of bytes.
+///     group.throughput(Throughput::Bytes(1_000));
+///     group.bench_function("stream", |b| {
+///         b.to_async(FuturesExecutor)
+///             .iter_bytes(|| async { read_all().await.len() as u64 })
+///     });
+///     group.finish();
+/// }
+/// # }
+/// ```
+pub trait AsyncThroughputExt {
+    /// Same as [`criterion::AsyncBencher::iter`], recording the number of bytes
+    /// returned by every iteration.
+    fn iter_bytes<R, F>(&mut self, routine: R)
+    where
+        R: FnMut() -> F,
+        F: Future<Output = u64>;
+}
+
+impl<'a, 'b, A: AsyncExecutor, M: Measurement> AsyncThroughputExt
+    for AsyncBencher<'a, 'b, A, DecimalByteMeasurement<M>>
+{
+    fn iter_bytes<R, F>(&mut self, mut routine: R)
+    where
+        R: FnMut() -> F,
+        F: Future<Output = u64>,
+    {
+        self.iter(|| recording(routine()))
+    }
+}
+
+async fn recording<F: Future<Output = u64>>(future: F) {
+    context::record_observed_bytes(future.await);
+}
+
+#[cfg(all(test, feature = "async_futures"))]
+mod test {
+    use super::*;
+    use criterion::{async_executor::FuturesExecutor, measurement::ValueFormatter, Throughput};
+
+    #[test]
+    fn observed_bytes_of_iterations_replace_configured_throughput() {
+        let measurement = DecimalByteMeasurement::new();
+        measurement.start();
+        FuturesExecutor.block_on(recording(async { 1_000_000 }));
+        FuturesExecutor.block_on(recording(async { 3_000_000 }));
+
+        let result = measurement.format_throughput(&Throughput::Bytes(1), 1_000_000.0);
+
+        assert_eq!(result, "2.0000 GB/s");
+    }
+}
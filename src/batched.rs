@@ -0,0 +1,250 @@
+use crate::{context, DecimalByteMeasurement, IntoBenchmarkId};
+use criterion::{measurement::Measurement, BatchSize, Bencher, BenchmarkGroup, Throughput};
+
+/// Extension trait for [`criterion::Bencher`] that counts the bytes of the inputs generated
+/// with [`criterion::Bencher::iter_batched`] and [`criterion::Bencher::iter_batched_ref`].
+///
+/// The reported throughput uses the average size of all inputs generated for the benchmark
+/// instead of a guessed constant, see [measured bytes](crate#measured-bytes) for where it
+/// replaces the configured [`criterion::Throughput::Bytes`]. To have a representative value saved,
+/// set the throughput from a generated input with [`BatchedGroupThroughputExt::bench_batched_bytes`].
+///
+/// # Example
+///
+/// ```
+/// use criterion::{BatchSize, Throughput};
+/// use criterion_decimal_throughput::{BatchedThroughputExt, Criterion};
+///
+/// fn example_bench(c: &mut Criterion) {
+///     let mut group = c.benchmark_group("sort");
+///     // Typical size of an input, replaced by the observed size of the inputs.
+///     group.throughput(Throughput::Bytes(1_000));
+///     group.bench_function("random", |b| {
+///         b.iter_batched_bytes(
+///             || vec![0_u8; 1_000], // Generate a random input here...
+///             |input| input.len() as u64,
+///             |mut input| input.sort(),
+///             BatchSize::SmallInput,
+///         )
+///     });
+///     group.finish();
+/// }
+/// ```
+pub trait BatchedThroughputExt {
+    /// Same as [`criterion::Bencher::iter_batched`], recording `bytes` of every generated input.
+    fn iter_batched_bytes<I, O, S, B, R>(
+        &mut self,
+        setup: S,
+        bytes: B,
+        routine: R,
+        size: BatchSize,
+    ) where
+        S: FnMut() -> I,
+        B: FnMut(&I) -> u64,
+        R: FnMut(I) -> O;
+
+    /// Same as [`criterion::Bencher::iter_batched_ref`], recording `bytes` of every generated input.
+    fn iter_batched_ref_bytes<I, O, S, B, R>(
+        &mut self,
+        setup: S,
+        bytes: B,
+        routine: R,
+        size: BatchSize,
+    ) where
+        S: FnMut() -> I,
+        B: FnMut(&I) -> u64,
+        R: FnMut(&mut I) -> O;
+}
+
+impl<'a, M: Measurement> BatchedThroughputExt for Bencher<'a, DecimalByteMeasurement<M>> {
+    fn iter_batched_bytes<I, O, S, B, R>(&mut self, setup: S, bytes: B, routine: R, size: BatchSize)
+    where
+        S: FnMut() -> I,
+        B: FnMut(&I) -> u64,
+        R: FnMut(I) -> O,
+    {
+        self.iter_batched(recording(setup, bytes), routine, size)
+    }
+
+    fn iter_batched_ref_bytes<I, O, S, B, R>(
+        &mut self,
+        setup: S,
+        bytes: B,
+        routine: R,
+        size: BatchSize,
+    ) where
+        S: FnMut() -> I,
+        B: FnMut(&I) -> u64,
+        R: FnMut(&mut I) -> O,
+    {
+        self.iter_batched_ref(recording(setup, bytes), routine, size)
+    }
+}
+
+/// Extension trait for [`criterion::BenchmarkGroup`] that benchmarks routines on generated inputs
+/// with [`BatchedThroughputExt`], setting the throughput of the group from the inputs.
+///
+/// # Example
+///
+/// ```
+/// use criterion::BatchSize;
+/// use criterion_decimal_throughput::{BatchedGroupThroughputExt, Criterion};
+///
+/// fn example_bench(c: &mut Criterion) {
+///     let mut group = c.benchmark_group("sort");
+///     group.bench_batched_bytes(
+///         "random",
+///         || vec![0_u8; 1_000], // Generate a random input here...
+///         |input| input.len() as u64,
+///         |mut input| input.sort(),
+///         BatchSize::SmallInput,
+///     );
+///     group.finish();
+/// }
+/// ```
+pub trait BatchedGroupThroughputExt {
+    /// Measurement used by the group.
+    type Measurement: Measurement;
+
+    /// Benchmark `routine` on inputs generated by `setup`, as with
+    /// [`BatchedThroughputExt::iter_batched_bytes`].
+    ///
+    /// `setup` is called once before the benchmark, and the `bytes` of that input become the
+    /// [`Throughput::Bytes`] of the group, so that the results Criterion.rs saves have the size
+    /// of an actual input. The reported throughput is still the average over all generated inputs.
+    fn bench_batched_bytes<ID, I, O, S, B, R>(
+        &mut self,
+        id: ID,
+        setup: S,
+        bytes: B,
+        routine: R,
+        size: BatchSize,
+    ) -> &mut Self
+    where
+        ID: IntoBenchmarkId,
+        S: FnMut() -> I,
+        B: FnMut(&I) -> u64,
+        R: FnMut(I) -> O;
+}
+
+impl<'a, M: Measurement> BatchedGroupThroughputExt
+    for BenchmarkGroup<'a, DecimalByteMeasurement<M>>
+{
+    type Measurement = DecimalByteMeasurement<M>;
+
+    fn bench_batched_bytes<ID, I, O, S, B, R>(
+        &mut self,
+        id: ID,
+        mut setup: S,
+        mut bytes: B,
+        mut routine: R,
+        size: BatchSize,
+    ) -> &mut Self
+    where
+        ID: IntoBenchmarkId,
+        S: FnMut() -> I,
+        B: FnMut(&I) -> u64,
+        R: FnMut(I) -> O,
+    {
+        let nominal_bytes = bytes(&setup());
+        self.throughput(Throughput::Bytes(nominal_bytes));
+        id.bench_function(self, |b| {
+            b.iter_batched_bytes(&mut setup, &mut bytes, &mut routine, size)
+        });
+        self
+    }
+}
+
+fn recording<I, S, B>(mut setup: S, mut bytes: B) -> impl FnMut() -> I
+where
+    S: FnMut() -> I,
+    B: FnMut(&I) -> u64,
+{
+    move || {
+        let input = setup();
+        context::record_observed_bytes(bytes(&input));
+        input
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{results, test_criterion};
+    use criterion::measurement::ValueFormatter;
+
+    #[test]
+    fn observed_bytes_replace_configured_throughput() {
+        let measurement = DecimalByteMeasurement::new();
+        let mut sizes = [1_000_000_u64, 3_000_000].into_iter();
+        let mut setup = recording(|| sizes.next().unwrap(), |size| *size);
+        measurement.start();
+        setup();
+        setup();
+
+        let result = measurement.format_throughput(&Throughput::Bytes(1), 1_000_000.0);
+
+        assert_eq!(result, "2.0000 GB/s");
+    }
+
+    #[test]
+    fn observed_bytes_reset_after_report() {
+        let measurement = DecimalByteMeasurement::new();
+        let mut setup = recording(|| 1_000_000_u64, |size| *size);
+        measurement.start();
+        setup();
+
+        measurement.format_throughput(&Throughput::Bytes(1), 1_000_000.0);
+        measurement.start();
+        let result = measurement.format_throughput(&Throughput::Bytes(1_000), 1_000_000.0);
+
+        assert_eq!(result, "1.0000 MB/s");
+    }
+
+    #[test]
+    fn observed_bytes_replace_configured_throughput_when_scaling() {
+        let measurement = DecimalByteMeasurement::new();
+        let mut setup = recording(|| 3_000_000_u64, |size| *size);
+        measurement.start();
+        setup();
+
+        let mut values = [1_000_000.0, 2_000_000.0];
+        let unit = measurement.scale_throughputs(1_000_000.0, &Throughput::Bytes(1), &mut values);
+
+        assert_eq!(unit, "GB/s");
+        assert_eq!(values, [3.0, 1.5]);
+    }
+
+    #[test]
+    fn observed_bytes_apply_to_their_measurement_only() {
+        let measurement = DecimalByteMeasurement::new();
+        let mut setup = recording(|| 3_000_000_u64, |size| *size);
+        measurement.start();
+        setup();
+
+        let other = DecimalByteMeasurement::new();
+        let result = other.format_throughput(&Throughput::Bytes(1_000), 1_000_000.0);
+
+        assert_eq!(result, "1.0000 MB/s");
+    }
+
+    #[test]
+    fn bench_batched_bytes_sets_throughput_from_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut c = test_criterion(dir.path());
+        let mut group = c.benchmark_group("test");
+
+        group.bench_batched_bytes(
+            "sort",
+            || vec![3_u8, 2, 1],
+            |input| input.len() as u64,
+            |mut input| input.sort(),
+            BatchSize::SmallInput,
+        );
+        group.finish();
+
+        let results = results::load_results(dir.path()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].throughput, Some(Throughput::Bytes(3)));
+    }
+}
@@ -0,0 +1,180 @@
+//! Print the throughput of results Criterion.rs saved in its output directory in decimal units.
+//!
+//! This works with results of any previous run, including ones made with the default
+//! measurement of Criterion.rs, since the saved estimates are always times in nanoseconds.
+//!
+//! Install with `cargo install criterion-decimal-throughput --features cli`
+//! and run `cdt-report --help` for usage.
+
+use criterion_decimal_throughput::{
+    report,
+    results::{self, BenchmarkResult},
+};
+use std::{env, io, path::PathBuf, process::ExitCode};
+
+const USAGE: &str = "\
+Print throughput of saved Criterion.rs results in decimal units.
+
+Usage: cdt-report [OPTIONS]
+
+Options:
+  -d, --output-directory <PATH>  Directory with the results [default: target/criterion]
+  -g, --group <GROUP>            Only report benchmarks of the given group
+  -b, --baseline <NAME>          Compare the latest results to the given saved baseline
+  -f, --format <FORMAT>          Output format: table, markdown, or csv [default: table]
+  -h, --help                     Print this help";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Table,
+    Markdown,
+    Csv,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Options {
+    output_directory: Option<PathBuf>,
+    group: Option<String>,
+    baseline: Option<String>,
+    format: Format,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Command {
+    Report(Options),
+    Help,
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(Command::Report(options)) => options,
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut options = Options {
+        output_directory: None,
+        group: None,
+        baseline: None,
+        format: Format::Table,
+    };
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value of {}", arg))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-d" | "--output-directory" => options.output_directory = Some(value()?.into()),
+            "-g" | "--group" => options.group = Some(value()?),
+            "-b" | "--baseline" => options.baseline = Some(value()?),
+            "-f" | "--format" => {
+                options.format = match value()?.as_str() {
+                    "table" => Format::Table,
+                    "markdown" => Format::Markdown,
+                    "csv" => Format::Csv,
+                    other => return Err(format!("unknown format '{}'", other)),
+                }
+            }
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    if options.baseline.is_some() && options.format != Format::Table {
+        return Err("--baseline is only supported with the table format".to_owned());
+    }
+
+    Ok(Command::Report(options))
+}
+
+fn run(options: &Options) -> io::Result<()> {
+    let output_directory = options
+        .output_directory
+        .clone()
+        .unwrap_or_else(results::default_output_directory);
+    let in_group = |result: &BenchmarkResult| {
+        options
+            .group
+            .as_ref()
+            .is_none_or(|group| &result.group_id == group)
+    };
+    let latest: Vec<_> = results::load_results(&output_directory)?
+        .into_iter()
+        .filter(in_group)
+        .collect();
+
+    match (options.format, &options.baseline) {
+        (Format::Table, Some(baseline)) => {
+            let baseline: Vec<_> = results::load_baseline(&output_directory, baseline)?
+                .into_iter()
+                .filter(in_group)
+                .collect();
+            for change in report::compare_throughputs(&baseline, &latest) {
+                println!("{}", change);
+            }
+        }
+        (Format::Table, None) => print!("{}", report::throughput_table(&latest)),
+        (Format::Markdown, _) => print!("{}", report::markdown_table(&latest)),
+        (Format::Csv, _) => report::write_csv(&latest, io::stdout().lock())?,
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_options() {
+        let command = parse(&["-d", "out", "--group", "parse", "-b", "main"]).unwrap();
+
+        assert_eq!(
+            command,
+            Command::Report(Options {
+                output_directory: Some("out".into()),
+                group: Some("parse".to_owned()),
+                baseline: Some("main".to_owned()),
+                format: Format::Table,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert_eq!(
+            parse(&["--format", "xml"]),
+            Err("unknown format 'xml'".to_owned())
+        );
+        assert_eq!(
+            parse(&["--group"]),
+            Err("missing value of --group".to_owned())
+        );
+        assert_eq!(
+            parse(&["-f", "csv", "-b", "main"]),
+            Err("--baseline is only supported with the table format".to_owned())
+        );
+    }
+}
@@ -0,0 +1,480 @@
+use crate::{
+    format::{self, Align},
+    results::{self, BenchmarkResult},
+    units::Unit,
+    DecimalByteMeasurement,
+};
+use criterion::measurement::{Measurement, ValueFormatter};
+use std::{
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Concise summary of a group with one line per benchmark, showing both its time
+/// and throughput, e.g. `parse: 412.00 µs (2.4300 GB/s)`.
+///
+/// Like [`ThroughputGate`](crate::ThroughputGate), the report reads the results Criterion.rs saved
+/// in its output directory, so it has to be printed after the group finishes. Only results saved after
+/// the report was constructed are included.
+///
+/// Criterion.rs always prints its own report for every benchmark, which cannot be turned off
+/// from the bench code. Passing `--output-format bencher` to the bench binary makes it the shortest.
+///
+/// For groups with many benchmarks, [`CompactReport::table`] gives an aligned table
+/// with the change of throughput compared to the previous run instead,
+/// and [`CompactReport::percentiles`] shows the throughput of the slow tail of the samples.
+///
+/// # Example
+///
+/// ```
+/// use criterion::Throughput;
+/// use criterion_decimal_throughput::{CompactReport, Criterion};
+///
+/// fn example_bench(c: &mut Criterion) {
+///     let report = CompactReport::new();
+///
+///     let mut group = c.benchmark_group("example_name");
+///     group.throughput(Throughput::Bytes(1_000_000));
+///     // Add your benchmarks to the group here...
+///     group.finish();
+///
+///     report.print("example_name").unwrap();
+/// }
+/// ```
+pub struct CompactReport {
+    output_directory: PathBuf,
+    formatter: Box<dyn ValueFormatter>,
+    color: bool,
+    created_at: SystemTime,
+}
+
+impl Default for CompactReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompactReport {
+    /// Create a new report formatting with the default options of [`DecimalByteMeasurement`] and reading results
+    /// from the [default output directory](results::default_output_directory).
+    pub fn new() -> Self {
+        CompactReport {
+            output_directory: results::default_output_directory(),
+            formatter: Box::new(format::Plain),
+            color: io::stdout().is_terminal(),
+            created_at: SystemTime::now(),
+        }
+    }
+
+    /// Read the results from the given directory instead of the default one.
+    ///
+    /// Use this if you changed it with [`criterion::Criterion::output_directory`].
+    pub fn output_directory<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.output_directory = path.as_ref().to_owned();
+        self
+    }
+
+    /// Format times and throughput with the given measurement, e.g. the one the group was run with.
+    pub fn with_measurement<M: Measurement + 'static>(
+        mut self,
+        measurement: DecimalByteMeasurement<M>,
+    ) -> Self {
+        self.formatter = Box::new(format::Detached(measurement));
+        self
+    }
+
+    /// Color changes in the [table](CompactReport::table) green or red.
+    ///
+    /// By default, colors are used if the standard output is a terminal.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Lines of the summary of the group with the given name, sorted by the id of the benchmark.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the results could not be read.
+    pub fn lines(&self, group: &str) -> io::Result<Vec<String>> {
+        let results = results::load_results(&self.output_directory)?;
+
+        Ok(results
+            .iter()
+            .filter(|r| r.group_id == group && r.is_fresh(self.created_at))
+            .map(|r| self.line(r))
+            .collect())
+    }
+
+    /// Print the summary of the group with the given name to the standard output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the results could not be read.
+    pub fn print(&self, group: &str) -> io::Result<()> {
+        for line in self.lines(group)? {
+            println!("{}", line);
+        }
+        Ok(())
+    }
+
+    /// Aligned table of the throughput of every benchmark in the group with the given name,
+    /// sorted by the id of the benchmark.
+    ///
+    /// The last column is the change of throughput compared to the previous run, if Criterion.rs
+    /// compared them. With [colors](CompactReport::with_color), it is green if the throughput
+    /// improved and red if it regressed with the configured confidence, e.g.:
+    ///
+    /// ```text
+    /// Benchmark   Throughput   Change
+    /// rows       2.4272 GB/s  +11.11%
+    /// setup                -        -
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the results could not be read.
+    pub fn table(&self, group: &str) -> io::Result<String> {
+        let results = results::load_results(&self.output_directory)?;
+        let rows: Vec<_> = results
+            .iter()
+            .filter(|r| r.group_id == group && r.is_fresh(self.created_at))
+            .map(|r| self.row(r))
+            .collect();
+
+        let mut cells = vec![["Benchmark", "Throughput", "Change"].map(str::to_owned)];
+        let mut directions = vec![None];
+        for row in rows {
+            cells.push(row.cells);
+            directions.push(row.direction);
+        }
+        format::align_columns(&mut cells, [Align::Left, Align::Right, Align::Right]);
+
+        if self.color {
+            for (row, direction) in cells.iter_mut().zip(directions) {
+                if let Some(improved) = direction {
+                    let color = if improved { GREEN } else { RED };
+                    row[2] = format!("{}{}{}", color, row[2], RESET);
+                }
+            }
+        }
+
+        Ok(format::join_rows(&cells))
+    }
+
+    /// Print the [table](CompactReport::table) of the group with the given name to the standard output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the results could not be read.
+    pub fn print_table(&self, group: &str) -> io::Result<()> {
+        print!("{}", self.table(group)?);
+        Ok(())
+    }
+
+    /// Lines ranking the benchmarks of the group with the given name by their throughput,
+    /// from the fastest to the slowest, for groups comparing implementations on the same input.
+    ///
+    /// Each line shows how many times faster the benchmark is than the slowest one, e.g.:
+    ///
+    /// ```text
+    /// simd: 3.1000 GB/s (4.2x scalar)
+    /// scalar: 738.10 MB/s (slowest)
+    /// ```
+    ///
+    /// Benchmarks without throughput are skipped. Throughput in bytes and in elements
+    /// is not comparable, so benchmarks with each of them are ranked separately, bytes first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the results could not be read.
+    pub fn ranking(&self, group: &str) -> io::Result<Vec<String>> {
+        let results = results::load_results(&self.output_directory)?;
+        let mut ranked: Vec<_> = results
+            .iter()
+            .filter(|r| r.group_id == group && r.is_fresh(self.created_at))
+            .filter_map(|r| {
+                let unit = Unit::of(r.throughput.as_ref()?);
+                Some((unit, r.typical_units_per_second()?, r))
+            })
+            .collect();
+        ranked.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.total_cmp(&a.1)));
+
+        let mut lines = vec![];
+        for unit in [Unit::Byte, Unit::Elem] {
+            let entries: Vec<_> = ranked.iter().filter(|(u, ..)| *u == unit).collect();
+            let (_, slowest_rate, slowest) = match entries.last() {
+                Some(slowest) => **slowest,
+                None => continue,
+            };
+            for (_, rate, result) in &entries {
+                let comparison = if result.full_id == slowest.full_id {
+                    "slowest".to_owned()
+                } else {
+                    format!("{:.1}x {}", rate / slowest_rate, name(slowest))
+                };
+                let throughput = self.throughput(result);
+                lines.push(format!("{}: {} ({})", name(result), throughput, comparison));
+            }
+        }
+
+        Ok(lines)
+    }
+
+    /// Print the [ranking](CompactReport::ranking) of the group with the given name to the standard output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the results could not be read.
+    pub fn print_ranking(&self, group: &str) -> io::Result<()> {
+        for line in self.ranking(group)? {
+            println!("{}", line);
+        }
+        Ok(())
+    }
+
+    /// Lines with the throughput at the 50th, 95th, and 99th percentile of the time of an iteration
+    /// of every benchmark with throughput in the group with the given name, sorted by the id of the benchmark.
+    ///
+    /// The throughput at a percentile is computed from the time at that percentile, so e.g. 5%
+    /// of the samples were slower than the throughput at p95, which shows the slow tail, e.g.:
+    ///
+    /// ```text
+    /// rows: p50 2.4272 GB/s, p95 2.1053 GB/s, p99 1.9802 GB/s
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the results or their samples could not be read.
+    pub fn percentiles(&self, group: &str) -> io::Result<Vec<String>> {
+        let results = results::load_results(&self.output_directory)?;
+        let mut lines = vec![];
+
+        for result in results
+            .iter()
+            .filter(|r| r.group_id == group && r.is_fresh(self.created_at))
+        {
+            let throughput = match &result.throughput {
+                Some(throughput) => throughput,
+                None => continue,
+            };
+            let sample = result.load_sample()?;
+            let percentiles: Vec<_> = PERCENTILES
+                .iter()
+                .filter_map(|p| {
+                    let nanos = sample.percentile(*p)?;
+                    let formatted = self.formatter.format_throughput(throughput, nanos);
+                    Some(format!("p{} {}", p, formatted.trim_start()))
+                })
+                .collect();
+
+            if !percentiles.is_empty() {
+                lines.push(format!("{}: {}", name(result), percentiles.join(", ")));
+            }
+        }
+
+        Ok(lines)
+    }
+
+    /// Print the [percentiles](CompactReport::percentiles) of the group with the given name
+    /// to the standard output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the results or their samples could not be read.
+    pub fn print_percentiles(&self, group: &str) -> io::Result<()> {
+        for line in self.percentiles(group)? {
+            println!("{}", line);
+        }
+        Ok(())
+    }
+
+    /// Formatted typical throughput of the benchmark, or `-` if it has none.
+    fn throughput(&self, result: &BenchmarkResult) -> String {
+        let nanos = result.estimates.typical().point_estimate;
+        match &result.throughput {
+            Some(throughput) => self
+                .formatter
+                .format_throughput(throughput, nanos)
+                .trim_start()
+                .to_owned(),
+            None => "-".to_owned(),
+        }
+    }
+
+    fn row(&self, result: &BenchmarkResult) -> Row {
+        let throughput = self.throughput(result);
+        let (change, direction) = match (&result.change, &result.throughput) {
+            (Some(change), Some(_)) => {
+                let time = &change.mean;
+                // The change is of the time of an iteration, so throughput changes inversely.
+                let change = 1.0 / (1.0 + time.point_estimate) - 1.0;
+                let direction = if time.confidence_interval.upper_bound < 0.0 {
+                    Some(true)
+                } else if time.confidence_interval.lower_bound > 0.0 {
+                    Some(false)
+                } else {
+                    None
+                };
+                (format!("{:+.2}%", change * 100.0), direction)
+            }
+            _ => ("-".to_owned(), None),
+        };
+
+        Row {
+            cells: [name(result).to_owned(), throughput, change],
+            direction,
+        }
+    }
+
+    fn line(&self, result: &BenchmarkResult) -> String {
+        let nanos = result.estimates.typical().point_estimate;
+        let time = self.formatter.format_value(nanos);
+
+        match &result.throughput {
+            Some(_) => format!(
+                "{}: {} ({})",
+                name(result),
+                time.trim_start(),
+                self.throughput(result)
+            ),
+            None => format!("{}: {}", name(result), time.trim_start()),
+        }
+    }
+}
+
+/// Percentiles shown by [`CompactReport::percentiles`].
+const PERCENTILES: [f64; 3] = [50.0, 95.0, 99.0];
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// Row of [`CompactReport::table`], with the direction of the change if it is significant.
+struct Row {
+    cells: [String; 3],
+    direction: Option<bool>,
+}
+
+/// Id of the benchmark within its group.
+pub(crate) fn name(result: &BenchmarkResult) -> &str {
+    result
+        .full_id
+        .strip_prefix(&result.group_id)
+        .and_then(|id| id.strip_prefix('/'))
+        .unwrap_or(&result.full_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::results::test::{save_change, save_result, save_sample};
+    use criterion::Throughput;
+
+    #[test]
+    fn lines_show_time_and_throughput() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = CompactReport::new().output_directory(dir.path());
+        save_result(
+            dir.path(),
+            "new",
+            "parse/rows",
+            Some(Throughput::Bytes(1_000_000)),
+            412_000.0,
+        );
+        save_result(dir.path(), "new", "parse/setup", None, 1_500.0);
+        save_result(
+            dir.path(),
+            "new",
+            "other/rows",
+            Some(Throughput::Bytes(1)),
+            1.0,
+        );
+
+        let lines = report.lines("parse").unwrap();
+
+        assert_eq!(lines, ["rows: 412.00 µs (2.4272 GB/s)", "setup: 1.5000 µs"]);
+    }
+
+    #[test]
+    fn table_aligns_columns_and_colors_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = CompactReport::new()
+            .output_directory(dir.path())
+            .with_color(true);
+        save_result(
+            dir.path(),
+            "new",
+            "parse/rows",
+            Some(Throughput::Bytes(1_000_000)),
+            412_000.0,
+        );
+        save_change(dir.path(), "parse/rows", -0.1);
+        save_result(dir.path(), "new", "parse/setup", None, 1_500.0);
+
+        let table = report.table("parse").unwrap();
+
+        assert_eq!(
+            table,
+            "Benchmark   Throughput   Change\n\
+             rows       2.4272 GB/s  \x1b[32m+11.11%\x1b[0m\n\
+             setup                -        -\n"
+        );
+    }
+
+    #[test]
+    fn ranking_compares_to_slowest() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = CompactReport::new().output_directory(dir.path());
+        let bytes = Some(Throughput::Bytes(1_000_000));
+        save_result(dir.path(), "new", "hash/scalar", bytes.clone(), 1_000_000.0);
+        save_result(dir.path(), "new", "hash/simd", bytes.clone(), 250_000.0);
+        save_result(dir.path(), "new", "hash/table", bytes, 500_000.0);
+        save_result(
+            dir.path(),
+            "new",
+            "hash/keys",
+            Some(Throughput::Elements(1_000)),
+            1_000.0,
+        );
+        save_result(dir.path(), "new", "hash/setup", None, 1_000.0);
+
+        let ranking = report.ranking("hash").unwrap();
+
+        assert_eq!(
+            ranking,
+            [
+                "simd: 4.0000 GB/s (4.0x scalar)",
+                "table: 2.0000 GB/s (2.0x scalar)",
+                "scalar: 1.0000 GB/s (slowest)",
+                "keys: 1.0000 Gelem/s (slowest)",
+            ]
+        );
+    }
+
+    #[test]
+    fn percentiles_show_throughput_of_slow_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = CompactReport::new().output_directory(dir.path());
+        save_result(
+            dir.path(),
+            "new",
+            "parse/rows",
+            Some(Throughput::Bytes(1_000)),
+            1_000.0,
+        );
+        let times: Vec<_> = (1..=101)
+            .map(|i| 1_000.0 + f64::from(i - 1) * 10.0)
+            .collect();
+        save_sample(dir.path(), "new", "parse/rows", &[1.0; 101], &times);
+        save_result(dir.path(), "new", "parse/setup", None, 1_000.0);
+
+        let lines = report.percentiles("parse").unwrap();
+
+        assert_eq!(
+            lines,
+            ["rows: p50 666.67 MB/s, p95 512.82 MB/s, p99 502.51 MB/s"]
+        );
+    }
+}
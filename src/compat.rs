@@ -0,0 +1,185 @@
+//! Implementations of the measurement traits of newer versions of Criterion.rs.
+//!
+//! The [`Measurement`](criterion::measurement::Measurement) and
+//! [`ValueFormatter`](criterion::measurement::ValueFormatter) traits did not change between the
+//! versions, except for the additional [`Throughput`] variant for decimal bytes, so the implementations
+//! convert the throughput and delegate to the ones for the version this crate depends on.
+//! They exist for a [`DecimalByteMeasurement`] of any time measurement of that version.
+//!
+//! Only the measurement traits are implemented. The extension traits, e.g.
+//! [`GroupThroughputExt`](crate::GroupThroughputExt), and the [`ThroughputGroup`](crate::ThroughputGroup)
+//! take the groups and benchers of Criterion.rs 0.3, so they cannot be used with the newer versions.
+//!
+//! Criterion.rs 0.6 and later are not supported. They add [`Throughput`] variants with more
+//! than one count, e.g. `ElementsAndBytes`, that `convert!` would have to map to one of the two,
+//! and their measurement traits cannot be implemented without depending on them.
+
+use crate::DecimalByteMeasurement;
+use criterion::{
+    measurement::{Measurement, ValueFormatter},
+    Throughput,
+};
+
+macro_rules! impl_criterion_compat {
+    ($criterion:ident) => {
+        impl<M: Measurement> $criterion::measurement::Measurement for DecimalByteMeasurement<M> {
+            type Intermediate = M::Intermediate;
+
+            type Value = M::Value;
+
+            fn start(&self) -> Self::Intermediate {
+                Measurement::start(self)
+            }
+
+            fn end(&self, i: Self::Intermediate) -> Self::Value {
+                Measurement::end(self, i)
+            }
+
+            fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+                Measurement::add(self, v1, v2)
+            }
+
+            fn zero(&self) -> Self::Value {
+                Measurement::zero(self)
+            }
+
+            fn to_f64(&self, value: &Self::Value) -> f64 {
+                Measurement::to_f64(self, value)
+            }
+
+            fn formatter(&self) -> &dyn $criterion::measurement::ValueFormatter {
+                self
+            }
+        }
+
+        impl<M: Measurement> $criterion::measurement::ValueFormatter for DecimalByteMeasurement<M> {
+            fn format_value(&self, value: f64) -> String {
+                ValueFormatter::format_value(self, value)
+            }
+
+            fn format_throughput(&self, throughput: &$criterion::Throughput, value: f64) -> String {
+                ValueFormatter::format_throughput(self, &convert!($criterion, throughput), value)
+            }
+
+            fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+                ValueFormatter::scale_values(self, typical_value, values)
+            }
+
+            fn scale_throughputs(
+                &self,
+                typical_value: f64,
+                throughput: &$criterion::Throughput,
+                values: &mut [f64],
+            ) -> &'static str {
+                ValueFormatter::scale_throughputs(
+                    self,
+                    typical_value,
+                    &convert!($criterion, throughput),
+                    values,
+                )
+            }
+
+            fn scale_for_machines(&self, values: &mut [f64]) -> &'static str {
+                ValueFormatter::scale_for_machines(self, values)
+            }
+        }
+    };
+}
+
+/// Convert the throughput of the given version of Criterion.rs, treating all bytes as decimal.
+macro_rules! convert {
+    ($criterion:ident, $throughput:expr) => {
+        match *$throughput {
+            $criterion::Throughput::Bytes(bytes) | $criterion::Throughput::BytesDecimal(bytes) => {
+                Throughput::Bytes(bytes)
+            }
+            $criterion::Throughput::Elements(elements) => Throughput::Elements(elements),
+        }
+    };
+}
+
+#[cfg(feature = "criterion04")]
+impl_criterion_compat!(criterion04);
+#[cfg(feature = "criterion05")]
+impl_criterion_compat!(criterion05);
+
+#[cfg(test)]
+mod test {
+    use crate::DecimalByteMeasurement;
+
+    /// Time measurement of Criterion.rs 0.3 counting ticks.
+    #[cfg(feature = "criterion05")]
+    struct Ticks;
+
+    #[cfg(feature = "criterion05")]
+    impl criterion::measurement::Measurement for Ticks {
+        type Intermediate = u64;
+
+        type Value = u64;
+
+        fn start(&self) -> Self::Intermediate {
+            0
+        }
+
+        fn end(&self, i: Self::Intermediate) -> Self::Value {
+            i + 1
+        }
+
+        fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+            v1 + v2
+        }
+
+        fn zero(&self) -> Self::Value {
+            0
+        }
+
+        fn to_f64(&self, value: &Self::Value) -> f64 {
+            *value as f64
+        }
+
+        fn formatter(&self) -> &dyn criterion::measurement::ValueFormatter {
+            &crate::format::Plain
+        }
+    }
+
+    #[cfg(feature = "criterion04")]
+    #[test]
+    fn criterion04_formats_decimal_throughput() {
+        use criterion04::{measurement::ValueFormatter, Throughput};
+        let measurement = DecimalByteMeasurement::new();
+        let formatter: &dyn ValueFormatter = &measurement;
+
+        let bytes = formatter.format_throughput(&Throughput::Bytes(2_000), 1_000.0);
+        let decimal = formatter.format_throughput(&Throughput::BytesDecimal(2_000), 1_000.0);
+
+        assert_eq!(bytes, "2.0000 GB/s");
+        assert_eq!(decimal, "2.0000 GB/s");
+    }
+
+    #[cfg(feature = "criterion05")]
+    #[test]
+    fn criterion05_scales_throughputs() {
+        use criterion05::{measurement::ValueFormatter, Throughput};
+        let measurement = DecimalByteMeasurement::new();
+        let formatter: &dyn ValueFormatter = &measurement;
+        let mut values = [1_000.0];
+
+        let unit = formatter.scale_throughputs(1_000.0, &Throughput::Elements(2_000), &mut values);
+
+        assert_eq!(unit, "Gelem/s");
+        assert_eq!(values, [2.0]);
+    }
+
+    #[cfg(feature = "criterion05")]
+    #[test]
+    fn criterion05_measures_with_time_measurement() {
+        use criterion05::measurement::Measurement;
+        let measurement = DecimalByteMeasurement::from_time_measurement(Ticks);
+
+        let value = measurement.end(measurement.start());
+        let total = measurement.add(&value, &value);
+
+        assert_eq!(measurement.to_f64(&total), 2.0);
+        assert_eq!(measurement.zero(), 0);
+    }
+}
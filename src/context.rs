@@ -0,0 +1,552 @@
+//! Per-thread state shared between the group extensions and the formatter.
+//!
+//! Criterion.rs does not give the [`ValueFormatter`](criterion::measurement::ValueFormatter)
+//! any information about the benchmark being reported other than its [`Throughput`],
+//! and the [`BenchmarkGroup`](criterion::BenchmarkGroup) does not expose its measurement,
+//! so extensions register additional information here for the formatter to pick up.
+//! Benchmarks are run and reported on the thread that created the group, so a thread-local suffices.
+//! State of a group is only registered while the group runs a benchmark, so that it cannot
+//! leak into other groups.
+
+use crate::{
+    counter,
+    units::{self, DecimalUnitSystem, Multiple, Unit},
+    CombinedThroughput, DirectionalThroughput,
+};
+use criterion::Throughput;
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    mem,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+thread_local! {
+    static SCOPE: RefCell<Option<GroupScope>> = const { RefCell::new(None) };
+    static OBSERVED: RefCell<Option<ObservedBytes>> = const { RefCell::new(None) };
+    static COUNTED: RefCell<Option<CountedBytes>> = const { RefCell::new(None) };
+    static REPORTED: RefCell<Reported> = const { RefCell::new(Reported { values: false, throughput: false }) };
+    static BENCHMARK_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+    static MEASURED_BY: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+static MEASUREMENTS: AtomicU64 = AtomicU64::new(0);
+
+/// Bytes of the inputs generated by the batched bencher extensions for the current benchmark.
+#[derive(Debug, Clone, Copy)]
+struct ObservedBytes {
+    total_bytes: u128,
+    inputs: u64,
+    reported: bool,
+}
+
+/// Bytes added to the [`counter`](crate::counter) in the samples of the current benchmark.
+#[derive(Debug, Clone, Copy)]
+struct CountedBytes {
+    total_bytes: u128,
+    iterations: u64,
+    reported: bool,
+}
+
+/// What the formatter was asked to format since the last measurement started.
+#[derive(Debug, Clone, Copy)]
+struct Reported {
+    values: bool,
+    throughput: bool,
+}
+
+/// Additional information about the throughput of a [`ThroughputGroup`](crate::ThroughputGroup)
+/// that the formatter needs to display it.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Decoration {
+    /// Element throughput to display next to the byte throughput.
+    Combined(CombinedThroughput),
+    /// Written bytes to display next to the read ones.
+    Directional(DirectionalThroughput),
+    /// The throughput is the aggregate over the given number of threads.
+    Aggregate(Throughput, u64),
+    /// The throughput stands for the given fractional number of units.
+    Fractional(Throughput, f64),
+    /// The throughput is a count of a [`ThroughputKind`](crate::units::ThroughputKind)
+    /// displayed in the given units.
+    Kind(Throughput, DecimalUnitSystem),
+}
+
+/// State of a [`ThroughputGroup`](crate::ThroughputGroup) visible to the formatter
+/// while the group runs a benchmark.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GroupScope {
+    pub(crate) decoration: Option<Decoration>,
+    /// Throughput reported by the benchmarks of the group, if its units are consistent.
+    pub(crate) consistent_units: Option<ConsistentUnits>,
+}
+
+/// Throughput reported by the benchmarks of a group with consistent units and its summary.
+///
+/// The multiple of a unit can only be chosen from the group-wide typical value once all benchmarks
+/// of the group were reported, so the summary is formatted again after each report.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConsistentUnits {
+    benchmarks: Vec<ConsistentBenchmark>,
+    /// Multiples used for each unit while the summary is formatted.
+    multiples: Option<HashMap<Unit, Multiple>>,
+    /// Lines of the summary, each with the throughput of a benchmark in the order they ran.
+    pub(crate) summary: Vec<String>,
+}
+
+/// Times of a benchmark formatted for a group with consistent units.
+#[derive(Debug, Clone)]
+struct ConsistentBenchmark {
+    id: String,
+    throughput: Throughput,
+    decoration: Option<Decoration>,
+    nanos: Vec<f64>,
+}
+
+impl ConsistentBenchmark {
+    /// Median of the times formatted for the benchmark, e.g. its point estimate
+    /// between the bounds of its confidence interval.
+    fn typical_nanos(&self) -> f64 {
+        let mut nanos = self.nanos.clone();
+        nanos.sort_by(f64::total_cmp);
+        nanos[(nanos.len() - 1) / 2]
+    }
+}
+
+/// Run `f` with the state of the group known to the formatter, restoring the previous one afterwards.
+///
+/// Changes to the state made by the formatter while `f` runs are written back to `scope`.
+pub(crate) fn with_group_scope<T>(scope: &mut GroupScope, f: impl FnOnce() -> T) -> T {
+    let previous = SCOPE.with(|s| s.replace(Some(mem::take(scope))));
+    let result = f();
+    *scope = SCOPE
+        .with(|s| s.replace(previous))
+        .expect("scope of the group must be set while it runs");
+    result
+}
+
+fn decoration() -> Option<Decoration> {
+    SCOPE.with(|s| {
+        s.borrow()
+            .as_ref()
+            .and_then(|scope| scope.decoration.clone())
+    })
+}
+
+/// Get the secondary throughput to display alongside `throughput`, if one was registered.
+pub(crate) fn secondary_throughput(throughput: &Throughput) -> Option<Throughput> {
+    match (decoration()?, throughput) {
+        (Decoration::Combined(combined), Throughput::Bytes(bytes)) if combined.bytes == *bytes => {
+            Some(Throughput::Elements(combined.elements))
+        }
+        _ => None,
+    }
+}
+
+/// Get the written throughput to display alongside `throughput` as the read one, if one was registered.
+pub(crate) fn write_throughput(throughput: &Throughput) -> Option<Throughput> {
+    match (decoration()?, throughput) {
+        (Decoration::Directional(directional), Throughput::Bytes(bytes))
+            if directional.read == *bytes =>
+        {
+            Some(Throughput::Bytes(directional.write))
+        }
+        _ => None,
+    }
+}
+
+/// Get the number of threads `throughput` is the aggregate over, if it was registered.
+pub(crate) fn aggregate_threads(throughput: &Throughput) -> Option<u64> {
+    match decoration()? {
+        Decoration::Aggregate(aggregate, threads) if aggregate == *throughput => Some(threads),
+        _ => None,
+    }
+}
+
+/// Get the factor to multiply times by so that `throughput` has the rate of the fractional
+/// number of units it stands for, if it was registered.
+pub(crate) fn fractional_time_factor(throughput: &Throughput) -> Option<f64> {
+    match decoration()? {
+        Decoration::Fractional(placeholder, units) if placeholder == *throughput => {
+            Some(crate::units::total_units(&placeholder) / units)
+        }
+        _ => None,
+    }
+}
+
+/// Get the units of the kind `throughput` is a count of, if it was registered.
+pub(crate) fn kind_units(throughput: &Throughput) -> Option<DecimalUnitSystem> {
+    match decoration()? {
+        Decoration::Kind(kind, units) if kind == *throughput => Some(units),
+        _ => None,
+    }
+}
+
+/// Get the [`Multiple`] to use for `throughput`, given the one selected for the value at hand.
+///
+/// While the summary of a group with consistent units is formatted, the first call for a unit
+/// decides the [`Multiple`] for all further calls for it.
+pub(crate) fn consistent_multiple(throughput: &Throughput, selected: Multiple) -> Multiple {
+    SCOPE.with(|s| {
+        let mut scope = s.borrow_mut();
+        match scope
+            .as_mut()
+            .and_then(|scope| scope.consistent_units.as_mut())
+            .and_then(|units| units.multiples.as_mut())
+        {
+            Some(multiples) => *multiples.entry(Unit::of(throughput)).or_insert(selected),
+            None => selected,
+        }
+    })
+}
+
+/// Record the time formatted for `throughput` of the benchmark being run, if its group has
+/// consistent units and its id is known.
+///
+/// Returns whether it was recorded, in which case the summary of the group needs updating.
+pub(crate) fn record_consistent_units(throughput: &Throughput, nanos: f64) -> bool {
+    let Some(id) = BENCHMARK_ID.with(|i| i.borrow().clone()) else {
+        return false;
+    };
+    SCOPE.with(|s| {
+        let mut scope = s.borrow_mut();
+        let Some(scope) = scope.as_mut() else {
+            return false;
+        };
+        let decoration = scope.decoration.clone();
+        let Some(units) = scope
+            .consistent_units
+            .as_mut()
+            .filter(|units| units.multiples.is_none())
+        else {
+            return false;
+        };
+        match units.benchmarks.iter_mut().find(|b| b.id == id) {
+            Some(benchmark) if benchmark.nanos.contains(&nanos) => return false,
+            Some(benchmark) => benchmark.nanos.push(nanos),
+            None => units.benchmarks.push(ConsistentBenchmark {
+                id,
+                throughput: throughput.clone(),
+                decoration,
+                nanos: vec![nanos],
+            }),
+        }
+        true
+    })
+}
+
+/// Format the summary of the group with consistent units being run, if it has any.
+///
+/// `format` formats the throughput of a benchmark in the given times in the same unit.
+/// For each unit the benchmark with the median typical rate of the ones reported in it
+/// is formatted first, so that it decides the multiple regardless of the order of the benchmarks.
+pub(crate) fn summarize_consistent_units(format: impl Fn(&Throughput, &[f64]) -> Vec<String>) {
+    let benchmarks = SCOPE.with(|s| {
+        let mut scope = s.borrow_mut();
+        let units = scope.as_mut()?.consistent_units.as_mut()?;
+        units.multiples = Some(HashMap::new());
+        Some(units.benchmarks.clone())
+    });
+    let Some(benchmarks) = benchmarks else {
+        return;
+    };
+    let id = BENCHMARK_ID.with(|i| i.take());
+    let decoration = SCOPE.with(|s| s.borrow_mut().as_mut()?.decoration.take());
+    let format_benchmark = |benchmark: &ConsistentBenchmark, nanos: &[f64]| {
+        BENCHMARK_ID.with(|i| *i.borrow_mut() = Some(benchmark.id.clone()));
+        SCOPE.with(|s| {
+            if let Some(scope) = s.borrow_mut().as_mut() {
+                scope.decoration = benchmark.decoration.clone();
+            }
+        });
+        format(&benchmark.throughput, nanos)
+    };
+
+    let mut by_rate: Vec<_> = benchmarks.iter().collect();
+    by_rate.sort_by(|a, b| {
+        let rate = |b: &ConsistentBenchmark| units::total_units(&b.throughput) / b.typical_nanos();
+        rate(a).total_cmp(&rate(b))
+    });
+    let mut units: Vec<Unit> = Vec::new();
+    for benchmark in &benchmarks {
+        let unit = Unit::of(&benchmark.throughput);
+        if units.contains(&unit) {
+            continue;
+        }
+        units.push(unit);
+        let in_unit: Vec<_> = by_rate
+            .iter()
+            .filter(|b| Unit::of(&b.throughput) == unit)
+            .collect();
+        let typical = in_unit[(in_unit.len() - 1) / 2];
+        format_benchmark(typical, &[typical.typical_nanos()]);
+    }
+
+    let width = benchmarks.iter().map(|b| b.id.len()).max().unwrap_or(0);
+    let summary = benchmarks
+        .iter()
+        .map(|benchmark| {
+            let mut nanos = benchmark.nanos.clone();
+            nanos.sort_by(|a, b| b.total_cmp(a));
+            let rates: Vec<_> = format_benchmark(benchmark, &nanos)
+                .iter()
+                .map(|rate| rate.trim_start().to_owned())
+                .collect();
+            format!("{:<width$}  thrpt:  [{}]", benchmark.id, rates.join(" "))
+        })
+        .collect();
+
+    BENCHMARK_ID.with(|i| *i.borrow_mut() = id);
+    SCOPE.with(|s| {
+        if let Some(scope) = s.borrow_mut().as_mut() {
+            scope.decoration = decoration;
+            if let Some(units) = scope.consistent_units.as_mut() {
+                units.multiples = None;
+                units.summary = summary;
+            }
+        }
+    })
+}
+
+/// Record the byte size of an input generated for the current benchmark.
+///
+/// Inputs recorded after the previous benchmark was reported start a new record.
+pub(crate) fn record_observed_bytes(bytes: u64) {
+    OBSERVED.with(|o| {
+        let mut observed = o.borrow_mut();
+        let record = match *observed {
+            Some(ref mut record) if !record.reported => record,
+            _ => observed.insert(ObservedBytes {
+                total_bytes: 0,
+                inputs: 0,
+                reported: false,
+            }),
+        };
+        record.total_bytes += u128::from(bytes);
+        record.inputs += 1;
+    })
+}
+
+/// Get a new identifier of a measurement, which it passes to [`start_measurement`]
+/// and to the functions reading what was recorded during its measurements.
+pub(crate) fn new_measurement() -> u64 {
+    MEASUREMENTS.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Whether the records of the current benchmark were measured by the given measurement.
+///
+/// Records stay in place until the next benchmark starts, so this keeps them from applying
+/// to formatting by other measurements, e.g. after the benchmarks finished.
+pub(crate) fn measured_by(measurement: u64) -> bool {
+    MEASURED_BY.with(|m| m.get() == Some(measurement))
+}
+
+/// Called when a measurement by the given measurement starts. Drops the record of a benchmark
+/// that was already reported, so that it does not apply to a following benchmark not using
+/// the batched extensions.
+///
+/// Returns whether the benchmark reported since the previous measurement had no throughput.
+pub(crate) fn start_measurement(measurement: u64) -> bool {
+    MEASURED_BY.with(|m| m.set(Some(measurement)));
+    OBSERVED.with(|o| {
+        let mut observed = o.borrow_mut();
+        if matches!(*observed, Some(record) if record.reported) {
+            *observed = None;
+        }
+    });
+    COUNTED.with(|c| {
+        let mut counted = c.borrow_mut();
+        if matches!(*counted, Some(record) if record.reported) {
+            *counted = None;
+        }
+    });
+    counter::take();
+    #[cfg(feature = "alloc_stats")]
+    crate::allocation::start_sample();
+    take_missing_throughput()
+}
+
+/// Called when the formatter scales times of a benchmark, which happens when it is reported.
+pub(crate) fn report_values() {
+    REPORTED.with(|r| r.borrow_mut().values = true);
+}
+
+/// Called when the formatter scales throughput of a benchmark.
+pub(crate) fn report_throughput() {
+    REPORTED.with(|r| r.borrow_mut().throughput = true);
+}
+
+/// Whether a benchmark was reported without throughput since the last call.
+pub(crate) fn take_missing_throughput() -> bool {
+    REPORTED.with(|r| {
+        let reported = r.replace(Reported {
+            values: false,
+            throughput: false,
+        });
+        reported.values && !reported.throughput
+    })
+}
+
+/// Called when a measurement of `nanos` nanoseconds ends. Records the bytes counted during it
+/// and, with the `alloc_stats` feature, the allocations.
+#[cfg_attr(not(feature = "alloc_stats"), allow(unused_variables))]
+pub(crate) fn end_measurement(nanos: f64) {
+    let (bytes, iterations) = counter::take();
+    record_counted_bytes(bytes, iterations);
+    #[cfg(feature = "alloc_stats")]
+    crate::allocation::end_sample(nanos);
+}
+
+fn record_counted_bytes(bytes: u64, iterations: u64) {
+    COUNTED.with(|c| {
+        let mut counted = c.borrow_mut();
+        let record = counted.get_or_insert(CountedBytes {
+            total_bytes: 0,
+            iterations: 0,
+            reported: false,
+        });
+        record.total_bytes += u128::from(bytes);
+        record.iterations += iterations;
+    })
+}
+
+/// Get the average counted bytes per iteration of the benchmark being reported, if any were
+/// counted during the measurements of the given measurement.
+///
+/// The average is the same for all estimates of the benchmark.
+pub(crate) fn counted_bytes(measurement: u64) -> Option<u64> {
+    if !measured_by(measurement) {
+        return None;
+    }
+    COUNTED.with(|c| {
+        let mut counted = c.borrow_mut();
+        let record = counted.as_mut()?;
+        record.reported = true;
+        if record.total_bytes == 0 {
+            return None;
+        }
+        Some((record.total_bytes as f64 / record.iterations as f64).round() as u64)
+    })
+}
+
+/// Get the average observed bytes per input of the benchmark being reported, if any were recorded
+/// for the measurements of the given measurement.
+pub(crate) fn observed_bytes(measurement: u64) -> Option<u64> {
+    if !measured_by(measurement) {
+        return None;
+    }
+    OBSERVED.with(|o| {
+        let mut observed = o.borrow_mut();
+        let record = observed.as_mut().filter(|r| r.inputs > 0)?;
+        record.reported = true;
+        Some((record.total_bytes as f64 / record.inputs as f64).round() as u64)
+    })
+}
+
+/// Run `f` with the id of the benchmark it runs known to the formatter, restoring the previous one afterwards.
+pub(crate) fn with_benchmark_id<T>(id: Option<String>, f: impl FnOnce() -> T) -> T {
+    let previous = BENCHMARK_ID.with(|i| i.replace(id));
+    let result = f();
+    BENCHMARK_ID.with(|i| *i.borrow_mut() = previous);
+    result
+}
+
+/// Run `f` with the state of the benchmark being run hidden from the formatter and the
+/// formatting it does not registered, restoring both afterwards.
+///
+/// Used for formatting outside of the reports of Criterion.rs, e.g. in custom reports,
+/// so that it only depends on the options of the measurement and does not affect the reports.
+pub(crate) fn detached<T>(f: impl FnOnce() -> T) -> T {
+    let scope = SCOPE.with(|s| s.take());
+    let id = BENCHMARK_ID.with(|i| i.take());
+    let measured_by = MEASURED_BY.with(|m| m.take());
+    let reported = REPORTED.with(|r| *r.borrow());
+    let result = f();
+    SCOPE.with(|s| *s.borrow_mut() = scope);
+    BENCHMARK_ID.with(|i| *i.borrow_mut() = id);
+    MEASURED_BY.with(|m| m.set(measured_by));
+    REPORTED.with(|r| *r.borrow_mut() = reported);
+    result
+}
+
+/// Look up something by the id of the benchmark being run, if it is known.
+pub(crate) fn by_benchmark_id<T>(f: impl FnOnce(&str) -> Option<T>) -> Option<T> {
+    BENCHMARK_ID.with(|i| i.borrow().as_deref().and_then(f))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::DecimalByteMeasurement;
+    use criterion::measurement::ValueFormatter;
+
+    #[test]
+    fn counted_bytes_are_averaged_over_iterations() {
+        let measurement = new_measurement();
+        start_measurement(measurement);
+        record_counted_bytes(3_000, 2);
+        record_counted_bytes(1_000, 2);
+
+        assert_eq!(counted_bytes(measurement), Some(1_000));
+        assert_eq!(counted_bytes(measurement), Some(1_000));
+    }
+
+    #[test]
+    fn counted_bytes_reset_after_report() {
+        let measurement = new_measurement();
+        start_measurement(measurement);
+        record_counted_bytes(1_000, 1);
+        counted_bytes(measurement);
+        start_measurement(measurement);
+
+        assert_eq!(counted_bytes(measurement), None);
+    }
+
+    #[test]
+    fn samples_without_counted_bytes_do_not_carry_over() {
+        let measurement = new_measurement();
+        start_measurement(measurement);
+        record_counted_bytes(0, 0);
+        assert_eq!(counted_bytes(measurement), None);
+        start_measurement(measurement);
+        record_counted_bytes(1_000, 1);
+
+        assert_eq!(counted_bytes(measurement), Some(1_000));
+    }
+
+    #[test]
+    fn counted_bytes_apply_to_every_estimate_of_their_measurement_only() {
+        let measurement = DecimalByteMeasurement::new();
+        start_measurement(measurement.id);
+        record_counted_bytes(4_000, 2);
+
+        let estimates = [1_000.0, 2_000.0]
+            .map(|nanos| measurement.format_throughput(&Throughput::Bytes(1), nanos));
+        let other = DecimalByteMeasurement::new().format_throughput(&Throughput::Bytes(1), 1_000.0);
+
+        assert_eq!(estimates, ["2.0000 GB/s", "1.0000 GB/s"]);
+        assert_eq!(other, "1.0000 MB/s");
+    }
+
+    #[test]
+    fn detached_formatting_ignores_and_keeps_state_of_benchmark() {
+        let measurement = DecimalByteMeasurement::new();
+        start_measurement(measurement.id);
+        record_counted_bytes(4_000, 2);
+        record_observed_bytes(3_000);
+        let mut scope = GroupScope {
+            decoration: None,
+            consistent_units: Some(ConsistentUnits::default()),
+        };
+
+        let result = with_group_scope(&mut scope, || {
+            detached(|| measurement.format_throughput(&Throughput::Bytes(1_000), 1_000.0))
+        });
+
+        assert_eq!(result, "1.0000 GB/s");
+        assert!(scope.consistent_units.unwrap().benchmarks.is_empty());
+        assert!(REPORTED.with(|r| !r.borrow().throughput));
+        assert_eq!(observed_bytes(measurement.id), Some(3_000));
+        assert_eq!(counted_bytes(measurement.id), Some(2_000));
+    }
+}
@@ -0,0 +1,57 @@
+//! Counter of bytes produced by a benchmark, e.g. the output of a compressor or a serializer.
+//!
+//! Call [`add_bytes`] once in every iteration of the measured routine with the number of bytes
+//! it produced.
+//! The reported throughput of the benchmark then uses the counted bytes instead of the configured
+//! [`Throughput::Bytes`](criterion::Throughput::Bytes), see [measured bytes](crate#measured-bytes)
+//! for where it is replaced.
+//!
+//! The counter is reset at the start of every sample, and the reported throughput uses the average
+//! of the counted bytes over all iterations of all samples, so the throughput of every estimate
+//! of the benchmark is computed from the same number of bytes. The counter is global, so routines
+//! can add to it from any thread, as long as they do so once per iteration.
+//!
+//! # Example
+//!
+//! ```
+//! use criterion::Throughput;
+//! use criterion_decimal_throughput::{counter, Criterion};
+//!
+//! fn compress(input: &[u8]) -> Vec<u8> {
+//!     input.to_vec() // Compress here...
+//! }
+//!
+//! fn example_bench(c: &mut Criterion) {
+//!     let input = vec![0_u8; 1_000_000];
+//!     let mut group = c.benchmark_group("compress");
+//!     // Typical size of the output, replaced by the counted number of bytes.
+//!     group.throughput(Throughput::Bytes(250_000));
+//!     group.bench_function("output", |b| {
+//!         b.iter(|| {
+//!             let output = compress(&input);
+//!             counter::add_bytes(output.len() as u64);
+//!             output
+//!         })
+//!     });
+//!     group.finish();
+//! }
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static BYTES: AtomicU64 = AtomicU64::new(0);
+static ITERATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Add `bytes` produced by one iteration to the bytes counted in the current sample.
+pub fn add_bytes(bytes: u64) {
+    BYTES.fetch_add(bytes, Ordering::Relaxed);
+    ITERATIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Take the bytes and the number of iterations counted since the last call, resetting the counter.
+pub(crate) fn take() -> (u64, u64) {
+    (
+        BYTES.swap(0, Ordering::Relaxed),
+        ITERATIONS.swap(0, Ordering::Relaxed),
+    )
+}
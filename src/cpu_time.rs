@@ -0,0 +1,112 @@
+use crate::DecimalByteMeasurement;
+use cpu_time::ProcessTime;
+use criterion::measurement::{Measurement, ValueFormatter, WallTime};
+use std::time::Duration;
+
+/// [`DecimalByteMeasurement`] measuring the CPU time of the process instead of wall time.
+///
+/// Requires the `cpu_time` feature.
+///
+/// # Example
+///
+/// ```
+/// use criterion_decimal_throughput::DecimalByteMeasurement;
+///
+/// let config = criterion::Criterion::default().with_measurement(DecimalByteMeasurement::cpu_time());
+/// ```
+pub type DecimalByteCpuTime = DecimalByteMeasurement<CpuTime>;
+
+/// Measurement of the CPU time used by the whole process, in nanoseconds.
+///
+/// Time spent by all threads of the process is counted, while time spent blocked or sleeping is not.
+/// Values are displayed the same way as for [`WallTime`].
+///
+/// Requires the `cpu_time` feature.
+pub struct CpuTime {
+    wall_time: WallTime,
+}
+
+impl CpuTime {
+    /// Create a new [`CpuTime`] measurement.
+    pub fn new() -> Self {
+        CpuTime {
+            wall_time: WallTime,
+        }
+    }
+}
+
+impl Default for CpuTime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DecimalByteMeasurement<CpuTime> {
+    /// Create a new [`DecimalByteCpuTime`] struct.
+    pub fn cpu_time() -> Self {
+        Self::from_time_measurement(CpuTime::new())
+    }
+}
+
+impl Measurement for CpuTime {
+    type Intermediate = ProcessTime;
+
+    type Value = Duration;
+
+    fn start(&self) -> Self::Intermediate {
+        ProcessTime::now()
+    }
+
+    fn end(&self, i: Self::Intermediate) -> Self::Value {
+        i.elapsed()
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        self.wall_time.add(v1, v2)
+    }
+
+    fn zero(&self) -> Self::Value {
+        self.wall_time.zero()
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        self.wall_time.to_f64(value)
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        self.wall_time.formatter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use criterion::Throughput;
+
+    #[test]
+    fn cpu_time_counts_busy_time() {
+        let measurement = CpuTime::new();
+        let start = measurement.start();
+        let mut x = 0_u64;
+        while measurement.to_f64(&start.elapsed()) == 0.0 {
+            x = std::hint::black_box(x.wrapping_add(1));
+        }
+
+        assert!(measurement.to_f64(&measurement.end(start)) > 0.0);
+    }
+
+    #[test]
+    fn cpu_time_uses_decimal_formatter() {
+        let measurement = DecimalByteMeasurement::cpu_time();
+        let mut values = [1_000.0];
+
+        let result = measurement.formatter().scale_throughputs(
+            1_000.0,
+            &Throughput::Bytes(2_000),
+            &mut values,
+        );
+
+        assert_eq!(result, "GB/s");
+        assert_eq!(values, [2.0]);
+    }
+}
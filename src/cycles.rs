@@ -0,0 +1,365 @@
+use crate::{
+    context, exact,
+    units::{Multiple, Unit},
+    DecimalByteMeasurement,
+};
+use criterion::{
+    measurement::{Measurement, ValueFormatter, WallTime},
+    Throughput,
+};
+use std::cell::RefCell;
+
+/// Wrapper over a [`Measurement`] counting CPU cycles that displays throughput in decimal units per cycle,
+/// so `B/cycle`, `Kelem/cycle`, etc.
+///
+/// The wrapped measurement's values have to be cycle counts. Measured values themselves are
+/// displayed by the wrapped measurement's formatter.
+///
+/// Requires the `cycles` feature.
+///
+/// # Example
+///
+/// ```
+/// use criterion_decimal_throughput::DecimalCycleMeasurement;
+/// # use criterion::measurement::WallTime as MyCycleCounter;
+///
+/// // Replace MyCycleCounter with your own cycle-counting measurement.
+/// let measurement = DecimalCycleMeasurement::new(MyCycleCounter);
+/// let config = criterion::Criterion::default().with_measurement(measurement);
+/// ```
+pub struct DecimalCycleMeasurement<M> {
+    inner: M,
+}
+
+impl<M: Measurement> DecimalCycleMeasurement<M> {
+    /// Wrap the given cycle-counting measurement.
+    pub fn new(inner: M) -> Self {
+        DecimalCycleMeasurement { inner }
+    }
+
+    /// The wrapped measurement.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+}
+
+impl<M: Measurement> Measurement for DecimalCycleMeasurement<M> {
+    type Intermediate = M::Intermediate;
+
+    type Value = M::Value;
+
+    fn start(&self) -> Self::Intermediate {
+        self.inner.start()
+    }
+
+    fn end(&self, i: Self::Intermediate) -> Self::Value {
+        self.inner.end(i)
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        self.inner.add(v1, v2)
+    }
+
+    fn zero(&self) -> Self::Value {
+        self.inner.zero()
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        self.inner.to_f64(value)
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        self
+    }
+}
+
+impl<M: Measurement> ValueFormatter for DecimalCycleMeasurement<M> {
+    fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+        self.inner.formatter().scale_values(typical_value, values)
+    }
+
+    fn scale_throughputs(
+        &self,
+        typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        scale_per_cycle(typical_value, throughput, values)
+    }
+
+    fn scale_for_machines(&self, values: &mut [f64]) -> &'static str {
+        self.inner.formatter().scale_for_machines(values)
+    }
+}
+
+/// Scale `values`, given in cycles, to throughput in decimal units per cycle.
+fn scale_per_cycle(
+    typical_value: f64,
+    throughput: &Throughput,
+    values: &mut [f64],
+) -> &'static str {
+    let count = exact::count(throughput);
+    let multiple = if crate::is_valid_time(typical_value) {
+        exact::multiple_for_quotient(count, 1, typical_value)
+    } else {
+        Multiple::One
+    };
+
+    for val in values {
+        *val = if crate::is_valid_time(*val) {
+            exact::scaled_quotient(count, 1, *val, multiple)
+        } else {
+            0.0
+        };
+    }
+
+    Unit::of(throughput).per_cycle_label(multiple)
+}
+
+/// Measurement of wall time that also counts CPU cycles with another [`Measurement`], and displays
+/// both the throughput per second and per cycle, e.g. `2.4000 GB/s, 0.8000  B/cycle`.
+///
+/// Throughput per second depends on the clock speed of the machine, while throughput per cycle
+/// is comparable between machines with different clock speeds.
+///
+/// Criterion.rs analyzes a single value per sample, so the statistics and the time line of
+/// the report are of the wall time. The throughput per cycle is derived from the typical time
+/// and the average number of cycles per nanosecond over all samples of the benchmark.
+///
+/// The cycle-counting measurement's values have to be cycle counts.
+///
+/// Requires the `cycles` feature.
+///
+/// # Example
+///
+/// ```
+/// use criterion_decimal_throughput::DecimalWallCycleMeasurement;
+/// # use criterion::measurement::WallTime as MyCycleCounter;
+///
+/// // Replace MyCycleCounter with your own cycle-counting measurement.
+/// let measurement = DecimalWallCycleMeasurement::new(MyCycleCounter);
+/// let config = criterion::Criterion::default().with_measurement(measurement);
+/// ```
+pub struct DecimalWallCycleMeasurement<C, M = WallTime> {
+    time: DecimalByteMeasurement<M>,
+    cycles: C,
+}
+
+impl<C: Measurement> DecimalWallCycleMeasurement<C> {
+    /// Count cycles with the given measurement alongside the default [`DecimalByteMeasurement`].
+    pub fn new(cycles: C) -> Self {
+        Self::from_measurements(DecimalByteMeasurement::new(), cycles)
+    }
+}
+
+impl<C: Measurement, M: Measurement> DecimalWallCycleMeasurement<C, M> {
+    /// Count cycles with the given measurement alongside the given time measurement,
+    /// which also formats the throughput per second.
+    pub fn from_measurements(time: DecimalByteMeasurement<M>, cycles: C) -> Self {
+        DecimalWallCycleMeasurement { time, cycles }
+    }
+
+    /// The time measurement.
+    pub fn time(&self) -> &DecimalByteMeasurement<M> {
+        &self.time
+    }
+
+    /// The cycle-counting measurement.
+    pub fn cycles(&self) -> &C {
+        &self.cycles
+    }
+}
+
+impl<C: Measurement, M: Measurement> Measurement for DecimalWallCycleMeasurement<C, M> {
+    type Intermediate = (M::Intermediate, C::Intermediate);
+
+    type Value = M::Value;
+
+    fn start(&self) -> Self::Intermediate {
+        start_sample();
+        let time = self.time.start();
+        (time, self.cycles.start())
+    }
+
+    fn end(&self, (time, cycles): Self::Intermediate) -> Self::Value {
+        let cycles = self.cycles.end(cycles);
+        let value = self.time.end(time);
+        record_sample(self.cycles.to_f64(&cycles), self.time.to_f64(&value));
+        value
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        self.time.add(v1, v2)
+    }
+
+    fn zero(&self) -> Self::Value {
+        self.time.zero()
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        self.time.to_f64(value)
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        self
+    }
+}
+
+impl<C: Measurement, M: Measurement> ValueFormatter for DecimalWallCycleMeasurement<C, M> {
+    fn format_throughput(&self, throughput: &Throughput, value: f64) -> String {
+        let formatted = self.time.format_throughput(throughput, value);
+
+        match cycles_per_nano(self.time.id) {
+            Some(cycles_per_nano) if value > 0.0 => {
+                let mut values = [value * cycles_per_nano];
+                let unit = scale_per_cycle(values[0], throughput, &mut values);
+                let number = self.time.format_number(values[0]);
+                format!("{}, {}", formatted, self.time.with_label(&number, unit))
+            }
+            _ => formatted,
+        }
+    }
+
+    fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+        self.time.scale_values(typical_value, values)
+    }
+
+    fn scale_throughputs(
+        &self,
+        typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        self.time
+            .scale_throughputs(typical_value, throughput, values)
+    }
+
+    fn scale_for_machines(&self, values: &mut [f64]) -> &'static str {
+        self.time.scale_for_machines(values)
+    }
+}
+
+thread_local! {
+    static SAMPLES: RefCell<Option<CycleCount>> = const { RefCell::new(None) };
+}
+
+/// Cycles counted in the samples of the current benchmark.
+#[derive(Debug, Clone, Copy)]
+struct CycleCount {
+    total_cycles: f64,
+    total_nanos: f64,
+    reported: bool,
+}
+
+/// Called when a measurement starts. Drops the record of a benchmark that was already reported.
+fn start_sample() {
+    SAMPLES.with(|s| {
+        let mut samples = s.borrow_mut();
+        if matches!(*samples, Some(record) if record.reported) {
+            *samples = None;
+        }
+    });
+}
+
+fn record_sample(cycles: f64, nanos: f64) {
+    SAMPLES.with(|s| {
+        let mut samples = s.borrow_mut();
+        let record = samples.get_or_insert(CycleCount {
+            total_cycles: 0.0,
+            total_nanos: 0.0,
+            reported: false,
+        });
+        record.total_cycles += cycles;
+        record.total_nanos += nanos;
+    })
+}
+
+/// Average cycles per nanosecond in the benchmark being reported, if it was measured
+/// by the time measurement with the given id.
+fn cycles_per_nano(measurement: u64) -> Option<f64> {
+    if !context::measured_by(measurement) {
+        return None;
+    }
+    SAMPLES.with(|s| {
+        let mut samples = s.borrow_mut();
+        let record = samples.as_mut()?;
+        record.reported = true;
+        if record.total_cycles <= 0.0 || record.total_nanos <= 0.0 {
+            return None;
+        }
+        Some(record.total_cycles / record.total_nanos)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use criterion::measurement::WallTime;
+
+    #[test]
+    fn scale_throughputs_bytes_per_cycle() {
+        let measurement = DecimalCycleMeasurement::new(WallTime);
+        let mut values = [2_000.0, 4_000.0];
+
+        let result = measurement.scale_throughputs(2_000.0, &Throughput::Bytes(1_000), &mut values);
+
+        assert_eq!(result, " B/cycle");
+        assert_eq!(values, [0.5, 0.25]);
+    }
+
+    #[test]
+    fn scale_throughputs_kilo_elems_per_cycle() {
+        let measurement = DecimalCycleMeasurement::new(WallTime);
+        let mut values = [10.0];
+
+        let result =
+            measurement.scale_throughputs(10.0, &Throughput::Elements(20_000), &mut values);
+
+        assert_eq!(result, "Kelem/cycle");
+        assert_eq!(values, [2.0]);
+    }
+
+    #[test]
+    fn scale_throughputs_zero_cycles_scale_to_zero() {
+        let measurement = DecimalCycleMeasurement::new(WallTime);
+        let mut values = [0.0, 2_000.0];
+
+        let result = measurement.scale_throughputs(0.0, &Throughput::Bytes(1_000), &mut values);
+
+        assert_eq!(result, " B/cycle");
+        assert_eq!(values, [0.0, 0.5]);
+    }
+
+    #[test]
+    fn wall_cycle_measurement_formats_both_throughputs() {
+        let measurement = DecimalWallCycleMeasurement::new(WallTime);
+        measurement.start();
+        record_sample(3_000_000.0, 1_000_000.0);
+
+        let result = measurement.format_throughput(&Throughput::Bytes(2_400_000), 1_000_000.0);
+
+        assert_eq!(result, "2.4000 GB/s, 0.8000  B/cycle");
+    }
+
+    #[test]
+    fn wall_cycle_measurement_ignores_cycles_of_other_measurements() {
+        let measured = DecimalWallCycleMeasurement::new(WallTime);
+        measured.start();
+        record_sample(3_000_000.0, 1_000_000.0);
+
+        let other = DecimalWallCycleMeasurement::new(WallTime);
+        let result = other.format_throughput(&Throughput::Bytes(2_400_000), 1_000_000.0);
+
+        assert_eq!(result, "2.4000 GB/s");
+    }
+
+    #[test]
+    fn wall_cycle_measurement_without_samples_formats_wall_time() {
+        let measurement = DecimalWallCycleMeasurement::new(WallTime);
+        start_sample();
+
+        let result = measurement.format_throughput(&Throughput::Bytes(2_400_000), 1_000_000.0);
+
+        assert_eq!(result, "2.4000 GB/s");
+    }
+}
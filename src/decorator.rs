@@ -0,0 +1,136 @@
+use crate::DecimalByteMeasurement;
+use criterion::{
+    measurement::{Measurement, ValueFormatter},
+    Throughput,
+};
+
+/// Formatter layered on top of the decimal formatting of [`DecimalByteMeasurement`],
+/// registered with [`DecimalByteMeasurement::with_formatter_decorator`].
+///
+/// Every method gets the decimal formatter as `inner` and by default delegates to it,
+/// so a decorator only overrides the methods it changes, e.g. to annotate formatted throughput.
+///
+/// # Example
+///
+/// ```
+/// use criterion::{measurement::ValueFormatter, Throughput};
+/// use criterion_decimal_throughput::{DecimalByteMeasurement, FormatterDecorator};
+///
+/// struct Annotated;
+///
+/// impl FormatterDecorator for Annotated {
+///     fn format_throughput(&self, inner: &dyn ValueFormatter, throughput: &Throughput, value: f64) -> String {
+///         format!("{} [cold cache]", inner.format_throughput(throughput, value))
+///     }
+/// }
+///
+/// let measurement = DecimalByteMeasurement::new().with_formatter_decorator(Annotated);
+/// let formatted = measurement.format_throughput(&Throughput::Bytes(1_000_000), 1_000_000.0);
+///
+/// assert_eq!(formatted, "1.0000 GB/s [cold cache]");
+/// ```
+pub trait FormatterDecorator {
+    /// Format a single measured value, see [`ValueFormatter::format_value`].
+    fn format_value(&self, inner: &dyn ValueFormatter, value: f64) -> String {
+        inner.format_value(value)
+    }
+
+    /// Format the throughput of a single measured value, see [`ValueFormatter::format_throughput`].
+    fn format_throughput(
+        &self,
+        inner: &dyn ValueFormatter,
+        throughput: &Throughput,
+        value: f64,
+    ) -> String {
+        inner.format_throughput(throughput, value)
+    }
+
+    /// Scale measured values for display, see [`ValueFormatter::scale_values`].
+    fn scale_values(
+        &self,
+        inner: &dyn ValueFormatter,
+        typical_value: f64,
+        values: &mut [f64],
+    ) -> &'static str {
+        inner.scale_values(typical_value, values)
+    }
+
+    /// Scale measured values to throughput for display, see [`ValueFormatter::scale_throughputs`].
+    fn scale_throughputs(
+        &self,
+        inner: &dyn ValueFormatter,
+        typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        inner.scale_throughputs(typical_value, throughput, values)
+    }
+
+    /// Scale measured values for machine-readable output, see [`ValueFormatter::scale_for_machines`].
+    fn scale_for_machines(&self, inner: &dyn ValueFormatter, values: &mut [f64]) -> &'static str {
+        inner.scale_for_machines(values)
+    }
+}
+
+/// The decimal formatter of the measurement without its decorator, passed to the decorator as `inner`.
+pub(crate) struct Undecorated<'a, M>(pub(crate) &'a DecimalByteMeasurement<M>);
+
+impl<M: Measurement> ValueFormatter for Undecorated<'_, M> {
+    fn format_throughput(&self, throughput: &Throughput, value: f64) -> String {
+        self.0.format_throughput_undecorated(throughput, value)
+    }
+
+    fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+        self.0.scale_values_undecorated(typical_value, values)
+    }
+
+    fn scale_throughputs(
+        &self,
+        typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        self.0
+            .scale_throughputs_undecorated(typical_value, throughput, values)
+    }
+
+    fn scale_for_machines(&self, values: &mut [f64]) -> &'static str {
+        self.0.scale_for_machines_undecorated(values)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Doubled;
+
+    impl FormatterDecorator for Doubled {
+        fn scale_throughputs(
+            &self,
+            inner: &dyn ValueFormatter,
+            typical_value: f64,
+            throughput: &Throughput,
+            values: &mut [f64],
+        ) -> &'static str {
+            let unit = inner.scale_throughputs(typical_value, throughput, values);
+            for val in values {
+                *val *= 2.0;
+            }
+            unit
+        }
+    }
+
+    #[test]
+    fn decorator_wraps_decimal_scaling() {
+        let measurement = DecimalByteMeasurement::new().with_formatter_decorator(Doubled);
+        let mut values = [1_000.0];
+
+        let unit = measurement.scale_throughputs(1_000.0, &Throughput::Bytes(2_000), &mut values);
+        let time = measurement.format_value(1_500.0);
+
+        assert_eq!(unit, "GB/s");
+        assert_eq!(values, [4.0]);
+        assert_eq!(time, "1.5000 µs");
+    }
+}
@@ -0,0 +1,129 @@
+//! Decimal formatting of throughput of [divan](https://docs.rs/divan) benchmarks.
+//!
+//! Divan attaches counters, like `BytesCount` and `ItemsCount`, to benchmarks and displays
+//! throughput with its own units. [`DivanFormatter`] formats the counters with the same
+//! units as [`DecimalByteMeasurement`], so that benchmarks of both harnesses report them consistently.
+//!
+//! The adapter takes the count and times divan reports, so it does not depend on divan itself
+//! and works with any of its versions.
+//!
+//! Requires the `divan` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use criterion_decimal_throughput::divan::DivanFormatter;
+//! use std::time::Duration;
+//!
+//! let formatter = DivanFormatter::new();
+//!
+//! // A benchmark with `BytesCount::new(1_000_000)` and a median time of 412 µs.
+//! let rate = formatter.bytes(1_000_000, Duration::from_micros(412));
+//!
+//! assert_eq!(rate, "2.4272 GB/s");
+//! ```
+
+use crate::DecimalByteMeasurement;
+use criterion::{
+    measurement::{Measurement, WallTime},
+    Throughput,
+};
+use std::time::Duration;
+
+/// Formatter of divan counters in decimal units, configured with a [`DecimalByteMeasurement`].
+pub struct DivanFormatter<M = WallTime> {
+    measurement: DecimalByteMeasurement<M>,
+}
+
+impl Default for DivanFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DivanFormatter {
+    /// Create a new formatter with the default configuration of [`DecimalByteMeasurement`].
+    pub fn new() -> Self {
+        Self::from_measurement(DecimalByteMeasurement::new())
+    }
+}
+
+impl<M: Measurement> DivanFormatter<M> {
+    /// Create a new formatter with the configuration of the given measurement,
+    /// e.g. the one used for Criterion.rs benchmarks of the same workspace.
+    pub fn from_measurement(measurement: DecimalByteMeasurement<M>) -> Self {
+        DivanFormatter { measurement }
+    }
+
+    /// Format the throughput of an iteration counting `bytes` that took `time`.
+    pub fn bytes(&self, bytes: u64, time: Duration) -> String {
+        self.rate(&Throughput::Bytes(bytes), time)
+    }
+
+    /// Format the throughput of an iteration counting `items` that took `time`.
+    pub fn items(&self, items: u64, time: Duration) -> String {
+        self.rate(&Throughput::Elements(items), time)
+    }
+
+    /// Format the throughput of the columns divan reports for a benchmark, which are the times
+    /// of the fastest, slowest, median, and mean iteration, in this order.
+    ///
+    /// All columns are in the same unit, selected for the median.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion::Throughput;
+    /// use criterion_decimal_throughput::divan::DivanFormatter;
+    /// use std::time::Duration;
+    ///
+    /// let formatter = DivanFormatter::new();
+    /// let times = [200, 2_000, 500, 1_000].map(Duration::from_nanos);
+    ///
+    /// let row = formatter.columns(&Throughput::Elements(1_000), times);
+    ///
+    /// assert_eq!(row, ["5.0000 Gelem/s", "0.5000 Gelem/s", "2.0000 Gelem/s", "1.0000 Gelem/s"]);
+    /// ```
+    pub fn columns(&self, throughput: &Throughput, times: [Duration; 4]) -> [String; 4] {
+        let nanos = times.map(|time| time.as_nanos() as f64);
+        let mut formatted = self
+            .measurement
+            .format_rates(nanos[2], &nanos, throughput)
+            .into_iter()
+            .map(|rate| rate.trim_start().to_owned());
+
+        [(); 4].map(|()| formatted.next().unwrap_or_default())
+    }
+
+    fn rate(&self, throughput: &Throughput, time: Duration) -> String {
+        self.measurement
+            .format_rate(time.as_nanos() as f64, throughput)
+            .trim_start()
+            .to_owned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::UnitFamily;
+
+    #[test]
+    fn formats_items_in_decimal_units() {
+        let formatter = DivanFormatter::new();
+
+        let result = formatter.items(1_500, Duration::from_millis(1));
+
+        assert_eq!(result, "1.5000 Melem/s");
+    }
+
+    #[test]
+    fn uses_configuration_of_measurement() {
+        let measurement = DecimalByteMeasurement::new().with_unit_family(UnitFamily::Binary);
+        let formatter = DivanFormatter::from_measurement(measurement);
+
+        let result = formatter.bytes(1_048_576, Duration::from_secs(1));
+
+        assert_eq!(result, "1.0000 MiB/s");
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{context, DecimalByteMeasurement, IntoBenchmarkId};
+use criterion::{measurement::Measurement, BenchmarkGroup, Throughput};
+
+/// Extension trait for [`criterion::BenchmarkGroup`] for benchmarks that process a different
+/// number of bytes in each iteration, e.g. a decompressor producing outputs of varying length.
+///
+/// The routine returns the number of bytes it processed, and the throughput is computed from
+/// the average number of bytes processed in an iteration instead of a fixed [`Throughput`],
+/// see [measured bytes](crate#measured-bytes) for where it replaces the configured one.
+///
+/// # Example
+///
+/// ```
+/// use criterion_decimal_throughput::{Criterion, DynamicThroughputExt};
+///
+/// fn example_bench(c: &mut Criterion) {
+///     let mut group = c.benchmark_group("decompress");
+///     group.bench_with_dynamic_bytes("example", || {
+///         let output = vec![0_u8; 1_000]; // Decompress here...
+///         output.len() as u64
+///     });
+///     group.finish();
+/// }
+/// ```
+pub trait DynamicThroughputExt {
+    /// Benchmark a routine that returns the number of bytes it processed in the iteration.
+    ///
+    /// The routine is called once before the benchmark to get a nominal number of bytes,
+    /// which becomes the [`Throughput::Bytes`] of the group, so that the results Criterion.rs saves
+    /// have the size of an actual iteration. The measured times are those of the iterations,
+    /// and the reported throughput is the average number of bytes of an iteration over them.
+    ///
+    /// Returned values are passed through [`black_box`](criterion::black_box).
+    ///
+    /// # Panics
+    ///
+    /// If the routine reports zero bytes processed in its first call.
+    fn bench_with_dynamic_bytes<ID, F>(&mut self, id: ID, routine: F) -> &mut Self
+    where
+        ID: IntoBenchmarkId,
+        F: FnMut() -> u64;
+}
+
+impl<'a, M: Measurement> DynamicThroughputExt for BenchmarkGroup<'a, DecimalByteMeasurement<M>> {
+    fn bench_with_dynamic_bytes<ID, F>(&mut self, id: ID, mut routine: F) -> &mut Self
+    where
+        ID: IntoBenchmarkId,
+        F: FnMut() -> u64,
+    {
+        let nominal_bytes = routine();
+        assert!(
+            nominal_bytes > 0,
+            "the routine has to process at least one byte"
+        );
+
+        self.throughput(Throughput::Bytes(nominal_bytes));
+        let mut routine = recording(routine);
+        id.bench_function(self, |b| b.iter(&mut routine));
+        self
+    }
+}
+
+fn recording<F: FnMut() -> u64>(mut routine: F) -> impl FnMut() -> u64 {
+    move || {
+        let bytes = routine();
+        context::record_observed_bytes(bytes);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{decimal_byte_measurement, results, test_criterion};
+    use criterion::measurement::ValueFormatter;
+
+    #[test]
+    fn dynamic_bytes_replace_nominal_throughput() {
+        let measurement = DecimalByteMeasurement::new();
+        let mut sizes = [100_u64, 300].into_iter();
+        let mut routine = recording(|| sizes.next().unwrap());
+        measurement.start();
+        routine();
+        routine();
+
+        let result = measurement.format_throughput(&Throughput::Bytes(100), 1_000.0);
+
+        assert_eq!(result, "200.00 MB/s");
+    }
+
+    #[test]
+    fn bench_with_dynamic_bytes_sets_nominal_throughput() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut c = test_criterion(dir.path());
+        let mut group = c.benchmark_group("test");
+        let mut sizes = [100_u64, 300].into_iter().cycle();
+
+        group.bench_with_dynamic_bytes("decompress", || sizes.next().unwrap());
+        group.finish();
+
+        let results = results::load_results(dir.path()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].throughput, Some(Throughput::Bytes(100)));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one byte")]
+    fn bench_with_dynamic_bytes_zero_nominal_bytes_panics() {
+        let mut c = decimal_byte_measurement();
+        let mut group = c.benchmark_group("test");
+
+        group.bench_with_dynamic_bytes("empty", || 0);
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{
+    exact,
+    units::{Multiple, Unit},
+};
+use criterion::{
+    measurement::{Measurement, ValueFormatter},
+    Throughput,
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+const DEFAULT_ZONE: &str = "/sys/class/powercap/intel-rapl:0";
+const MICROJOULES_PER_JOULE: u64 = 1_000_000;
+
+/// Measurement of the energy used during an iteration, read from a Linux powercap zone,
+/// e.g. RAPL on Intel and AMD CPUs.
+///
+/// Energy is displayed in `µJ`, `mJ`, `J`, etc., and throughput as units per joule,
+/// e.g. `MB/J` or `Kelem/J`.
+///
+/// The energy of the whole zone is measured, including other processes running on the same package,
+/// so run the benchmarks on an otherwise idle machine. Note that reading the energy counters
+/// usually requires root privileges.
+///
+/// Requires the `energy` feature and is available only on Linux.
+///
+/// # Example
+///
+/// ```no_run
+/// use criterion_decimal_throughput::DecimalEnergyMeasurement;
+///
+/// let measurement = DecimalEnergyMeasurement::try_new().expect("RAPL should be available");
+/// let config = criterion::Criterion::default().with_measurement(measurement);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DecimalEnergyMeasurement {
+    energy_path: PathBuf,
+    max_energy: u64,
+}
+
+impl DecimalEnergyMeasurement {
+    /// Create a measurement reading the energy of the first CPU package,
+    /// `/sys/class/powercap/intel-rapl:0`.
+    ///
+    /// # Errors
+    ///
+    /// If the energy counter of the zone cannot be read.
+    pub fn try_new() -> io::Result<Self> {
+        Self::try_with_zone(DEFAULT_ZONE)
+    }
+
+    /// Create a measurement reading the energy of the given powercap zone directory,
+    /// e.g. `/sys/class/powercap/intel-rapl:0:0` for the cores of the first package.
+    ///
+    /// # Errors
+    ///
+    /// If the energy counter of the zone cannot be read.
+    pub fn try_with_zone<P: AsRef<Path>>(zone: P) -> io::Result<Self> {
+        let zone = zone.as_ref();
+        let max_energy = read_counter(&zone.join("max_energy_range_uj"))?;
+        let energy_path = zone.join("energy_uj");
+        read_counter(&energy_path)?;
+
+        Ok(DecimalEnergyMeasurement {
+            energy_path,
+            max_energy,
+        })
+    }
+
+    fn read_energy(&self) -> u64 {
+        read_counter(&self.energy_path).expect("reading the energy counter should succeed")
+    }
+}
+
+fn read_counter(path: &Path) -> io::Result<u64> {
+    fs::read_to_string(path)?
+        .trim()
+        .parse()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Energy used between two readings of a counter that wraps around after `max_energy`.
+fn energy_between(start: u64, end: u64, max_energy: u64) -> u64 {
+    if end >= start {
+        end - start
+    } else {
+        max_energy - start + end
+    }
+}
+
+impl Measurement for DecimalEnergyMeasurement {
+    type Intermediate = u64;
+
+    type Value = u64;
+
+    fn start(&self) -> Self::Intermediate {
+        self.read_energy()
+    }
+
+    fn end(&self, i: Self::Intermediate) -> Self::Value {
+        energy_between(i, self.read_energy(), self.max_energy)
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        v1 + v2
+    }
+
+    fn zero(&self) -> Self::Value {
+        0
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        self
+    }
+}
+
+impl ValueFormatter for DecimalEnergyMeasurement {
+    fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+        // Values are in microjoules, so the multiples start from there.
+        let multiple = Multiple::for_value(typical_value);
+        let denominator = multiple.denominator();
+
+        for val in values {
+            *val /= denominator;
+        }
+
+        match multiple {
+            Multiple::One => "µJ",
+            Multiple::Kilo => "mJ",
+            Multiple::Mega => " J",
+            Multiple::Giga => "kJ",
+            Multiple::Tera => "MJ",
+        }
+    }
+
+    fn scale_throughputs(
+        &self,
+        typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let count = exact::count(throughput);
+        let multiple = if crate::is_valid_time(typical_value) {
+            exact::multiple_for_quotient(count, MICROJOULES_PER_JOULE, typical_value)
+        } else {
+            Multiple::One
+        };
+
+        for val in values {
+            *val = if crate::is_valid_time(*val) {
+                exact::scaled_quotient(count, MICROJOULES_PER_JOULE, *val, multiple)
+            } else {
+                0.0
+            };
+        }
+
+        Unit::of(throughput).per_joule_label(multiple)
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "µJ"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fake_zone(energy: u64) -> tempfile::TempDir {
+        let zone = tempfile::tempdir().unwrap();
+        fs::write(zone.path().join("max_energy_range_uj"), "1000000\n").unwrap();
+        fs::write(zone.path().join("energy_uj"), format!("{}\n", energy)).unwrap();
+        zone
+    }
+
+    #[test]
+    fn measures_energy_of_zone() {
+        let zone = fake_zone(100);
+        let measurement = DecimalEnergyMeasurement::try_with_zone(zone.path()).unwrap();
+
+        let start = measurement.start();
+        fs::write(zone.path().join("energy_uj"), "350\n").unwrap();
+        let result = measurement.end(start);
+
+        assert_eq!(result, 250);
+    }
+
+    #[test]
+    fn missing_zone_is_error() {
+        let zone = tempfile::tempdir().unwrap();
+
+        let result = DecimalEnergyMeasurement::try_with_zone(zone.path());
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn energy_between_wraps_around() {
+        assert_eq!(energy_between(900, 100, 1_000), 200);
+    }
+
+    #[test]
+    fn scale_values_joules() {
+        let zone = fake_zone(0);
+        let measurement = DecimalEnergyMeasurement::try_with_zone(zone.path()).unwrap();
+        let mut values = [2_000_000.0];
+
+        let result = measurement.scale_values(2_000_000.0, &mut values);
+
+        assert_eq!(result, " J");
+        assert_eq!(values, [2.0]);
+    }
+
+    #[test]
+    fn scale_throughputs_bytes_per_joule() {
+        let zone = fake_zone(0);
+        let measurement = DecimalEnergyMeasurement::try_with_zone(zone.path()).unwrap();
+        let mut values = [2_000.0];
+
+        // 1 MB per 2 mJ.
+        let result =
+            measurement.scale_throughputs(2_000.0, &Throughput::Bytes(1_000_000), &mut values);
+
+        assert_eq!(result, "MB/J");
+        assert_eq!(values, [500.0]);
+    }
+
+    #[test]
+    fn scale_throughputs_zero_energy_scales_to_zero() {
+        let zone = fake_zone(0);
+        let measurement = DecimalEnergyMeasurement::try_with_zone(zone.path()).unwrap();
+        let mut values = [0.0, 2_000.0];
+
+        let result = measurement.scale_throughputs(0.0, &Throughput::Bytes(1_000_000), &mut values);
+
+        assert_eq!(result, " B/J");
+        assert_eq!(values, [0.0, 500_000_000.0]);
+    }
+}
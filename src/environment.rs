@@ -0,0 +1,133 @@
+//! Overrides of formatting options from environment variables, so that CI jobs can change them
+//! without recompiling the bench binary. See [`DecimalByteMeasurement::from_time_measurement`].
+
+use crate::{
+    units::{DecimalUnitSystem, Multiple},
+    DecimalByteMeasurement, UnitFamily,
+};
+use std::{collections::BTreeMap, env, sync::Mutex};
+
+/// Display all throughput in the given [`Multiple`], e.g. `mega`.
+pub(crate) const UNIT: &str = "CDT_UNIT";
+/// [`UnitFamily`] to use, `decimal` or `binary`.
+pub(crate) const FAMILY: &str = "CDT_FAMILY";
+/// Number of significant figures of formatted throughput.
+pub(crate) const PRECISION: &str = "CDT_PRECISION";
+/// Label of elements, e.g. `rows` for `Mrows/s`.
+pub(crate) const LABEL: &str = "CDT_LABEL";
+
+impl<M> DecimalByteMeasurement<M> {
+    /// Apply the overrides set in the environment of the process.
+    pub(crate) fn with_environment(self) -> Self {
+        self.with_overrides(|name| env::var(name).ok())
+    }
+
+    /// Apply the overrides given by `var`, which returns the value of the variable with the given name.
+    fn with_overrides<F: Fn(&str) -> Option<String>>(mut self, var: F) -> Self {
+        let invalid = |name: &str, err: &dyn std::fmt::Display| -> ! {
+            panic!("invalid value of {}: {}", name, err)
+        };
+
+        if let Some(value) = var(UNIT) {
+            let multiple: Multiple = value.parse().unwrap_or_else(|err| invalid(UNIT, &err));
+            self.min_unit = multiple;
+            self.max_unit = multiple;
+            self.environment_unit = true;
+        }
+        if let Some(value) = var(FAMILY) {
+            self.unit_family = value
+                .parse::<UnitFamily>()
+                .unwrap_or_else(|err| invalid(FAMILY, &err));
+        }
+        if let Some(value) = var(PRECISION) {
+            let figures = value
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|figures| *figures > 0)
+                .unwrap_or_else(|| {
+                    invalid(PRECISION, &format!("'{}' is not a positive integer", value))
+                });
+            self.significant_figures = Some(figures);
+        }
+        if let Some(label) = var(LABEL) {
+            self.element_units = Some(Box::new(leaked_unit_system(label.trim())));
+        }
+
+        self
+    }
+}
+
+/// A [`DecimalUnitSystem`] for a label known only at runtime.
+///
+/// Criterion.rs requires unit labels to be `&'static str`, so they are leaked
+/// once for each distinct label, however many measurements are created.
+fn leaked_unit_system(label: &str) -> DecimalUnitSystem {
+    static SYSTEMS: Mutex<BTreeMap<String, DecimalUnitSystem>> = Mutex::new(BTreeMap::new());
+
+    let mut systems = SYSTEMS.lock().unwrap_or_else(|err| err.into_inner());
+    *systems.entry(label.to_string()).or_insert_with(|| {
+        let labels = [" ", "K", "M", "G", "T"]
+            .map(|prefix| &*Box::leak(format!("{}{}/s", prefix, label).into_boxed_str()));
+        DecimalUnitSystem::from_labels(labels)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::UnitSystem;
+    use criterion::{measurement::ValueFormatter, Throughput};
+    use std::collections::HashMap;
+
+    fn with_vars(vars: &[(&str, &str)]) -> DecimalByteMeasurement {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        DecimalByteMeasurement::new().with_overrides(|name| vars.get(name).map(|v| v.to_string()))
+    }
+
+    #[test]
+    fn overrides_formatting_options() {
+        let measurement = with_vars(&[(UNIT, "mega"), (PRECISION, "2"), (LABEL, "rows")]);
+
+        let bytes = measurement.format_throughput(&Throughput::Bytes(2_000_000), 1_000_000.0);
+        let elements = measurement.format_throughput(&Throughput::Elements(2_000), 1_000_000.0);
+
+        assert_eq!(bytes, "  2000 MB/s");
+        assert_eq!(elements, "   2.0 Mrows/s");
+    }
+
+    #[test]
+    fn overrides_unit_family() {
+        let measurement = with_vars(&[(FAMILY, "binary")]);
+
+        let result = measurement.format_throughput(&Throughput::Bytes(1_048_576), 1_000_000_000.0);
+
+        assert_eq!(result, "1.0000 MiB/s");
+    }
+
+    #[test]
+    fn unit_bounds_set_in_code_take_precedence() {
+        let capped = with_vars(&[(UNIT, "mega")]).with_max_unit(Multiple::Kilo);
+        let raised = with_vars(&[(UNIT, "mega")]).with_min_unit(Multiple::Giga);
+
+        let capped = capped.format_throughput(&Throughput::Bytes(2_000_000), 1_000_000.0);
+        let raised = raised.format_throughput(&Throughput::Bytes(2_000), 1_000_000.0);
+
+        assert_eq!(capped, "2000000 KB/s");
+        assert_eq!(raised, "0.0020 GB/s");
+    }
+
+    #[test]
+    fn labels_are_leaked_once() {
+        let first = leaked_unit_system("rows");
+        let second = leaked_unit_system("rows");
+
+        assert!(std::ptr::eq(first.ladder()[0].1, second.ladder()[0].1));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid value of CDT_PRECISION: '0' is not a positive integer")]
+    fn invalid_value_panics() {
+        with_vars(&[(PRECISION, "0")]);
+    }
+}
@@ -0,0 +1,323 @@
+//! Throughput arithmetic without intermediate rounding.
+//!
+//! Computing `units * (1e9 / nanos)` in floating point rounds the unit count,
+//! the inverse of the time, and their product. Close to a threshold between two multiples
+//! this can select the wrong one, and for counts near [`u64::MAX`] the scaled values are
+//! off by several ulps. Instead, the rational `units * 1e9 * period / (multiple * nanos)`
+//! is kept in integers: unit selection is exact, and scaled values are correctly rounded.
+
+use crate::units::{Multiple, Period};
+use criterion::Throughput;
+use std::cmp::Ordering;
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+/// The exact number of units in the throughput.
+pub(crate) fn count(throughput: &Throughput) -> u64 {
+    match *throughput {
+        Throughput::Bytes(bytes) => bytes,
+        Throughput::Elements(elements) => elements,
+    }
+}
+
+/// The largest multiple in which `units` per `nanos` nanoseconds is at least `threshold` per second,
+/// or [`Multiple::One`] if there is none. Selection is exact for a threshold of one.
+pub(crate) fn multiple_for_rate(units: u64, nanos: f64, threshold: f64) -> Multiple {
+    let units_nanos = u128::from(units) * NANOS_PER_SECOND;
+    let threshold_nanos = nanos * threshold;
+    Multiple::ALL
+        .iter()
+        .rev()
+        .copied()
+        .find(|m| compare(units_nanos, threshold_nanos, decimal(*m)) != Ordering::Less)
+        .unwrap_or(Multiple::One)
+}
+
+/// The shortest period in which `units` per `nanos` nanoseconds amounts to at least one unit,
+/// or [`Period::Hour`] if there is no such period.
+pub(crate) fn period_for_rate(units: u64, nanos: f64) -> Period {
+    let units_nanos = u128::from(units) * NANOS_PER_SECOND;
+    [Period::Second, Period::Minute]
+        .into_iter()
+        .find(|p| compare(units_nanos * seconds(*p), nanos, 1) != Ordering::Less)
+        .unwrap_or(Period::Hour)
+}
+
+/// The rate of `units` per `nanos` nanoseconds, per `period` and in the given multiple,
+/// correctly rounded.
+pub(crate) fn scaled_rate(units: u64, nanos: f64, period: Period, multiple: Multiple) -> f64 {
+    scaled(
+        u128::from(units) * NANOS_PER_SECOND * seconds(period),
+        nanos,
+        multiple,
+    )
+}
+
+/// The largest multiple in which `units * scale` per `amount` is at least one, or [`Multiple::One`]
+/// if there is none, for throughput per a quantity other than time, e.g. per cycle with a scale
+/// of one, or per joule of an amount in microjoules with a scale of a million.
+#[cfg(any(feature = "cycles", all(feature = "energy", target_os = "linux")))]
+pub(crate) fn multiple_for_quotient(units: u64, scale: u64, amount: f64) -> Multiple {
+    let numerator = u128::from(units) * u128::from(scale);
+    Multiple::ALL
+        .iter()
+        .rev()
+        .copied()
+        .find(|m| compare(numerator, amount, decimal(*m)) != Ordering::Less)
+        .unwrap_or(Multiple::One)
+}
+
+/// `units * scale` per `amount` in the given multiple, correctly rounded.
+#[cfg(any(feature = "cycles", all(feature = "energy", target_os = "linux")))]
+pub(crate) fn scaled_quotient(units: u64, scale: u64, amount: f64, multiple: Multiple) -> f64 {
+    scaled(u128::from(units) * u128::from(scale), amount, multiple)
+}
+
+/// The smallest multiple in which `amount` per `units` is at least one, or [`Multiple::Tera`]
+/// if there is none, for counts of events per unit, e.g. instructions per byte.
+#[cfg(all(feature = "perf_event", target_os = "linux"))]
+pub(crate) fn multiple_for_inverse_quotient(units: u64, amount: f64) -> Multiple {
+    Multiple::ALL
+        .iter()
+        .copied()
+        .find(|m| compare(u128::from(units), amount, decimal(*m)) != Ordering::Greater)
+        .unwrap_or(Multiple::Tera)
+}
+
+/// `amount` per `units` in the given multiple, correctly rounded. The amount has to be positive
+/// and finite, and the units nonzero.
+#[cfg(all(feature = "perf_event", target_os = "linux"))]
+pub(crate) fn scaled_inverse_quotient(units: u64, amount: f64, multiple: Multiple) -> f64 {
+    let (mantissa, exponent) = decompose(amount).expect("amount is positive and finite");
+    let numerator = u128::from(mantissa) * decimal(multiple);
+    scale_by_power_of_two(ratio(numerator, u128::from(units)), exponent)
+}
+
+/// `numerator` per `amount` in the given multiple, correctly rounded.
+fn scaled(numerator: u128, amount: f64, multiple: Multiple) -> f64 {
+    match decompose(amount) {
+        Some((mantissa, exponent)) => {
+            let denominator = decimal(multiple) * u128::from(mantissa);
+            scale_by_power_of_two(ratio(numerator, denominator), -exponent)
+        }
+        None => numerator as f64 / decimal(multiple) as f64 / amount,
+    }
+}
+
+fn decimal(multiple: Multiple) -> u128 {
+    match multiple {
+        Multiple::One => 1,
+        Multiple::Kilo => 1_000,
+        Multiple::Mega => 1_000_000,
+        Multiple::Giga => 1_000_000_000,
+        Multiple::Tera => 1_000_000_000_000,
+    }
+}
+
+fn seconds(period: Period) -> u128 {
+    match period {
+        Period::Second => 1,
+        Period::Minute => 60,
+        Period::Hour => 3_600,
+    }
+}
+
+/// Split a positive finite `value` into `mantissa * 2^exponent`.
+fn decompose(value: f64) -> Option<(u64, i32)> {
+    if !value.is_finite() || value <= 0.0 {
+        return None;
+    }
+    let bits = value.to_bits();
+    let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+    if biased_exponent == 0 {
+        Some((fraction, -1074))
+    } else {
+        Some((fraction | (1 << 52), biased_exponent - 1075))
+    }
+}
+
+/// `numerator / denominator`, correctly rounded. The denominator has to be below 2^127.
+fn ratio(numerator: u128, denominator: u128) -> f64 {
+    if numerator == 0 {
+        return 0.0;
+    }
+    // Long division until the quotient has more than 64 significant bits,
+    // with a sticky bit for any remainder so that converting it rounds correctly.
+    let mut quotient = numerator / denominator;
+    let mut remainder = numerator % denominator;
+    let mut shift = 0;
+    while quotient >> 64 == 0 {
+        remainder <<= 1;
+        quotient <<= 1;
+        if remainder >= denominator {
+            remainder -= denominator;
+            quotient |= 1;
+        }
+        shift += 1;
+    }
+    if remainder != 0 {
+        quotient |= 1;
+    }
+    scale_by_power_of_two(quotient as f64, -shift)
+}
+
+fn scale_by_power_of_two(mut value: f64, mut exponent: i32) -> f64 {
+    // Powers of two outside of this range are not representable, so scale in steps.
+    const STEP: i32 = 512;
+    while exponent > STEP {
+        value *= 2_f64.powi(STEP);
+        exponent -= STEP;
+    }
+    while exponent < -STEP {
+        value *= 2_f64.powi(-STEP);
+        exponent += STEP;
+    }
+    value * 2_f64.powi(exponent)
+}
+
+/// Compare `lhs` with `rhs * scale` exactly. `lhs` is less than NaN and infinite `rhs`.
+fn compare(lhs: u128, rhs: f64, scale: u128) -> Ordering {
+    if !rhs.is_finite() {
+        return Ordering::Less;
+    }
+    if rhs <= 0.0 {
+        return if lhs == 0 && (rhs == 0.0 || scale == 0) {
+            Ordering::Equal
+        } else {
+            Ordering::Greater
+        };
+    }
+
+    let (mantissa, exponent) = decompose(rhs).expect("rhs is positive and finite");
+    let scaled_mantissa = u128::from(mantissa)
+        .checked_mul(scale)
+        .expect("scale has to fit within 75 bits");
+
+    if exponent >= 0 {
+        // lhs vs scaled_mantissa << exponent, with rhs overflowing meaning it's larger.
+        if scaled_mantissa.leading_zeros() as i32 <= exponent {
+            Ordering::Less
+        } else {
+            lhs.cmp(&(scaled_mantissa << exponent))
+        }
+    } else {
+        // lhs << -exponent vs scaled_mantissa, with lhs overflowing meaning it's larger.
+        let shift = -exponent;
+        if lhs == 0 {
+            0.cmp(&scaled_mantissa)
+        } else if lhs.leading_zeros() as i32 <= shift {
+            Ordering::Greater
+        } else {
+            (lhs << shift).cmp(&scaled_mantissa)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn reference_multiple(units: u64, nanos: u64) -> Multiple {
+        let units_nanos = u128::from(units) * NANOS_PER_SECOND;
+        Multiple::ALL
+            .iter()
+            .rev()
+            .copied()
+            .find(|m| units_nanos >= decimal(*m) * u128::from(nanos))
+            .unwrap_or(Multiple::One)
+    }
+
+    fn ulp(value: f64) -> f64 {
+        f64::from_bits(value.to_bits() + 1) - value
+    }
+
+    #[test]
+    fn compare_integers() {
+        assert_eq!(compare(6, 3.0, 2), Ordering::Equal);
+        assert_eq!(compare(7, 3.0, 2), Ordering::Greater);
+        assert_eq!(compare(5, 3.0, 2), Ordering::Less);
+    }
+
+    #[test]
+    fn compare_fractions() {
+        assert_eq!(compare(1, 0.5, 2), Ordering::Equal);
+        assert_eq!(compare(1, 0.1, 9), Ordering::Greater);
+        assert_eq!(compare(0, f64::MIN_POSITIVE, 1), Ordering::Less);
+        assert_eq!(compare(1, f64::MAX, 1), Ordering::Less);
+    }
+
+    #[test]
+    fn multiple_for_rate_at_threshold() {
+        // Exactly 1 TB/s, which rounds down to 999999999999.9999 B/s in naive floating point arithmetic.
+        assert_eq!(
+            multiple_for_rate(49_000_000_000_000, 49e9, 1.0),
+            Multiple::Tera
+        );
+        assert_eq!(multiple_for_rate(1_000, 1e9, 1.0), Multiple::Kilo);
+        assert_eq!(
+            multiple_for_rate(1_000, f64::from_bits(1e9_f64.to_bits() + 1), 1.0),
+            Multiple::One
+        );
+    }
+
+    #[test]
+    #[cfg(any(feature = "cycles", all(feature = "energy", target_os = "linux")))]
+    fn multiple_for_quotient_at_threshold() {
+        // Just below 1k units per cycle, which the units rounded to floating point reach.
+        assert_eq!(
+            multiple_for_quotient(999_999_999_999_999_999, 1, 1e15),
+            Multiple::One
+        );
+        assert_eq!(multiple_for_quotient(49_000, 1, 49.0), Multiple::Kilo);
+        assert_eq!(multiple_for_quotient(1, 1_000_000, 1_000.0), Multiple::Kilo);
+        assert_eq!(multiple_for_quotient(999, 1, 1.0), Multiple::One);
+    }
+
+    #[test]
+    #[cfg(all(feature = "perf_event", target_os = "linux"))]
+    fn multiple_for_inverse_quotient_at_threshold() {
+        assert_eq!(multiple_for_inverse_quotient(1_000, 3_000.0), Multiple::One);
+        assert_eq!(multiple_for_inverse_quotient(1_000, 999.0), Multiple::Kilo);
+        assert_eq!(multiple_for_inverse_quotient(1_000, 1_000.0), Multiple::One);
+        assert_eq!(
+            multiple_for_inverse_quotient(u64::MAX, 1e-3),
+            Multiple::Tera
+        );
+        assert_eq!(
+            scaled_inverse_quotient(3, 1.0, Multiple::Kilo),
+            1_000.0 / 3.0
+        );
+    }
+
+    #[test]
+    fn scaled_rate_for_max_units() {
+        let result = scaled_rate(u64::MAX, 1e9, Period::Second, Multiple::Tera);
+
+        assert_eq!(result, 18_446_744.073_709_55);
+    }
+
+    proptest! {
+        #[test]
+        fn multiple_for_rate_is_exact(units in any::<u64>(), nanos in 1..(1_u64 << 53)) {
+            let result = multiple_for_rate(units, nanos as f64, 1.0);
+
+            prop_assert_eq!(result, reference_multiple(units, nanos));
+        }
+
+        #[test]
+        fn scaled_rate_is_within_one_ulp(units in any::<u64>(), nanos in 1..(1_u64 << 30), multiple_index in 0..5_usize) {
+            let multiple = Multiple::ALL[multiple_index];
+            let result = scaled_rate(units, nanos as f64, Period::Second, multiple);
+
+            // The exact value is units * 1e9 / (multiple * nanos).
+            let numerator = u128::from(units) * NANOS_PER_SECOND;
+            let scale = decimal(multiple) * u128::from(nanos);
+            let below = result - ulp(result);
+            let above = result + ulp(result);
+            prop_assert_ne!(compare(numerator, below, scale), Ordering::Less);
+            prop_assert_ne!(compare(numerator, above, scale), Ordering::Greater);
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! Number formatting mirroring the one used by Criterion.rs in its console output.
+
+use crate::{
+    context, exact,
+    units::{self, Multiple, Period, Unit},
+    RoundingMode,
+};
+use criterion::{
+    measurement::{Measurement, ValueFormatter, WallTime},
+    Throughput,
+};
+
+/// Format a number with four significant digits, the same way Criterion.rs does.
+pub(crate) fn short(n: f64) -> String {
+    format!("{:.*}", short_decimals(n), n)
+}
+
+/// Format a number with the same number of decimals as [`short`], rounded with the given mode.
+pub(crate) fn short_rounded(n: f64, mode: RoundingMode) -> String {
+    let decimals = short_decimals(n);
+    let scale = 10_f64.powi(-(decimals as i32));
+    format!("{:.*}", decimals, round(n / scale, mode) * scale)
+}
+
+fn short_decimals(n: f64) -> usize {
+    if n < 10.0 {
+        4
+    } else if n < 100.0 {
+        3
+    } else if n < 1000.0 {
+        2
+    } else if n < 10000.0 {
+        1
+    } else {
+        0
+    }
+}
+
+/// Format a number rounded to the given number of significant figures with the given mode.
+pub(crate) fn significant(n: f64, figures: usize, mode: RoundingMode) -> String {
+    if n == 0.0 || !n.is_finite() {
+        return format!("{:.*}", figures.saturating_sub(1), n);
+    }
+
+    let magnitude = n.abs().log10().floor() as i32;
+    let scale = 10_f64.powi(magnitude + 1 - figures as i32);
+    let rounded = round(n / scale, mode) * scale;
+    // Rounding can carry over to the next power of ten, e.g. 9.996 to 10.0.
+    let magnitude = rounded.abs().log10().floor() as i32;
+    let decimals = (figures as i32 - 1 - magnitude).max(0) as usize;
+
+    format!("{:.*}", decimals, rounded)
+}
+
+/// Round `x` to an integer with the given mode.
+///
+/// Ties are decided on the exact binary value of `x`. The directed modes treat values
+/// within the error of scaling from an integer as that integer, so that e.g. `2.4272`
+/// scaled to `24271.999999999996` is not rounded down to `24271`.
+fn round(x: f64, mode: RoundingMode) -> f64 {
+    let nearest = x.round();
+    let is_integer = (x - nearest).abs() <= x.abs() * 4.0 * f64::EPSILON;
+
+    match mode {
+        RoundingMode::HalfAwayFromZero => nearest,
+        RoundingMode::HalfEven => x.round_ties_even(),
+        _ if is_integer => nearest,
+        RoundingMode::Floor => x.floor(),
+        RoundingMode::Ceil => x.ceil(),
+        RoundingMode::Truncate => x.trunc(),
+    }
+}
+
+/// Format throughput given in base units per second the same way as the console output,
+/// without the padding.
+pub(crate) fn units_per_second(throughput: &Throughput, units_per_second: f64) -> String {
+    let nanos = units::total_units(throughput) * 1e9 / units_per_second;
+    units::format_throughput(nanos, throughput)
+}
+
+/// Escape `text` for a cell of a Markdown table, so that e.g. a `|` in a benchmark id
+/// does not split the cell.
+pub(crate) fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Alignment of a column of a table, see [`align_columns`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Align {
+    Left,
+    Right,
+}
+
+/// Pad every cell to the width of its column in characters, so that the columns line up
+/// when [joined](join_rows).
+pub(crate) fn align_columns<const N: usize>(rows: &mut [[String; N]], alignments: [Align; N]) {
+    for (column, alignment) in alignments.into_iter().enumerate() {
+        let width = rows
+            .iter()
+            .map(|row| row[column].chars().count())
+            .max()
+            .unwrap_or(0);
+        for row in rows.iter_mut() {
+            row[column] = match alignment {
+                Align::Left => format!("{:<1$}", row[column], width),
+                Align::Right => format!("{:>1$}", row[column], width),
+            };
+        }
+    }
+}
+
+/// Lines of the cells of every row, separated by two spaces.
+pub(crate) fn join_rows<const N: usize>(rows: &[[String; N]]) -> String {
+    rows.iter().map(|row| row.join("  ") + "\n").collect()
+}
+
+/// Scale times in nanoseconds to throughput in base units per second, see
+/// [`DecimalByteMeasurement::scale_throughputs_for_machines`](crate::DecimalByteMeasurement::scale_throughputs_for_machines).
+pub(crate) fn throughputs_for_machines(
+    throughput: &Throughput,
+    values: &mut [f64],
+) -> &'static str {
+    let count = exact::count(throughput);
+
+    for val in values {
+        *val = exact::scaled_rate(count, *val, Period::Second, Multiple::One);
+    }
+
+    Unit::of(throughput).rate_label(Multiple::One).trim_start()
+}
+
+/// Formatter with the default options of [`DecimalByteMeasurement`](crate::DecimalByteMeasurement),
+/// for formatting outside of benchmarks, e.g. in reports.
+///
+/// Unlike the measurement it only scales through the unit ladder, so it has no side effects and
+/// does not depend on the benchmark being run or the environment, e.g. recorded input sizes,
+/// consistent units of a group or unit overrides.
+pub(crate) struct Plain;
+
+impl ValueFormatter for Plain {
+    fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+        WallTime.formatter().scale_values(typical_value, values)
+    }
+
+    fn scale_throughputs(
+        &self,
+        typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let count = exact::count(throughput);
+        let multiple = if crate::is_valid_time(typical_value) {
+            exact::multiple_for_rate(count, typical_value, 1.0)
+        } else {
+            Multiple::One
+        };
+
+        for val in values {
+            *val = exact::scaled_rate(count, *val, Period::Second, multiple);
+        }
+
+        Unit::of(throughput).rate_label(multiple)
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "ns"
+    }
+}
+
+/// Formatter formatting with the options of a measurement, but without depending on
+/// or affecting the benchmark being run, for formatting outside of benchmarks, e.g. in reports.
+pub(crate) struct Detached<F>(pub(crate) F);
+
+impl<F: ValueFormatter> ValueFormatter for Detached<F> {
+    fn format_value(&self, value: f64) -> String {
+        context::detached(|| self.0.format_value(value))
+    }
+
+    fn format_throughput(&self, throughput: &Throughput, value: f64) -> String {
+        context::detached(|| self.0.format_throughput(throughput, value))
+    }
+
+    fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+        context::detached(|| self.0.scale_values(typical_value, values))
+    }
+
+    fn scale_throughputs(
+        &self,
+        typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        context::detached(|| self.0.scale_throughputs(typical_value, throughput, values))
+    }
+
+    fn scale_for_machines(&self, values: &mut [f64]) -> &'static str {
+        context::detached(|| self.0.scale_for_machines(values))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn significant_rounds_to_figures() {
+        let significant = |n, figures| significant(n, figures, RoundingMode::HalfAwayFromZero);
+
+        assert_eq!(significant(1.23456, 3), "1.23");
+        assert_eq!(significant(123.456, 2), "120");
+        assert_eq!(significant(0.0123456, 2), "0.012");
+        assert_eq!(significant(9.996, 3), "10.0");
+        assert_eq!(significant(999.7, 3), "1000");
+        assert_eq!(significant(0.0, 3), "0.00");
+    }
+
+    #[test]
+    fn significant_rounds_with_mode() {
+        let modes = [
+            RoundingMode::HalfAwayFromZero,
+            RoundingMode::HalfEven,
+            RoundingMode::Floor,
+            RoundingMode::Ceil,
+            RoundingMode::Truncate,
+        ];
+
+        let ties = modes.map(|mode| significant(0.125, 2, mode));
+        let negative = modes.map(|mode| significant(-1.27, 2, mode));
+
+        assert_eq!(ties, ["0.13", "0.12", "0.12", "0.13", "0.12"]);
+        assert_eq!(negative, ["-1.3", "-1.3", "-1.3", "-1.2", "-1.2"]);
+    }
+
+    #[test]
+    fn plain_formats_like_default_measurement() {
+        let measurement = crate::DecimalByteMeasurement::new();
+        let cases = [
+            (Throughput::Bytes(2_000), 1_000.0),
+            (Throughput::Bytes(5), 1e9),
+            (Throughput::Elements(1_000), 1e6),
+            (Throughput::Elements(u64::MAX), 1.0),
+        ];
+
+        for (throughput, nanos) in cases {
+            assert_eq!(
+                Plain.format_throughput(&throughput, nanos),
+                measurement.format_throughput(&throughput, nanos)
+            );
+        }
+        assert_eq!(
+            Plain.format_value(1_500.0),
+            measurement.format_value(1_500.0)
+        );
+    }
+
+    #[test]
+    fn align_columns_pads_to_widest_cell() {
+        let mut rows = [
+            ["Benchmark", "Throughput"].map(str::to_owned),
+            ["µs", "1 GB/s"].map(str::to_owned),
+        ];
+
+        align_columns(&mut rows, [Align::Left, Align::Right]);
+
+        assert_eq!(
+            join_rows(&rows),
+            "Benchmark  Throughput\n\
+             µs             1 GB/s\n"
+        );
+    }
+
+    #[test]
+    fn short_rounded_keeps_exact_values() {
+        assert_eq!(short_rounded(2.4272, RoundingMode::Floor), "2.4272");
+        assert_eq!(short_rounded(2.42729, RoundingMode::Floor), "2.4272");
+        assert_eq!(short_rounded(412.001, RoundingMode::Ceil), "412.01");
+        assert_eq!(short_rounded(12345.5, RoundingMode::HalfEven), "12346");
+    }
+}
@@ -0,0 +1,335 @@
+use crate::{
+    format,
+    results::{self, BenchmarkResult},
+};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Check that benchmarks reach a minimum throughput, e.g. to fail CI on a performance regression.
+///
+/// The gate reads the results Criterion.rs saved in its output directory, so it has to be
+/// checked after the benchmarks finish. Only results saved after the gate was constructed
+/// are checked, so stale data is never compared against the minimums. A minimum of a benchmark
+/// without such a result, e.g. because of a typo in its id or because it was filtered out,
+/// fails the check, as does a minimum of a benchmark without throughput.
+///
+/// # Example
+///
+/// ```
+/// use criterion::Throughput;
+/// use criterion_decimal_throughput::{Criterion, ThroughputGate};
+///
+/// fn example_bench(c: &mut Criterion) {
+///     let gate = ThroughputGate::new()
+///         // 2 GB/s
+///         .min_throughput("example_name/parse", 2e9);
+///
+///     let mut group = c.benchmark_group("example_name");
+///     group.throughput(Throughput::Bytes(1_000_000));
+///     // Add the "parse" benchmark to the group here...
+///     group.finish();
+///
+///     gate.assert();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThroughputGate {
+    output_directory: PathBuf,
+    minimums: Vec<(String, f64)>,
+    created_at: SystemTime,
+}
+
+/// Error returned by [`ThroughputGate::check`].
+#[derive(Debug)]
+pub enum ThroughputGateError {
+    /// Reading the results saved by Criterion.rs failed.
+    Io(io::Error),
+    /// No results were saved after the gate was created for the benchmarks with these full ids,
+    /// so their minimums could not be checked.
+    MissingResults(Vec<String>),
+    /// The results of these benchmarks have no throughput, so their minimums could not be checked.
+    MissingThroughput(Vec<BenchmarkResult>),
+    /// Some benchmarks did not reach their minimum throughput.
+    BelowMinimum(Vec<GateViolation>),
+}
+
+/// A benchmark that did not reach its minimum throughput.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GateViolation {
+    /// The results of the benchmark.
+    pub result: BenchmarkResult,
+    /// Configured minimum throughput in base units per second.
+    pub minimum: f64,
+    /// Typical measured throughput in base units per second.
+    pub measured: f64,
+}
+
+impl Default for ThroughputGate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThroughputGate {
+    /// Create a new gate with no minimums, reading results
+    /// from the [default output directory](results::default_output_directory).
+    pub fn new() -> Self {
+        ThroughputGate {
+            output_directory: results::default_output_directory(),
+            minimums: vec![],
+            created_at: SystemTime::now(),
+        }
+    }
+
+    /// Read the results from the given directory instead of the default one.
+    ///
+    /// Use this if you changed it with [`criterion::Criterion::output_directory`].
+    pub fn output_directory<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.output_directory = path.as_ref().to_owned();
+        self
+    }
+
+    /// Require the benchmark with the given full id, e.g. `group/function`,
+    /// to reach at least `units_per_second` of throughput.
+    ///
+    /// The unit is bytes or elements per second, depending on the [`criterion::Throughput`]
+    /// of the benchmark, so 2 GB/s is `2e9`.
+    pub fn min_throughput<S: Into<String>>(mut self, id: S, units_per_second: f64) -> Self {
+        self.minimums.push((id.into(), units_per_second));
+        self
+    }
+
+    /// Check all minimums against the results of benchmarks run after this gate was created.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ThroughputGateError::MissingResults`] listing all minimums without a fresh result,
+    /// [`ThroughputGateError::MissingThroughput`] listing all results of minimums without throughput,
+    /// [`ThroughputGateError::BelowMinimum`] listing all benchmarks that were too slow,
+    /// or [`ThroughputGateError::Io`] if the results could not be read.
+    /// Minimums that could not be checked take precedence over the ones that were not met.
+    pub fn check(&self) -> Result<(), ThroughputGateError> {
+        let results: Vec<_> = results::load_results(&self.output_directory)?
+            .into_iter()
+            .filter(|r| r.is_fresh(self.created_at))
+            .collect();
+        let mut missing_results = vec![];
+        let mut missing_throughput = vec![];
+        let mut violations = vec![];
+
+        for (id, minimum) in &self.minimums {
+            let result = match results.iter().find(|r| r.full_id == *id) {
+                Some(result) => result,
+                None => {
+                    missing_results.push(id.clone());
+                    continue;
+                }
+            };
+            match result.typical_units_per_second() {
+                Some(measured) if measured < *minimum => violations.push(GateViolation {
+                    result: result.clone(),
+                    minimum: *minimum,
+                    measured,
+                }),
+                Some(_) => (),
+                None => missing_throughput.push(result.clone()),
+            }
+        }
+
+        if !missing_results.is_empty() {
+            Err(ThroughputGateError::MissingResults(missing_results))
+        } else if !missing_throughput.is_empty() {
+            Err(ThroughputGateError::MissingThroughput(missing_throughput))
+        } else if !violations.is_empty() {
+            Err(ThroughputGateError::BelowMinimum(violations))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check all minimums and panic if any of them is not met.
+    ///
+    /// # Panics
+    ///
+    /// If [`ThroughputGate::check`] returns an error.
+    pub fn assert(&self) {
+        if let Err(err) = self.check() {
+            panic!("{}", err)
+        }
+    }
+}
+
+impl Display for GateViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let throughput = self
+            .result
+            .throughput
+            .as_ref()
+            .expect("violations are only recorded for benchmarks with throughput");
+
+        write!(
+            f,
+            "{}: measured {}, required at least {}",
+            self.result.full_id,
+            format::units_per_second(throughput, self.measured),
+            format::units_per_second(throughput, self.minimum),
+        )
+    }
+}
+
+impl Display for ThroughputGateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThroughputGateError::Io(err) => write!(f, "failed to read benchmark results: {}", err),
+            ThroughputGateError::MissingResults(ids) => write!(
+                f,
+                "no fresh results of benchmarks with a minimum throughput: {}",
+                ids.join(", ")
+            ),
+            ThroughputGateError::MissingThroughput(results) => {
+                let ids: Vec<_> = results.iter().map(|r| r.full_id.as_str()).collect();
+                write!(
+                    f,
+                    "benchmarks with a minimum throughput have no throughput: {}",
+                    ids.join(", ")
+                )
+            }
+            ThroughputGateError::BelowMinimum(violations) => {
+                write!(f, "benchmarks below minimum throughput:")?;
+                for violation in violations {
+                    write!(f, "\n  {}", violation)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for ThroughputGateError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ThroughputGateError::Io(err) => Some(err),
+            ThroughputGateError::MissingResults(_)
+            | ThroughputGateError::MissingThroughput(_)
+            | ThroughputGateError::BelowMinimum(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for ThroughputGateError {
+    fn from(err: io::Error) -> Self {
+        ThroughputGateError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::results::test::save_result;
+    use criterion::Throughput;
+    use std::time::Duration;
+
+    #[test]
+    fn check_passes_when_above_minimum() {
+        let dir = tempfile::tempdir().unwrap();
+        let gate = ThroughputGate::new()
+            .output_directory(dir.path())
+            .min_throughput("group/a", 1e9);
+        save_result(
+            dir.path(),
+            "new",
+            "group/a",
+            Some(Throughput::Bytes(2_000)),
+            1_000.0,
+        );
+
+        assert!(gate.check().is_ok());
+    }
+
+    #[test]
+    fn check_fails_when_below_minimum() {
+        let dir = tempfile::tempdir().unwrap();
+        let gate = ThroughputGate::new()
+            .output_directory(dir.path())
+            .min_throughput("group/a", 3e9);
+        save_result(
+            dir.path(),
+            "new",
+            "group/a",
+            Some(Throughput::Bytes(2_000)),
+            1_000.0,
+        );
+
+        let err = gate.check().unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "benchmarks below minimum throughput:\n  group/a: measured 2.0000 GB/s, required at least 3.0000 GB/s"
+        );
+    }
+
+    #[test]
+    fn check_fails_when_no_result_matches() {
+        let dir = tempfile::tempdir().unwrap();
+        let gate = ThroughputGate::new()
+            .output_directory(dir.path())
+            .min_throughput("group/a", 1e9)
+            .min_throughput("group/typo", 1e9);
+        save_result(
+            dir.path(),
+            "new",
+            "group/a",
+            Some(Throughput::Bytes(2_000)),
+            1_000.0,
+        );
+
+        let err = gate.check().unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "no fresh results of benchmarks with a minimum throughput: group/typo"
+        );
+    }
+
+    #[test]
+    fn check_fails_when_result_has_no_throughput() {
+        let dir = tempfile::tempdir().unwrap();
+        let gate = ThroughputGate::new()
+            .output_directory(dir.path())
+            .min_throughput("group/a", 1e9);
+        save_result(dir.path(), "new", "group/a", None, 1_000.0);
+
+        let err = gate.check().unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "benchmarks with a minimum throughput have no throughput: group/a"
+        );
+    }
+
+    #[test]
+    fn check_does_not_compare_stale_results() {
+        let dir = tempfile::tempdir().unwrap();
+        save_result(
+            dir.path(),
+            "new",
+            "group/a",
+            Some(Throughput::Bytes(2_000)),
+            1_000.0,
+        );
+        let mut gate = ThroughputGate::new()
+            .output_directory(dir.path())
+            .min_throughput("group/a", 3e9);
+        gate.created_at += Duration::from_secs(60);
+
+        assert!(matches!(
+            gate.check(),
+            Err(ThroughputGateError::MissingResults(ids)) if ids == ["group/a"]
+        ));
+    }
+}
@@ -0,0 +1,392 @@
+use crate::{DecimalByteMeasurement, IntoBenchmarkId};
+use criterion::{measurement::Measurement, Bencher, BenchmarkGroup, SamplingMode, Throughput};
+use std::{fs, io, path::Path, time::Duration};
+
+/// Extension trait for [`criterion::BenchmarkGroup`] that derives the [`Throughput`]
+/// from the actual size of the benchmark input.
+///
+/// Computing byte counts for [`criterion::BenchmarkGroup::throughput`] by hand is error-prone,
+/// since it is easy to forget to update it when the input changes. The methods of this trait
+/// set [`Throughput::Bytes`] from the input itself.
+///
+/// # Example
+///
+/// ```
+/// use criterion_decimal_throughput::{decimal_byte_measurement, GroupThroughputExt};
+///
+/// let input = vec![0_u8; 1_000_000];
+/// let mut c = decimal_byte_measurement();
+/// let mut group = c.benchmark_group("example_name");
+/// group.throughput_of(&input);
+///
+/// // Add your benchmarks to the group here...
+///
+/// group.finish();
+/// ```
+pub trait GroupThroughputExt {
+    /// Measurement used by the group.
+    type Measurement: Measurement;
+
+    /// Set the throughput of the group to the length of the given bytes.
+    fn throughput_of<T: AsRef<[u8]> + ?Sized>(&mut self, input: &T) -> &mut Self;
+
+    /// Set the throughput of the group to the length of the given string in bytes.
+    ///
+    /// Note that this is the length of the UTF-8 encoding, not the number of characters.
+    fn throughput_of_str(&mut self, input: &str) -> &mut Self;
+
+    /// Set the throughput of the group to the size of the file at the given path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the metadata of the file cannot be read,
+    /// for example when the file does not exist.
+    fn throughput_of_file<P: AsRef<Path>>(&mut self, path: P) -> io::Result<&mut Self>;
+
+    /// Set the throughput of the group to [`Throughput::Bytes`] of a count of any integer type,
+    /// e.g. a `usize` length or a [`NonZeroU64`](std::num::NonZeroU64).
+    ///
+    /// # Panics
+    ///
+    /// If the count does not fit in a `u64`, e.g. when it is negative.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion_decimal_throughput::{decimal_byte_measurement, GroupThroughputExt};
+    ///
+    /// let input = vec![0_u32; 1_000];
+    /// let mut c = decimal_byte_measurement();
+    /// let mut group = c.benchmark_group("example_name");
+    /// group.throughput_bytes(std::mem::size_of_val(&input[..]));
+    ///
+    /// // Add your benchmarks to the group here...
+    ///
+    /// group.finish();
+    /// ```
+    fn throughput_bytes<N: TryInto<u64>>(&mut self, bytes: N) -> &mut Self;
+
+    /// Set the throughput of the group to [`Throughput::Elements`] of a count of any integer type,
+    /// e.g. a `usize` length or a [`NonZeroU64`](std::num::NonZeroU64).
+    ///
+    /// # Panics
+    ///
+    /// If the count does not fit in a `u64`, e.g. when it is negative.
+    fn throughput_elements<N: TryInto<u64>>(&mut self, elements: N) -> &mut Self;
+
+    /// Set the throughput of the group to `bytes` and configure its sampling so that every sample
+    /// processes at least [`SampleVolume::bytes_per_sample`], e.g. 1 GB.
+    ///
+    /// This keeps the variance of benchmarks comparable when their inputs range from kilobytes
+    /// to gigabytes. The group is switched to [`SamplingMode::Flat`], so all samples run the same
+    /// number of iterations, and its sample size and measurement time are set from the
+    /// [`SampleVolume`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion_decimal_throughput::{decimal_byte_measurement, GroupThroughputExt, SampleVolume};
+    ///
+    /// let mut c = decimal_byte_measurement();
+    /// let mut group = c.benchmark_group("checksum");
+    /// // Inputs of 1 KB, so every sample runs at least a million iterations.
+    /// group.throughput_with_sample_volume(
+    ///     1_000,
+    ///     SampleVolume {
+    ///         bytes_per_sample: 1_000_000_000,
+    ///         samples: 10,
+    ///         estimated_bytes_per_second: 10e9,
+    ///     },
+    /// );
+    ///
+    /// // Add your benchmarks to the group here...
+    ///
+    /// group.finish();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `bytes` is zero or the [`SampleVolume`] is invalid, see [`SampleVolume::measurement_time`].
+    fn throughput_with_sample_volume(&mut self, bytes: u64, volume: SampleVolume) -> &mut Self;
+
+    /// Set the throughput of the group to the number of bytes computed from the input
+    /// and benchmark the function with it, as with [`criterion::BenchmarkGroup::bench_with_input`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion::BenchmarkId;
+    /// use criterion_decimal_throughput::{Criterion, GroupThroughputExt};
+    ///
+    /// fn example_bench(c: &mut Criterion) {
+    ///     let mut group = c.benchmark_group("sum");
+    ///     for size in [1_000, 1_000_000] {
+    ///         let input = vec![1_u64; size];
+    ///         group.bench_with_input_throughput(
+    ///             BenchmarkId::from_parameter(size),
+    ///             &input,
+    ///             |input| (input.len() * std::mem::size_of::<u64>()) as u64,
+    ///             |b, input| b.iter(|| input.iter().sum::<u64>()),
+    ///         );
+    ///     }
+    ///     group.finish();
+    /// }
+    /// ```
+    fn bench_with_input_throughput<ID, I, T, F>(
+        &mut self,
+        id: ID,
+        input: &I,
+        bytes: T,
+        f: F,
+    ) -> &mut Self
+    where
+        ID: IntoBenchmarkId,
+        I: ?Sized,
+        T: FnOnce(&I) -> u64,
+        F: FnMut(&mut Bencher<'_, Self::Measurement>, &I);
+}
+
+/// Fractional number of bytes or elements processed in one iteration, used with
+/// [`ThroughputGroup::throughput_fractional_bytes`](crate::ThroughputGroup::throughput_fractional_bytes) and
+/// [`ThroughputGroup::throughput_fractional_elements`](crate::ThroughputGroup::throughput_fractional_elements).
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct FractionalThroughput(pub f64);
+
+impl FractionalThroughput {
+    /// Integral count closest to this one that Criterion.rs is given instead.
+    pub(crate) fn rounded(self) -> u64 {
+        assert!(
+            self.0.is_finite() && self.0 > 0.0,
+            "fractional throughput must be positive and finite"
+        );
+        // Casting saturates, so counts above u64::MAX become u64::MAX.
+        (self.0.round() as u64).max(1)
+    }
+}
+
+/// Throughput given both in bytes and in elements, used with
+/// [`ThroughputGroup::throughput_combined`](crate::ThroughputGroup::throughput_combined).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CombinedThroughput {
+    /// Number of bytes processed in one iteration.
+    pub bytes: u64,
+    /// Number of elements processed in one iteration.
+    pub elements: u64,
+}
+
+/// Throughput given as separate numbers of bytes read and written, used with
+/// [`ThroughputGroup::throughput_directional`](crate::ThroughputGroup::throughput_directional).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DirectionalThroughput {
+    /// Number of bytes read, or received, in one iteration.
+    pub read: u64,
+    /// Number of bytes written, or sent, in one iteration.
+    pub write: u64,
+}
+
+/// Target amount of data processed in every sample of a benchmark, used with
+/// [`GroupThroughputExt::throughput_with_sample_volume`].
+///
+/// Criterion.rs decides the number of iterations from the measurement time, so the time needed
+/// for the volume is computed from a rough estimate of the throughput, e.g. from a previous run.
+/// If the benchmark turns out slower, Criterion.rs raises the number of iterations to one per
+/// sample and warns that the measurement time is too short.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleVolume {
+    /// Minimum number of bytes processed in one sample.
+    pub bytes_per_sample: u64,
+    /// Number of samples, at least 10 as required by Criterion.rs.
+    pub samples: usize,
+    /// Estimated throughput of the benchmarks in bytes per second.
+    pub estimated_bytes_per_second: f64,
+}
+
+impl SampleVolume {
+    /// Number of iterations processing `bytes` each that make up one sample.
+    ///
+    /// # Panics
+    ///
+    /// If `bytes` is zero.
+    pub fn iterations_per_sample(&self, bytes: u64) -> u64 {
+        assert!(bytes > 0, "number of bytes must be positive");
+        self.bytes_per_sample.div_ceil(bytes).max(1)
+    }
+
+    /// Measurement time in which all samples of iterations processing `bytes` each
+    /// reach the volume at the estimated throughput.
+    ///
+    /// # Panics
+    ///
+    /// If `bytes` is zero, there are fewer than 10 samples,
+    /// or the estimated throughput is not positive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion_decimal_throughput::SampleVolume;
+    /// use std::time::Duration;
+    ///
+    /// let volume = SampleVolume {
+    ///     bytes_per_sample: 1_000_000_000,
+    ///     samples: 10,
+    ///     estimated_bytes_per_second: 2e9,
+    /// };
+    ///
+    /// assert_eq!(volume.measurement_time(1_000), Duration::from_secs(5));
+    /// ```
+    pub fn measurement_time(&self, bytes: u64) -> Duration {
+        assert!(self.samples >= 10, "number of samples must be at least 10");
+        assert!(
+            self.estimated_bytes_per_second > 0.0,
+            "estimated throughput must be positive"
+        );
+        let bytes_per_sample = self.iterations_per_sample(bytes) as f64 * bytes as f64;
+
+        Duration::from_secs_f64(
+            self.samples as f64 * bytes_per_sample / self.estimated_bytes_per_second,
+        )
+    }
+}
+
+impl<'a, M: Measurement> GroupThroughputExt for BenchmarkGroup<'a, DecimalByteMeasurement<M>> {
+    type Measurement = DecimalByteMeasurement<M>;
+
+    fn throughput_of<T: AsRef<[u8]> + ?Sized>(&mut self, input: &T) -> &mut Self {
+        self.throughput(Throughput::Bytes(input.as_ref().len() as u64))
+    }
+
+    fn throughput_of_str(&mut self, input: &str) -> &mut Self {
+        self.throughput_of(input)
+    }
+
+    fn throughput_of_file<P: AsRef<Path>>(&mut self, path: P) -> io::Result<&mut Self> {
+        let metadata = fs::metadata(path)?;
+        Ok(self.throughput(Throughput::Bytes(metadata.len())))
+    }
+
+    fn throughput_bytes<N: TryInto<u64>>(&mut self, bytes: N) -> &mut Self {
+        let bytes = bytes
+            .try_into()
+            .unwrap_or_else(|_| panic!("number of bytes must fit in a u64"));
+        self.throughput(Throughput::Bytes(bytes))
+    }
+
+    fn throughput_elements<N: TryInto<u64>>(&mut self, elements: N) -> &mut Self {
+        let elements = elements
+            .try_into()
+            .unwrap_or_else(|_| panic!("number of elements must fit in a u64"));
+        self.throughput(Throughput::Elements(elements))
+    }
+
+    fn throughput_with_sample_volume(&mut self, bytes: u64, volume: SampleVolume) -> &mut Self {
+        let measurement_time = volume.measurement_time(bytes);
+
+        self.sampling_mode(SamplingMode::Flat)
+            .sample_size(volume.samples)
+            .measurement_time(measurement_time)
+            .throughput(Throughput::Bytes(bytes))
+    }
+
+    fn bench_with_input_throughput<ID, I, T, F>(
+        &mut self,
+        id: ID,
+        input: &I,
+        bytes: T,
+        f: F,
+    ) -> &mut Self
+    where
+        ID: IntoBenchmarkId,
+        I: ?Sized,
+        T: FnOnce(&I) -> u64,
+        F: FnMut(&mut Bencher<'_, Self::Measurement>, &I),
+    {
+        self.throughput(Throughput::Bytes(bytes(input)));
+        id.bench_with_input(self, input, f);
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{decimal_byte_measurement, results, test_criterion};
+    use std::{num::NonZeroU64, time::Duration};
+
+    #[test]
+    fn throughput_of_file_nonexistent_file_is_error() {
+        let mut c = decimal_byte_measurement();
+        let mut group = c.benchmark_group("test");
+
+        let result = group.throughput_of_file("this/file/does/not/exist");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn throughput_bytes_accepts_integer_types() {
+        let mut c = decimal_byte_measurement();
+        let mut group = c.benchmark_group("test");
+
+        group.throughput_bytes(3_usize);
+        group.throughput_elements(4_u32);
+        group.throughput_bytes(NonZeroU64::new(5).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "number of elements must fit in a u64")]
+    fn throughput_elements_negative_count_panics() {
+        let mut c = decimal_byte_measurement();
+        let mut group = c.benchmark_group("test");
+
+        group.throughput_elements(-1_i64);
+    }
+
+    #[test]
+    fn sample_volume_covers_inputs_smaller_and_larger_than_volume() {
+        let volume = SampleVolume {
+            bytes_per_sample: 1_000_000,
+            samples: 20,
+            estimated_bytes_per_second: 1e9,
+        };
+
+        assert_eq!(volume.iterations_per_sample(300_000), 4);
+        assert_eq!(volume.measurement_time(300_000), Duration::from_millis(24));
+        assert_eq!(volume.iterations_per_sample(5_000_000), 1);
+        assert_eq!(
+            volume.measurement_time(5_000_000),
+            Duration::from_millis(100)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "number of samples must be at least 10")]
+    fn sample_volume_too_few_samples_panics() {
+        let volume = SampleVolume {
+            bytes_per_sample: 1_000,
+            samples: 5,
+            estimated_bytes_per_second: 1e9,
+        };
+
+        volume.measurement_time(1_000);
+    }
+
+    #[test]
+    fn bench_with_input_throughput_sets_throughput_from_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut c = test_criterion(dir.path());
+        let mut group = c.benchmark_group("test");
+        let input = [1_u8, 2, 3];
+
+        group.bench_with_input_throughput(
+            "sum",
+            &input,
+            |input| input.len() as u64,
+            |b, input| b.iter(|| input.iter().sum::<u8>()),
+        );
+        group.finish();
+
+        let results = results::load_results(dir.path()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].throughput, Some(Throughput::Bytes(3)));
+    }
+}
@@ -0,0 +1,319 @@
+//! History of throughput across runs.
+//!
+//! Criterion.rs only keeps a single baseline to compare against. [`History`] appends the
+//! throughput of every run to a file, tagged with the time the results were saved and the
+//! current git commit, so that trends over many runs can be tracked.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use criterion_decimal_throughput::{history::{self, History}, results};
+//!
+//! // After the benchmarks finish.
+//! let history = History::new();
+//! history.record(&results::load_results(results::default_output_directory())?)?;
+//! print!("{}", history::trend_table(&history.load()?));
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::{format, results::BenchmarkResult};
+use criterion::Throughput;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    time::UNIX_EPOCH,
+};
+
+/// Name of the history file in the output directory of Criterion.rs.
+const HISTORY_FILE: &str = "decimal-throughput-history.jsonl";
+
+/// Throughput of a single benchmark in a single run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Full identifier of the benchmark, e.g. `group/function`.
+    pub full_id: String,
+    /// Time at which the results were saved, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Git commit the benchmarks were run at, if known.
+    pub commit: Option<String>,
+    /// Throughput configured for the benchmark.
+    pub throughput: Throughput,
+    /// Typical throughput in base units per second.
+    pub units_per_second: f64,
+}
+
+/// File with the [`HistoryEntry`] of every recorded run, one JSON object per line.
+#[derive(Debug, Clone)]
+pub struct History {
+    path: PathBuf,
+    commit: Option<String>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl History {
+    /// History stored in the [default output directory](crate::results::default_output_directory),
+    /// tagged with the commit reported by `git rev-parse --short HEAD`.
+    pub fn new() -> Self {
+        Self::at(crate::results::default_output_directory().join(HISTORY_FILE))
+    }
+
+    /// History stored in the given file, tagged with the commit reported by `git rev-parse --short HEAD`.
+    pub fn at<P: AsRef<Path>>(path: P) -> Self {
+        History {
+            path: path.as_ref().to_owned(),
+            commit: current_commit(),
+        }
+    }
+
+    /// Tag recorded entries with the given commit instead, e.g. one provided by the CI.
+    pub fn with_commit<S: Into<String>>(mut self, commit: S) -> Self {
+        self.commit = Some(commit.into());
+        self
+    }
+
+    /// Append the throughput of the given results to the history.
+    ///
+    /// Results that are already recorded, i.e. with the same id and time of saving,
+    /// are skipped, so recording all results after running only some of the benchmarks
+    /// does not duplicate the others. Results without throughput are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history cannot be read or written.
+    pub fn record(&self, results: &[BenchmarkResult]) -> io::Result<()> {
+        let existing = self.load()?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+
+        for result in results {
+            let (throughput, units_per_second) =
+                match (&result.throughput, result.typical_units_per_second()) {
+                    (Some(throughput), Some(units_per_second)) => (throughput, units_per_second),
+                    _ => continue,
+                };
+            let timestamp = result
+                .saved_at
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            let recorded = existing
+                .iter()
+                .any(|e| e.full_id == result.full_id && e.timestamp == timestamp);
+            if recorded {
+                continue;
+            }
+
+            let entry = HistoryEntry {
+                full_id: result.full_id.clone(),
+                timestamp,
+                commit: self.commit.clone(),
+                throughput: throughput.clone(),
+                units_per_second,
+            };
+            serde_json::to_writer(&mut file, &entry)?;
+            writeln!(file)?;
+        }
+
+        Ok(())
+    }
+
+    /// Load all recorded entries, in the order they were recorded.
+    ///
+    /// A history file that does not exist yet is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history cannot be read or is malformed.
+    pub fn load(&self) -> io::Result<Vec<HistoryEntry>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err),
+        };
+
+        let mut entries = vec![];
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// A Markdown table of the trend of throughput of each benchmark over the recorded runs.
+///
+/// Every benchmark's entries are listed in chronological order, with the change of throughput
+/// relative to the previous entry of the same benchmark.
+pub fn trend_table(entries: &[HistoryEntry]) -> String {
+    let mut entries: Vec<_> = entries.iter().collect();
+    entries.sort_by(|a, b| (&a.full_id, a.timestamp).cmp(&(&b.full_id, b.timestamp)));
+
+    let mut table = String::from("| Benchmark | Saved at (UTC) | Commit | Throughput | Change |\n");
+    table.push_str("|---|---|---|--:|--:|\n");
+    let mut previous: Option<&HistoryEntry> = None;
+
+    for entry in entries {
+        let change = match previous {
+            Some(prev) if prev.full_id == entry.full_id => format!(
+                "{:+.2}%",
+                (entry.units_per_second / prev.units_per_second - 1.0) * 100.0
+            ),
+            _ => "-".to_owned(),
+        };
+        table.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            format::markdown_cell(&entry.full_id),
+            format_timestamp(entry.timestamp),
+            entry.commit.as_deref().unwrap_or("-"),
+            format::units_per_second(&entry.throughput, entry.units_per_second),
+            change
+        ));
+        previous = Some(entry);
+    }
+
+    table
+}
+
+fn current_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let commit = String::from_utf8(output.stdout).ok()?;
+    Some(commit.trim().to_owned()).filter(|c| !c.is_empty())
+}
+
+/// Format seconds since the Unix epoch as `YYYY-MM-DD HH:MM`.
+fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds_of_day = timestamp % 86_400;
+
+    // Civil date from days since the epoch, see http://howardhinnant.github.io/date_algorithms.html.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::results::{self, test::save_result};
+
+    #[test]
+    fn record_skips_already_recorded_results() {
+        let dir = tempfile::tempdir().unwrap();
+        save_result(
+            dir.path(),
+            "new",
+            "group/a",
+            Some(Throughput::Bytes(2_000)),
+            1_000.0,
+        );
+        let results = results::load_results(dir.path()).unwrap();
+        let history = History::at(dir.path().join("history.jsonl")).with_commit("abc123");
+
+        history.record(&results).unwrap();
+        history.record(&results).unwrap();
+        let entries = history.load().unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].full_id, "group/a");
+        assert_eq!(entries[0].commit.as_deref(), Some("abc123"));
+        assert_eq!(entries[0].units_per_second, 2e9);
+    }
+
+    #[test]
+    fn load_nonexistent_history_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let entries = History::at(dir.path().join("history.jsonl"))
+            .load()
+            .unwrap();
+
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn trend_table_rows() {
+        let entry = |timestamp, commit: &str, units_per_second| HistoryEntry {
+            full_id: "group/a".to_owned(),
+            timestamp,
+            commit: Some(commit.to_owned()),
+            throughput: Throughput::Bytes(1_000),
+            units_per_second,
+        };
+        let entries = [
+            entry(1_700_003_600, "def", 2.5e9),
+            entry(1_700_000_000, "abc", 2e9),
+        ];
+
+        let table = trend_table(&entries);
+
+        assert_eq!(
+            table,
+            "| Benchmark | Saved at (UTC) | Commit | Throughput | Change |\n\
+             |---|---|---|--:|--:|\n\
+             | group/a | 2023-11-14 22:13 | abc | 2.0000 GB/s | - |\n\
+             | group/a | 2023-11-14 23:13 | def | 2.5000 GB/s | +25.00% |\n"
+        );
+    }
+
+    #[test]
+    fn trend_table_escapes_pipes() {
+        let entries = [HistoryEntry {
+            full_id: "group/a|b".to_owned(),
+            timestamp: 1_700_000_000,
+            commit: None,
+            throughput: Throughput::Bytes(1_000),
+            units_per_second: 2e9,
+        }];
+
+        let table = trend_table(&entries);
+
+        assert!(table.ends_with("| group/a\\|b | 2023-11-14 22:13 | - | 2.0000 GB/s | - |\n"));
+    }
+
+    #[test]
+    fn format_timestamp_epoch_and_leap_day() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00");
+    }
+}
@@ -0,0 +1,122 @@
+use criterion::{measurement::Measurement, Bencher, BenchmarkGroup, BenchmarkId};
+use std::fmt::Display;
+
+/// Identifier of a benchmark accepted by the methods of this crate, so a [`BenchmarkId`],
+/// a [`DecimalBenchmarkId`], a [`String`] or a `&str`.
+///
+/// This mirrors the trait Criterion.rs uses for [`BenchmarkGroup::bench_function`],
+/// which cannot be named outside of it.
+pub trait IntoBenchmarkId: private::Sealed {
+    #[doc(hidden)]
+    fn bench_function<M, F>(self, group: &mut BenchmarkGroup<'_, M>, f: F)
+    where
+        M: Measurement,
+        F: FnMut(&mut Bencher<'_, M>);
+
+    #[doc(hidden)]
+    fn bench_with_input<M, I, F>(self, group: &mut BenchmarkGroup<'_, M>, input: &I, f: F)
+    where
+        M: Measurement,
+        I: ?Sized,
+        F: FnMut(&mut Bencher<'_, M>, &I);
+
+    #[doc(hidden)]
+    fn key(&self) -> Option<String>;
+}
+
+/// [`BenchmarkId`] whose parts are known to this crate, so that the
+/// [unit override](crate::DecimalByteMeasurement::with_unit_override) of the benchmark applies.
+///
+/// Criterion.rs does not expose the parts of a [`BenchmarkId`], so benchmarks of
+/// a [`ThroughputGroup`](crate::ThroughputGroup) identified by one cannot be overridden.
+///
+/// # Example
+///
+/// ```
+/// use criterion_decimal_throughput::{
+///     Criterion, DecimalBenchmarkId, GroupThroughputExt, ThroughputGroup,
+/// };
+///
+/// fn example_bench(c: &mut Criterion) {
+///     let mut group = ThroughputGroup::new(c, "sum");
+///     for size in [1_000, 1_000_000] {
+///         let input = vec![1_u8; size];
+///         group.throughput_of(&input);
+///         // Overridden with the full id `sum/fold/1000`.
+///         group.bench_with_input(DecimalBenchmarkId::new("fold", size), &input, |b, input| {
+///             b.iter(|| input.iter().map(|&x| u64::from(x)).sum::<u64>())
+///         });
+///     }
+///     group.finish();
+/// }
+/// ```
+#[derive(Clone)]
+pub struct DecimalBenchmarkId {
+    id: BenchmarkId,
+    key: String,
+}
+
+impl DecimalBenchmarkId {
+    /// Construct an id from the name of the function and the parameter,
+    /// as with [`BenchmarkId::new`].
+    pub fn new<S: Into<String>, P: Display>(function_name: S, parameter: P) -> Self {
+        let function_name = function_name.into();
+        let key = format!("{}/{}", function_name, parameter);
+        DecimalBenchmarkId {
+            id: BenchmarkId::new(function_name, parameter),
+            key,
+        }
+    }
+
+    /// Construct an id from the parameter only, as with [`BenchmarkId::from_parameter`].
+    pub fn from_parameter<P: Display>(parameter: P) -> Self {
+        DecimalBenchmarkId {
+            key: parameter.to_string(),
+            id: BenchmarkId::from_parameter(parameter),
+        }
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for criterion::BenchmarkId {}
+    impl Sealed for super::DecimalBenchmarkId {}
+    impl Sealed for String {}
+    impl Sealed for &str {}
+}
+
+macro_rules! impl_into_benchmark_id {
+    ($type:ty, |$id:ident| $criterion_id:expr, |$key_id:ident| $key:expr) => {
+        impl IntoBenchmarkId for $type {
+            fn bench_function<M, F>(self, group: &mut BenchmarkGroup<'_, M>, f: F)
+            where
+                M: Measurement,
+                F: FnMut(&mut Bencher<'_, M>),
+            {
+                let $id = self;
+                group.bench_function($criterion_id, f);
+            }
+
+            fn bench_with_input<M, I, F>(self, group: &mut BenchmarkGroup<'_, M>, input: &I, f: F)
+            where
+                M: Measurement,
+                I: ?Sized,
+                F: FnMut(&mut Bencher<'_, M>, &I),
+            {
+                let $id = self;
+                group.bench_with_input($criterion_id, input, f);
+            }
+
+            fn key(&self) -> Option<String> {
+                let $key_id = self;
+                $key
+            }
+        }
+    };
+}
+
+// Criterion.rs does not expose the parts of a `BenchmarkId`.
+impl_into_benchmark_id!(BenchmarkId, |id| id, |_id| None);
+impl_into_benchmark_id!(DecimalBenchmarkId, |id| id.id, |id| Some(id.key.clone()));
+impl_into_benchmark_id!(String, |id| id, |id| Some(id.clone()));
+impl_into_benchmark_id!(&str, |id| id, |id| Some(id.to_string()));
@@ -0,0 +1,68 @@
+use criterion::Throughput;
+use std::mem;
+
+/// [`Throughput::Elements`] of the number of items remaining in the iterator.
+///
+/// # Example
+///
+/// ```
+/// use criterion::Throughput;
+/// use criterion_decimal_throughput::throughput_from_len;
+///
+/// let words = "a quick brown fox".split(' ').collect::<Vec<_>>();
+///
+/// assert_eq!(throughput_from_len(&words.iter()), Throughput::Elements(4));
+/// ```
+pub fn throughput_from_len<I: ExactSizeIterator>(iter: &I) -> Throughput {
+    Throughput::Elements(iter.len() as u64)
+}
+
+/// [`Throughput::Elements`] of the number of items in the slice.
+///
+/// # Example
+///
+/// ```
+/// use criterion::Throughput;
+/// use criterion_decimal_throughput::elements_of;
+///
+/// assert_eq!(elements_of(&[1_u32, 2, 3]), Throughput::Elements(3));
+/// ```
+pub fn elements_of<T>(slice: &[T]) -> Throughput {
+    Throughput::Elements(slice.len() as u64)
+}
+
+/// [`Throughput::Bytes`] of the memory taken by the items of the slice,
+/// so its length multiplied by the size of `T`.
+///
+/// # Example
+///
+/// ```
+/// use criterion::Throughput;
+/// use criterion_decimal_throughput::bytes_of_slice;
+///
+/// assert_eq!(bytes_of_slice(&[1_u32, 2, 3]), Throughput::Bytes(12));
+/// ```
+pub fn bytes_of_slice<T>(slice: &[T]) -> Throughput {
+    Throughput::Bytes(mem::size_of_val(slice) as u64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn throughput_from_len_counts_remaining_items() {
+        let mut iter = [1, 2, 3].iter();
+        iter.next();
+
+        assert_eq!(throughput_from_len(&iter), Throughput::Elements(2));
+    }
+
+    #[test]
+    fn bytes_of_slice_multiplies_by_item_size() {
+        let items = [(0_u64, 0_u64); 5];
+
+        assert_eq!(bytes_of_slice(&items), Throughput::Bytes(80));
+        assert_eq!(elements_of(&items), Throughput::Elements(5));
+    }
+}
@@ -9,7 +9,7 @@
 //! You need to:
 //!
 //! 1. Use the custom measurement type [`criterion_decimal_throughput::Criterion`](Criterion) from this crate,
-//! exposed with the [`decimal_byte_measurement`] function.
+//!    exposed with the [`decimal_byte_measurement`] function.
 //! 2. Enable throughput measurement in the benchmark group with [`criterion::BenchmarkGroup::throughput`].
 //!
 //! ### Example
@@ -67,6 +67,50 @@
 //! criterion_main!(example);
 //! ```
 //!
+//! The same can be written as `.with_decimal_throughput()` with the [`DecimalThroughputExt`] extension trait.
+//!
+//! ### Deriving throughput from inputs
+//!
+//! Instead of computing the byte count passed to [`criterion::Throughput::Bytes`] by hand,
+//! you can use the [`GroupThroughputExt`] extension trait to set it from the actual input:
+//!
+//! ```
+//! use criterion_decimal_throughput::{Criterion, GroupThroughputExt};
+//!
+//! fn example_bench(c: &mut Criterion) {
+//!     let input = "some input to parse";
+//!     let mut group = c.benchmark_group("example_name");
+//!     group.throughput_of_str(input);
+//!
+//!     // Add your benchmarks to the group here...
+//!
+//!     group.finish();
+//! }
+//! ```
+//!
+//! For throughput that needs more than a count to be displayed, e.g. both bytes and elements,
+//! wrap the group in a [`ThroughputGroup`].
+//!
+//! ### Measured bytes
+//!
+//! When the number of bytes is only known while the benchmark runs, it can be measured instead:
+//! counted with the [`counter`], observed on generated inputs with [`BatchedThroughputExt`],
+//! returned by the routine with [`DynamicThroughputExt`], or returned by asynchronous routines
+//! with `AsyncThroughputExt` (requires the `async` feature).
+//! The group still needs a [`criterion::Throughput::Bytes`] for the throughput to be reported at all,
+//! but its value is replaced by the measured average in the console output and in the HTML report
+//! and plots of Criterion.rs.
+//! Criterion.rs still saves the configured value with the results, and reports reading them,
+//! e.g. [`CompactReport`], use it, so set it to a representative size, e.g. of a typical input.
+//!
+//! ### Newer versions of Criterion.rs
+//!
+//! This crate depends on Criterion.rs 0.3. With the `criterion04` or `criterion05` features,
+//! [`DecimalByteMeasurement`] of any time measurement also implements the measurement traits
+//! of Criterion.rs 0.4 or 0.5, so that it can be registered with their `Criterion::with_measurement`.
+//! The extension traits, the [`ThroughputGroup`] and the post-run reports work only with 0.3.
+//! Criterion.rs 0.6 and later are not supported.
+//!
 //! ## Origin
 //!
 //! Related criterion.rs issue: <https://github.com/bheisler/criterion.rs/issues/581>.
@@ -81,13 +125,287 @@
     unused_lifetimes
 )]
 
+#[cfg(feature = "alloc_stats")]
+mod allocation;
+#[cfg(feature = "async")]
+mod asynchronous;
+mod batched;
+mod compact;
+#[cfg(any(feature = "criterion04", feature = "criterion05"))]
+mod compat;
+mod context;
+pub mod counter;
+#[cfg(feature = "cpu_time")]
+mod cpu_time;
+#[cfg(feature = "cycles")]
+mod cycles;
+mod decorator;
+#[cfg(feature = "divan")]
+pub mod divan;
+mod dynamic;
+#[cfg(all(feature = "energy", target_os = "linux"))]
+mod energy;
+mod environment;
+mod exact;
+mod format;
+mod gate;
+mod group;
+pub mod history;
+mod id;
+mod lengths;
+#[cfg(feature = "locale")]
+mod locale;
+mod parameterized;
+#[cfg(all(feature = "perf_event", target_os = "linux"))]
+mod perf;
+#[cfg(feature = "plots")]
+pub mod plots;
+pub mod report;
+pub mod results;
+mod run;
+#[cfg(feature = "test_util")]
+pub mod testing;
+mod throughput_group;
+pub mod units;
+#[cfg(feature = "wasm")]
+mod wasm;
+
 use criterion::{
     measurement::{Measurement, ValueFormatter, WallTime},
     Throughput,
 };
+use decorator::Undecorated;
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display},
+    mem,
+    str::FromStr,
+};
+use units::{Multiple, Period, Statistic, TimeUnit, Unit, UnitOverride, UnitSystem};
+
+#[cfg(feature = "alloc_stats")]
+pub use allocation::{allocated_bytes, CountingAllocator};
+#[cfg(feature = "async")]
+pub use asynchronous::AsyncThroughputExt;
+pub use batched::{BatchedGroupThroughputExt, BatchedThroughputExt};
+pub use compact::CompactReport;
+#[cfg(feature = "cpu_time")]
+pub use cpu_time::{CpuTime, DecimalByteCpuTime};
+#[cfg(feature = "cycles")]
+pub use cycles::{DecimalCycleMeasurement, DecimalWallCycleMeasurement};
+pub use decorator::FormatterDecorator;
+pub use dynamic::DynamicThroughputExt;
+#[cfg(all(feature = "energy", target_os = "linux"))]
+pub use energy::DecimalEnergyMeasurement;
+pub use gate::{GateViolation, ThroughputGate, ThroughputGateError};
+pub use group::{
+    CombinedThroughput, DirectionalThroughput, FractionalThroughput, GroupThroughputExt,
+    SampleVolume,
+};
+pub use id::{DecimalBenchmarkId, IntoBenchmarkId};
+pub use lengths::{bytes_of_slice, elements_of, throughput_from_len};
+#[cfg(feature = "locale")]
+pub use locale::Locale;
+pub use parameterized::DecimalBenchmarkGroup;
+#[cfg(all(feature = "perf_event", target_os = "linux"))]
+pub use perf::{DecimalPerfEventMeasurement, PerfEvent};
+pub use run::RunReport;
+pub use throughput_group::ThroughputGroup;
+#[cfg(feature = "wasm")]
+pub use wasm::{DecimalByteWasmTime, WasmInstant, WasmTime};
+
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub use criterion as __criterion;
+// Macro expansions refer to the crate by name, which has to resolve in its own tests as well.
+#[cfg(all(test, feature = "macros"))]
+extern crate self as criterion_decimal_throughput;
+/// Declare a benchmark with the [`DecimalByteMeasurement`] and its throughput configured,
+/// turning a plain benchmark routine into a `fn(&mut Criterion)` that can be listed
+/// in the targets of [`criterion::criterion_group`].
+///
+/// The routine takes a `&mut Bencher` and, optionally, a reference to the input.
+/// The attribute accepts:
+///
+/// - `bytes = "..."` or `elements = "..."` &ndash; an expression evaluating to the number of
+///   bytes or elements processed in an iteration, which can refer to the input argument by name;
+/// - `input = "..."` &ndash; an expression creating the input, required if the routine takes one;
+/// - `group = "..."` and `id = "..."` &ndash; names of the benchmark group and function,
+///   both defaulting to the name of the routine.
+///
+/// Requires the `macros` feature.
+///
+/// # Example
+///
+/// ```
+/// use criterion::{criterion_group, criterion_main, Bencher};
+/// use criterion_decimal_throughput::{decimal_bench, decimal_byte_measurement, DecimalByteMeasurement};
+///
+/// #[decimal_bench(bytes = "input.len()", input = "vec![0_u8; 1_000]")]
+/// fn sum(b: &mut Bencher<DecimalByteMeasurement>, input: &[u8]) {
+///     b.iter(|| input.iter().map(|&x| u64::from(x)).sum::<u64>())
+/// }
+///
+/// criterion_group!(
+///     name = example;
+///     config = decimal_byte_measurement();
+///     targets = sum
+/// );
+/// criterion_main!(example);
+/// ```
+#[cfg(feature = "macros")]
+pub use criterion_decimal_throughput_macros::decimal_bench;
 
 /// Measurement type for decimal multiple-byte units.
-pub struct DecimalByteMeasurement(WallTime);
+///
+/// By default it measures [`WallTime`]. Any other [`Measurement`] producing values in nanoseconds
+/// can be used instead with [`DecimalByteMeasurement::from_time_measurement`].
+///
+/// # Degenerate values
+///
+/// A throughput of zero units, e.g. [`Throughput::Bytes(0)`](Throughput::Bytes), is a rate of zero
+/// displayed in base units per second, `0.0000  B/s`. A time that is not positive, e.g. a slope estimate
+/// of zero nanoseconds for a routine optimized away, has no defined throughput: it is formatted as
+/// `n/a  B/s`, scaled to zero, and ignored when selecting the unit.
+/// [`with_degenerate_value_checks`](DecimalByteMeasurement::with_degenerate_value_checks)
+/// turns these cases into panics in debug builds.
+pub struct DecimalByteMeasurement<M = WallTime> {
+    time: M,
+    /// Identifies the records of measured bytes taken by this measurement.
+    id: u64,
+    unit_family: UnitFamily,
+    inverse_rate: bool,
+    sub_unit_rates: bool,
+    significant_figures: Option<usize>,
+    rounding_mode: Option<RoundingMode>,
+    label_style: LabelStyle,
+    cores: Option<u64>,
+    unit_threshold: f64,
+    binary_equivalent: bool,
+    element_units: Option<Box<dyn UnitSystem>>,
+    peak: Option<Peak>,
+    time_unit: Option<TimeUnit>,
+    min_unit: Multiple,
+    max_unit: Multiple,
+    /// Whether the unit clamps were set by `CDT_UNIT` and not by code yet.
+    environment_unit: bool,
+    scientific: bool,
+    unit_statistic: Statistic,
+    unit_overrides: HashMap<String, UnitOverride>,
+    bytes_per_element: Option<u64>,
+    elements_as_bytes: bool,
+    missing_throughput: MissingThroughput,
+    degenerate_checks: bool,
+    decorator: Option<Box<dyn FormatterDecorator>>,
+    #[cfg(feature = "locale")]
+    locale: Option<Locale>,
+    #[cfg(feature = "alloc_stats")]
+    allocation_stats: bool,
+}
+
+struct Peak {
+    bytes_per_second: f64,
+    name: String,
+}
+
+/// Family of multiple-byte units used to display throughput.
+///
+/// Can be parsed from a string, which is handy when the choice should be made at runtime,
+/// for example from an environment variable:
+///
+/// ```
+/// use criterion_decimal_throughput::{DecimalByteMeasurement, UnitFamily};
+///
+/// let unit_family = std::env::var("BENCH_UNITS")
+///     .ok()
+///     .and_then(|units| units.parse().ok())
+///     .unwrap_or_default();
+/// let measurement = DecimalByteMeasurement::new().with_unit_family(unit_family);
+/// ```
+///
+/// Criterion.rs rejects command line arguments it does not recognize, so a `--units` flag
+/// cannot be passed to the bench binary directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum UnitFamily {
+    /// Decimal multiples, so KB/s, MB/s, etc.
+    #[default]
+    Decimal,
+    /// Binary multiples, so KiB/s, MiB/s, etc., exactly as reported by [`criterion::measurement::WallTime`].
+    Binary,
+}
+
+/// Error returned when parsing a [`UnitFamily`] from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseUnitFamilyError(String);
+
+impl FromStr for UnitFamily {
+    type Err = ParseUnitFamilyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "decimal" => Ok(UnitFamily::Decimal),
+            "binary" => Ok(UnitFamily::Binary),
+            _ => Err(ParseUnitFamilyError(s.to_owned())),
+        }
+    }
+}
+
+impl Display for ParseUnitFamilyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid unit family '{}', expected 'decimal' or 'binary'",
+            self.0
+        )
+    }
+}
+
+impl Error for ParseUnitFamilyError {}
+
+/// What to do when a benchmark is reported without a [`Throughput`],
+/// set with [`DecimalByteMeasurement::with_missing_throughput`].
+///
+/// Without a throughput, Criterion.rs only reports the time of benchmarks, which is easy to miss
+/// in a long report, e.g. when a group forgets to call [`criterion::BenchmarkGroup::throughput`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MissingThroughput {
+    /// Report the benchmark without throughput.
+    #[default]
+    Ignore,
+    /// Print a warning to the standard error.
+    Warn,
+    /// Panic, failing the run.
+    Panic,
+}
+
+/// How formatted numbers are rounded to their displayed precision,
+/// set with [`DecimalByteMeasurement::with_rounding_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Round to nearest, with ties away from zero, so `0.125` to `0.13`.
+    HalfAwayFromZero,
+    /// Round to nearest, with ties to the even digit, so `0.125` to `0.12`.
+    /// Unlike the other modes to nearest, it has no bias when rounded values are aggregated.
+    HalfEven,
+    /// Round towards negative infinity.
+    Floor,
+    /// Round towards positive infinity.
+    Ceil,
+    /// Round towards zero.
+    Truncate,
+}
+
+/// How labels of units are aligned, set with [`DecimalByteMeasurement::with_label_style`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LabelStyle {
+    /// Pad numbers and labels so that they align in columns, as in Criterion.rs,
+    /// e.g. `2.0000  B/s` and `   1.2 GB/s`.
+    #[default]
+    Padded,
+    /// No padding, e.g. `2.0000 B/s` and `1.2 GB/s`, for throughput embedded in other text.
+    Compact,
+}
 
 /// Shorthand for the criterion manager with [`DecimalByteMeasurement`].
 pub type Criterion = criterion::Criterion<DecimalByteMeasurement>;
@@ -97,6 +415,43 @@ pub fn decimal_byte_measurement() -> Criterion {
     criterion::Criterion::default().with_measurement(DecimalByteMeasurement::new())
 }
 
+/// Manager for tests that run benchmarks, which measures briefly and saves results in `dir`.
+#[cfg(test)]
+pub(crate) fn test_criterion(dir: &std::path::Path) -> Criterion {
+    decimal_byte_measurement()
+        .output_directory(dir)
+        .without_plots()
+        .sample_size(10)
+        .warm_up_time(std::time::Duration::from_millis(1))
+        .measurement_time(std::time::Duration::from_millis(10))
+}
+
+/// Extension trait for [`criterion::Criterion`] that switches an existing configuration
+/// to the [`DecimalByteMeasurement`].
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use criterion_decimal_throughput::{Criterion, DecimalThroughputExt};
+///
+/// pub fn my_custom_config() -> Criterion {
+///     criterion::Criterion::default()
+///         .warm_up_time(Duration::from_secs(10))
+///         .with_decimal_throughput()
+/// }
+/// ```
+pub trait DecimalThroughputExt {
+    /// Measure with a default [`DecimalByteMeasurement`], keeping the rest of the configuration.
+    fn with_decimal_throughput(self) -> Criterion;
+}
+
+impl DecimalThroughputExt for criterion::Criterion<WallTime> {
+    fn with_decimal_throughput(self) -> Criterion {
+        self.with_measurement(DecimalByteMeasurement::new())
+    }
+}
+
 impl Default for DecimalByteMeasurement {
     fn default() -> Self {
         Self::new()
@@ -106,33 +461,666 @@ impl Default for DecimalByteMeasurement {
 impl DecimalByteMeasurement {
     /// Create a new [`DecimalByteMeasurement`] struct.
     pub fn new() -> Self {
-        DecimalByteMeasurement(WallTime)
+        Self::from_time_measurement(WallTime)
+    }
+
+    /// Create a new [`DecimalByteMeasurement`] struct displaying [`Throughput::Elements`]
+    /// in the units of the given [`Preset`](units::Preset), e.g. `Kops/s` or `Mpps`.
+    ///
+    /// This is a shorthand for [`with_element_units`](DecimalByteMeasurement::with_element_units)
+    /// with the preset.
+    pub fn preset(preset: units::Preset) -> Self {
+        Self::new().with_element_units(preset)
+    }
+}
+
+impl<M: Measurement> DecimalByteMeasurement<M> {
+    /// Create a new [`DecimalByteMeasurement`] struct measuring time with the given measurement.
+    ///
+    /// The values of the measurement have to be in nanoseconds.
+    ///
+    /// # Environment variables
+    ///
+    /// The following variables override the defaults, so that e.g. CI jobs can change
+    /// the formatting without recompiling the bench binary. Options set in code afterwards
+    /// take precedence over them.
+    ///
+    /// - `CDT_UNIT`: display all throughput in the given multiple, e.g. `mega` for MB/s,
+    ///   as with [`with_min_unit`](DecimalByteMeasurement::with_min_unit) and
+    ///   [`with_max_unit`](DecimalByteMeasurement::with_max_unit).
+    /// - `CDT_FAMILY`: the [`UnitFamily`], `decimal` or `binary`.
+    /// - `CDT_PRECISION`: the number of [significant figures](DecimalByteMeasurement::with_significant_figures).
+    /// - `CDT_LABEL`: the label of [element units](DecimalByteMeasurement::with_element_units),
+    ///   e.g. `rows` for `Mrows/s`.
+    ///
+    /// # Panics
+    ///
+    /// If any of the variables is set to an invalid value.
+    pub fn from_time_measurement(time: M) -> Self {
+        run::register_start();
+        DecimalByteMeasurement {
+            time,
+            id: context::new_measurement(),
+            unit_family: UnitFamily::Decimal,
+            inverse_rate: false,
+            sub_unit_rates: false,
+            significant_figures: None,
+            rounding_mode: None,
+            label_style: LabelStyle::Padded,
+            cores: None,
+            unit_threshold: 1.0,
+            binary_equivalent: false,
+            element_units: None,
+            peak: None,
+            time_unit: None,
+            min_unit: Multiple::One,
+            max_unit: Multiple::Tera,
+            environment_unit: false,
+            scientific: false,
+            unit_statistic: Statistic::Typical,
+            unit_overrides: HashMap::new(),
+            bytes_per_element: None,
+            elements_as_bytes: false,
+            missing_throughput: MissingThroughput::Ignore,
+            degenerate_checks: false,
+            decorator: None,
+            #[cfg(feature = "locale")]
+            locale: None,
+            #[cfg(feature = "alloc_stats")]
+            allocation_stats: false,
+        }
+        .with_environment()
+    }
+
+    /// Format throughput as time per unit instead of units per second,
+    /// so `ns/B`, `ns/KB`, `ns/elem`, etc.
+    ///
+    /// The multiple is chosen so that the typical value is at least one nanosecond per unit,
+    /// e.g. a throughput of 500 MB/s is displayed as 2 ns/B, and of 2 GB/s as 500 ns/KB.
+    /// This only applies to [`UnitFamily::Decimal`].
+    pub fn with_inverse_rate(mut self, inverse_rate: bool) -> Self {
+        self.inverse_rate = inverse_rate;
+        self
+    }
+
+    /// Express rates below one unit per second per minute or per hour instead,
+    /// e.g. `40 elem/min` instead of `0.6667 elem/s`.
+    ///
+    /// This is useful for benchmarks where a single iteration takes many seconds.
+    /// The shortest period in which the rate amounts to at least one unit is used,
+    /// see [`units::Period::for_rate`].
+    pub fn with_sub_unit_rates(mut self, sub_unit_rates: bool) -> Self {
+        self.sub_unit_rates = sub_unit_rates;
+        self
+    }
+
+    /// Switch to the next multiple once the throughput reaches the given fraction of it.
+    ///
+    /// By default the threshold is `1.0`, so `999.7 MB/s` is displayed as such. With a threshold
+    /// of `0.9995` it is displayed as `0.9997 GB/s` instead, which rounds to `1.0 GB/s`
+    /// with fewer [significant figures](DecimalByteMeasurement::with_significant_figures).
+    /// Thresholds above one delay the switch, e.g. `2.0` displays `1500 MB/s` instead of `1.5 GB/s`.
+    /// This only applies to [`UnitFamily::Decimal`].
+    ///
+    /// # Panics
+    ///
+    /// If `threshold` is not positive and finite.
+    pub fn with_unit_threshold(mut self, threshold: f64) -> Self {
+        assert!(
+            threshold.is_finite() && threshold > 0.0,
+            "unit threshold must be positive and finite"
+        );
+        self.unit_threshold = threshold;
+        self
+    }
+
+    /// Never display throughput in a multiple larger than the given one,
+    /// e.g. `1500 MB/s` instead of `1.5 GB/s` with [`Multiple::Mega`].
+    ///
+    /// This keeps the units of tables and dashboards aligned. It applies to rates in
+    /// [`UnitFamily::Decimal`], but not to [inverse rates](DecimalByteMeasurement::with_inverse_rate)
+    /// or [element unit systems](DecimalByteMeasurement::with_element_units).
+    ///
+    /// # Panics
+    ///
+    /// If `max_unit` is smaller than the [minimum unit](DecimalByteMeasurement::with_min_unit).
+    /// A minimum unit set by `CDT_UNIT` is lowered instead.
+    pub fn with_max_unit(mut self, max_unit: Multiple) -> Self {
+        if mem::take(&mut self.environment_unit) {
+            self.min_unit = self.min_unit.min(max_unit);
+        }
+        assert!(
+            max_unit >= self.min_unit,
+            "maximum unit must not be smaller than the minimum unit"
+        );
+        self.max_unit = max_unit;
+        self
+    }
+
+    /// Never display throughput in a multiple smaller than the given one,
+    /// e.g. `0.5000 GB/s` instead of `500.00 MB/s` with [`Multiple::Giga`].
+    ///
+    /// The same restrictions as for [`DecimalByteMeasurement::with_max_unit`] apply.
+    ///
+    /// # Panics
+    ///
+    /// If `min_unit` is larger than the [maximum unit](DecimalByteMeasurement::with_max_unit).
+    /// A maximum unit set by `CDT_UNIT` is raised instead.
+    pub fn with_min_unit(mut self, min_unit: Multiple) -> Self {
+        if mem::take(&mut self.environment_unit) {
+            self.max_unit = self.max_unit.max(min_unit);
+        }
+        assert!(
+            min_unit <= self.max_unit,
+            "minimum unit must not be larger than the maximum unit"
+        );
+        self.min_unit = min_unit;
+        self
+    }
+
+    /// Display throughput in scientific notation in the base unit, e.g. `2.410e9  B/s`,
+    /// instead of scaling it to a multiple.
+    ///
+    /// The number of [significant figures](DecimalByteMeasurement::with_significant_figures)
+    /// defaults to four, the same as in the regular output. For [element unit systems](DecimalByteMeasurement::with_element_units)
+    /// the first unit of the ladder is used. This only applies to [`UnitFamily::Decimal`],
+    /// and takes precedence over the [unit clamps](DecimalByteMeasurement::with_max_unit)
+    /// and [sub-unit rates](DecimalByteMeasurement::with_sub_unit_rates), but not
+    /// [inverse rates](DecimalByteMeasurement::with_inverse_rate).
+    pub fn with_scientific_notation(mut self, scientific: bool) -> Self {
+        self.scientific = scientific;
+        self
+    }
+
+    /// Select the multiple of throughput based on the given [`Statistic`] of the scaled values
+    /// instead of the typical value.
+    ///
+    /// All values are always scaled to the same multiple. With the default [`Statistic::Typical`]
+    /// the point estimate is above one in the multiple, but e.g. the lower bound of its confidence
+    /// interval may not be. With [`Statistic::Lowest`] all values are. With [`Statistic::Highest`]
+    /// the multiple is selected for the upper bound instead, so that an interval crossing into
+    /// the next multiple reads e.g. `[0.9980 GB/s 1.0000 GB/s]` instead of `[998.00 MB/s 1000.0 MB/s]`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion::{measurement::ValueFormatter, Throughput};
+    /// use criterion_decimal_throughput::{units::Statistic, DecimalByteMeasurement};
+    ///
+    /// let measurement = DecimalByteMeasurement::new().with_unit_statistic(Statistic::Lowest);
+    /// // 1 GB/s with the confidence interval from 0.8 GB/s to 1.25 GB/s.
+    /// let mut values = [1_000.0, 1_250.0, 800.0];
+    ///
+    /// let unit = measurement.scale_throughputs(1_000.0, &Throughput::Bytes(1_000), &mut values);
+    ///
+    /// assert_eq!(unit, "MB/s");
+    /// assert_eq!(values, [1_000.0, 800.0, 1_250.0]);
+    /// ```
+    pub fn with_unit_statistic(mut self, statistic: Statistic) -> Self {
+        self.unit_statistic = statistic;
+        self
+    }
+
+    /// Display the throughput of the benchmark with the given id in its own unit,
+    /// e.g. `Mrows/s` in a group where other benchmarks are in `MB/s`.
+    ///
+    /// The id is the full id of the benchmark, including the name of its group, e.g. `csv/parse_rows`.
+    /// Criterion.rs does not give the measurement the id of the benchmark being run,
+    /// so only benchmarks of a [`ThroughputGroup`] identified by a string or
+    /// a [`DecimalBenchmarkId`] are known by it.
+    /// The override takes precedence over all of the other unit settings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion::Throughput;
+    /// use criterion_decimal_throughput::{
+    ///     decimal_unit_system, units::UnitOverride, DecimalByteMeasurement, ThroughputGroup,
+    /// };
+    ///
+    /// pub fn my_custom_config() -> criterion::Criterion<DecimalByteMeasurement> {
+    ///     let measurement = DecimalByteMeasurement::new()
+    ///         .with_unit_override("csv/parse_rows", UnitOverride::units(decimal_unit_system!("rows")));
+    ///     criterion::Criterion::default().with_measurement(measurement)
+    /// }
+    ///
+    /// fn example_bench(c: &mut criterion::Criterion<DecimalByteMeasurement>) {
+    ///     let mut group = ThroughputGroup::new(c, "csv");
+    ///     group.throughput(Throughput::Elements(1_000));
+    ///     // Reported in rows/s instead of elem/s.
+    ///     group.bench_function("parse_rows", |b| b.iter(|| 2 + 2));
+    ///     group.finish();
+    /// }
+    /// ```
+    pub fn with_unit_override<S: Into<String>>(
+        mut self,
+        id: S,
+        unit_override: UnitOverride,
+    ) -> Self {
+        self.unit_overrides.insert(id.into(), unit_override);
+        self
+    }
+
+    /// Display formatted throughput with the given number of significant figures,
+    /// e.g. `1.23 GB/s` with 3 significant figures.
+    ///
+    /// By default, the same formatting as in Criterion.rs is used, which shows between
+    /// four and five significant figures. This only affects the formatted output,
+    /// the values produced by [`ValueFormatter::scale_throughputs`] are not rounded.
+    ///
+    /// # Panics
+    ///
+    /// If `figures` is zero.
+    pub fn with_significant_figures(mut self, figures: usize) -> Self {
+        assert!(
+            figures > 0,
+            "number of significant figures must be positive"
+        );
+        self.significant_figures = Some(figures);
+        self
+    }
+
+    /// Round formatted numbers to their displayed precision with the given [`RoundingMode`].
+    ///
+    /// By default, numbers are rounded to nearest, with ties of [significant figures](DecimalByteMeasurement::with_significant_figures)
+    /// away from zero and ties of the default formatting to even, as in Criterion.rs.
+    /// Numbers in [scientific notation](DecimalByteMeasurement::with_scientific_notation) are always
+    /// rounded to nearest. Like [`DecimalByteMeasurement::with_significant_figures`], this only affects
+    /// the formatted output.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion::Throughput;
+    /// use criterion_decimal_throughput::{DecimalByteMeasurement, RoundingMode};
+    ///
+    /// let measurement = DecimalByteMeasurement::new()
+    ///     .with_significant_figures(2)
+    ///     .with_rounding_mode(RoundingMode::Floor);
+    ///
+    /// assert_eq!(measurement.format_rate(1_000_000.0, &Throughput::Bytes(1_290_000)), "   1.2 GB/s");
+    /// ```
+    pub fn with_rounding_mode(mut self, rounding_mode: RoundingMode) -> Self {
+        self.rounding_mode = Some(rounding_mode);
+        self
+    }
+
+    /// Align labels of units in the given [`LabelStyle`].
+    ///
+    /// By default, labels are [padded](LabelStyle::Padded) as in Criterion.rs, so the labels of units
+    /// without a prefix, like `" B/s"` and `" elem/s"`, start with a space. With [`LabelStyle::Compact`],
+    /// the labels returned by [`ValueFormatter::scale_throughputs`] have no leading space, and
+    /// formatted throughput has no padding.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion::Throughput;
+    /// use criterion_decimal_throughput::{DecimalByteMeasurement, LabelStyle};
+    ///
+    /// let padded = DecimalByteMeasurement::new().with_significant_figures(2);
+    /// let compact = DecimalByteMeasurement::new()
+    ///     .with_significant_figures(2)
+    ///     .with_label_style(LabelStyle::Compact);
+    ///
+    /// assert_eq!(padded.format_rate(1e9, &Throughput::Bytes(2)), "   2.0  B/s");
+    /// assert_eq!(compact.format_rate(1e9, &Throughput::Bytes(2)), "2.0 B/s");
+    /// ```
+    pub fn with_label_style(mut self, label_style: LabelStyle) -> Self {
+        self.label_style = label_style;
+        self
+    }
+
+    /// Display throughput per core, divided by the given number of cores, e.g. `GB/(core·s)`.
+    ///
+    /// Use this for benchmarks saturating several cores, to compare their efficiency with
+    /// a single thread or between machines with different numbers of cores. The comparison
+    /// with the [peak bandwidth](DecimalByteMeasurement::with_peak_bandwidth) is still of the total
+    /// throughput. This does not apply to [inverse rates](DecimalByteMeasurement::with_inverse_rate)
+    /// or to machine-readable output.
+    ///
+    /// # Panics
+    ///
+    /// If `cores` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion::Throughput;
+    /// use criterion_decimal_throughput::DecimalByteMeasurement;
+    ///
+    /// let measurement = DecimalByteMeasurement::new().normalize_per_core(16);
+    ///
+    /// let result = measurement.format_rate(1_000_000.0, &Throughput::Bytes(16_000_000));
+    ///
+    /// assert_eq!(result, "1.0000 GB/(core·s)");
+    /// ```
+    pub fn normalize_per_core(mut self, cores: u64) -> Self {
+        assert!(cores > 0, "number of cores must be positive");
+        self.cores = Some(cores);
+        self
+    }
+
+    /// Format numbers in the output using the separators of the given [`Locale`].
+    ///
+    /// Like [`DecimalByteMeasurement::with_significant_figures`], this only affects the formatted output.
+    ///
+    /// Requires the `locale` feature.
+    #[cfg(feature = "locale")]
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = Some(locale);
+        self
+    }
+
+    /// Select the [`UnitFamily`] used to display throughput.
+    ///
+    /// With [`UnitFamily::Binary`] the formatting falls back to the one used by
+    /// [`criterion::measurement::WallTime`].
+    pub fn with_unit_family(mut self, unit_family: UnitFamily) -> Self {
+        self.unit_family = unit_family;
+        self
+    }
+
+    /// Always display the measured times in the given [`TimeUnit`], e.g. in `ms`,
+    /// instead of the one picked based on their magnitude.
+    ///
+    /// This keeps the times of related benchmarks comparable at a glance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion::measurement::ValueFormatter;
+    /// use criterion_decimal_throughput::{units::TimeUnit, DecimalByteMeasurement};
+    ///
+    /// let measurement = DecimalByteMeasurement::new().with_time_unit(TimeUnit::Milliseconds);
+    /// let mut values = [1_500.0];
+    ///
+    /// let unit = measurement.scale_values(1_500.0, &mut values);
+    ///
+    /// assert_eq!(unit, "ms");
+    /// assert_eq!(values, [0.0015]);
+    /// ```
+    pub fn with_time_unit(mut self, time_unit: TimeUnit) -> Self {
+        self.time_unit = Some(time_unit);
+        self
+    }
+
+    /// Append the byte throughput derived from [`Throughput::Elements`] to formatted element throughput,
+    /// for elements of a fixed size, e.g. `2.0000 Melem/s, 128.00 MB/s` for 64 bytes per element.
+    ///
+    /// Use [`with_elements_as_bytes`](DecimalByteMeasurement::with_elements_as_bytes)
+    /// to display only the byte throughput instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion::{measurement::ValueFormatter, Throughput};
+    /// use criterion_decimal_throughput::DecimalByteMeasurement;
+    ///
+    /// let measurement = DecimalByteMeasurement::new().with_bytes_per_element(64);
+    ///
+    /// let result = measurement.format_throughput(&Throughput::Elements(2_000), 1_000_000.0);
+    ///
+    /// assert_eq!(result, "2.0000 Melem/s, 128.00 MB/s");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `bytes` is zero.
+    pub fn with_bytes_per_element(mut self, bytes: u64) -> Self {
+        assert!(bytes > 0, "bytes per element must be positive");
+        self.bytes_per_element = Some(bytes);
+        self
+    }
+
+    /// Display [`Throughput::Elements`] only as the byte throughput derived with
+    /// [`with_bytes_per_element`](DecimalByteMeasurement::with_bytes_per_element),
+    /// e.g. `128.00 MB/s` instead of `2.0000 Melem/s`.
+    ///
+    /// This has no effect if the size of elements is not set.
+    pub fn with_elements_as_bytes(mut self, elements_as_bytes: bool) -> Self {
+        self.elements_as_bytes = elements_as_bytes;
+        self
+    }
+
+    /// Append the binary equivalent to formatted byte throughput, e.g. `2.0000 GB/s (1.8626 GiB/s)`.
+    ///
+    /// This only applies to [`UnitFamily::Decimal`] and to [`Throughput::Bytes`].
+    pub fn with_binary_equivalent(mut self, binary_equivalent: bool) -> Self {
+        self.binary_equivalent = binary_equivalent;
+        self
+    }
+
+    /// Detect benchmarks reported without a [`Throughput`] and warn about them or panic,
+    /// depending on the given [`MissingThroughput`].
+    ///
+    /// The check happens when the next benchmark starts, or when the measurement is dropped
+    /// after the last one, since only then it is known that the formatter was not asked for one.
+    /// It relies on Criterion.rs formatting the time of the benchmark in its report,
+    /// so nothing is detected with `--output-format bencher`.
+    pub fn with_missing_throughput(mut self, missing_throughput: MissingThroughput) -> Self {
+        self.missing_throughput = missing_throughput;
+        self
+    }
+
+    /// Panic if a benchmark is reported without a [`Throughput`].
+    ///
+    /// This is a shorthand for [`with_missing_throughput`](DecimalByteMeasurement::with_missing_throughput)
+    /// with [`MissingThroughput::Panic`].
+    pub fn strict(self) -> Self {
+        self.with_missing_throughput(MissingThroughput::Panic)
+    }
+
+    /// Panic in debug builds when throughput of degenerate values is formatted,
+    /// instead of handling them as described in [degenerate values](DecimalByteMeasurement#degenerate-values).
+    ///
+    /// Useful when testing custom reports, which should not produce such values.
+    pub fn with_degenerate_value_checks(mut self, degenerate_checks: bool) -> Self {
+        self.degenerate_checks = degenerate_checks;
+        self
+    }
+
+    /// Append the rate of allocations during the benchmark to formatted throughput, both per second
+    /// and per unit of throughput, e.g. `2.0000 GB/s (12.000 MB allocated/s, 0.0060 B allocated/B)`.
+    ///
+    /// Allocations are only counted if a [`CountingAllocator`] is installed as the global allocator.
+    ///
+    /// Requires the `alloc_stats` feature.
+    #[cfg(feature = "alloc_stats")]
+    pub fn with_allocation_stats(mut self, allocation_stats: bool) -> Self {
+        self.allocation_stats = allocation_stats;
+        self
+    }
+
+    /// Append the utilization of the given theoretical peak bandwidth to formatted byte throughput,
+    /// e.g. `2.4000 GB/s (7.5% of 32.000 GB/s DRAM peak)` for a peak of `32e9` named `"DRAM"`.
+    ///
+    /// # Panics
+    ///
+    /// If `bytes_per_second` is not positive and finite.
+    pub fn with_peak_bandwidth<S: Into<String>>(mut self, bytes_per_second: f64, name: S) -> Self {
+        assert!(
+            bytes_per_second.is_finite() && bytes_per_second > 0.0,
+            "peak bandwidth must be positive and finite"
+        );
+        self.peak = Some(Peak {
+            bytes_per_second,
+            name: name.into(),
+        });
+        self
+    }
+
+    /// Display [`Throughput::Elements`] in the given [`UnitSystem`] instead of `elem/s`,
+    /// e.g. in `Mpx/s` for [`decimal_unit_system!("px")`](decimal_unit_system).
+    ///
+    /// The [unit threshold](DecimalByteMeasurement::with_unit_threshold) applies to the system,
+    /// while [inverse](DecimalByteMeasurement::with_inverse_rate) and
+    /// [sub-unit](DecimalByteMeasurement::with_sub_unit_rates) rates and
+    /// [consistent units](ThroughputGroup::consistent_units) do not.
+    /// This only applies to [`UnitFamily::Decimal`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion_decimal_throughput::{decimal_unit_system, DecimalByteMeasurement};
+    ///
+    /// let measurement = DecimalByteMeasurement::new().with_element_units(decimal_unit_system!("px"));
+    /// ```
+    pub fn with_element_units<U: UnitSystem + 'static>(mut self, units: U) -> Self {
+        self.element_units = Some(Box::new(units));
+        self
+    }
+
+    /// Layer the given formatter on top of the decimal formatting, e.g. to add annotations
+    /// of an in-house [`ValueFormatter`] to the reports.
+    ///
+    /// All formatting of the measurement goes through the decorator, which gets the decimal
+    /// formatter to delegate to. See [`FormatterDecorator`] for an example.
+    pub fn with_formatter_decorator<D: FormatterDecorator + 'static>(
+        mut self,
+        decorator: D,
+    ) -> Self {
+        self.decorator = Some(Box::new(decorator));
+        self
+    }
+
+    /// Convert the given measured values in nanoseconds into throughput in base units per second,
+    /// without applying any multiple, and return the unit string.
+    ///
+    /// This is the machine-readable counterpart of [`ValueFormatter::scale_throughputs`].
+    /// The result is always in `B/s` or `elem/s`, regardless of the magnitude of the values
+    /// or the selected [`UnitFamily`], so scripts consuming it never have to deal with prefixes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion::Throughput;
+    /// use criterion_decimal_throughput::DecimalByteMeasurement;
+    ///
+    /// let measurement = DecimalByteMeasurement::new();
+    /// let mut values = [1_000_000_000.0, 500_000_000.0];
+    /// let unit = measurement.scale_throughputs_for_machines(&Throughput::Bytes(2_000), &mut values);
+    ///
+    /// assert_eq!(unit, "B/s");
+    /// assert_eq!(values, [2_000.0, 4_000.0]);
+    /// ```
+    pub fn scale_throughputs_for_machines(
+        &self,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        format::throughputs_for_machines(throughput, values)
+    }
+
+    /// Format the throughput of an iteration that took `nanos` nanoseconds exactly as it is
+    /// printed by Criterion.rs, including the padding and all configured decorations.
+    ///
+    /// This is the same as [`ValueFormatter::format_throughput`], for use in custom reports without
+    /// importing the trait. The output for a given configuration is part of the stable API
+    /// of the crate, so it only changes in a major version.
+    ///
+    /// Unlike the trait method, the output only depends on the options of the measurement,
+    /// not on the benchmark being run, e.g. its measured input sizes or the consistent units
+    /// of its group, and formatting does not affect how the benchmark is reported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion::Throughput;
+    /// use criterion_decimal_throughput::DecimalByteMeasurement;
+    ///
+    /// let measurement = DecimalByteMeasurement::new();
+    ///
+    /// assert_eq!(measurement.format_rate(500.0, &Throughput::Bytes(1_000)), "2.0000 GB/s");
+    /// ```
+    pub fn format_rate(&self, nanos: f64, throughput: &Throughput) -> String {
+        context::detached(|| ValueFormatter::format_throughput(self, throughput, nanos))
+    }
+
+    /// Format the throughput of several iterations in the same unit, selected for the one
+    /// that took `typical_nanos` nanoseconds, e.g. for a point estimate and its confidence interval.
+    ///
+    /// Numbers are formatted the same way as in [`DecimalByteMeasurement::format_rate`],
+    /// but without the decorations such as [binary equivalents](DecimalByteMeasurement::with_binary_equivalent).
+    /// Like it, the output is part of the stable API of the crate and only depends on the options
+    /// of the measurement.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion::Throughput;
+    /// use criterion_decimal_throughput::DecimalByteMeasurement;
+    ///
+    /// let measurement = DecimalByteMeasurement::new();
+    ///
+    /// let result = measurement.format_rates(500.0, &[400.0, 500.0, 2_000.0], &Throughput::Bytes(1_000));
+    ///
+    /// assert_eq!(result, ["2.5000 GB/s", "2.0000 GB/s", "0.5000 GB/s"]);
+    /// ```
+    pub fn format_rates(
+        &self,
+        typical_nanos: f64,
+        nanos: &[f64],
+        throughput: &Throughput,
+    ) -> Vec<String> {
+        let mut values = nanos.to_vec();
+        let unit =
+            context::detached(|| self.scale_throughputs(typical_nanos, throughput, &mut values));
+
+        self.format_scaled(throughput, values, unit)
+    }
+
+    /// Format the throughput in the given times in one unit, selected for the median of them,
+    /// for the summary of a group with [consistent units](ThroughputGroup::consistent_units).
+    fn format_consistent(&self, throughput: &Throughput, nanos: &[f64]) -> Vec<String> {
+        let mut values = nanos.to_vec();
+        let typical_nanos = nanos[(nanos.len() - 1) / 2];
+        let unit = self.scale_styled_throughputs(typical_nanos, throughput, &mut values);
+
+        self.format_scaled(throughput, values, unit)
+    }
+
+    fn format_scaled(&self, throughput: &Throughput, values: Vec<f64>, unit: &str) -> Vec<String> {
+        values
+            .into_iter()
+            .map(|value| {
+                let number = if self.uses_scientific_notation(throughput) {
+                    self.format_scientific(value)
+                } else {
+                    self.format_number(value)
+                };
+                self.with_label(&number, unit)
+            })
+            .collect()
     }
 }
 
-impl Measurement for DecimalByteMeasurement {
-    type Intermediate = <WallTime as Measurement>::Intermediate;
+impl<M: Measurement> Measurement for DecimalByteMeasurement<M> {
+    type Intermediate = M::Intermediate;
 
-    type Value = <WallTime as Measurement>::Value;
+    type Value = M::Value;
 
     fn start(&self) -> Self::Intermediate {
-        self.0.start()
+        self.check_throughput(context::start_measurement(self.id));
+        self.time.start()
     }
 
     fn end(&self, i: Self::Intermediate) -> Self::Value {
-        self.0.end(i)
+        let value = self.time.end(i);
+        context::end_measurement(self.time.to_f64(&value));
+        value
     }
 
     fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
-        self.0.add(v1, v2)
+        self.time.add(v1, v2)
     }
 
     fn zero(&self) -> Self::Value {
-        self.0.zero()
+        self.time.zero()
     }
 
     fn to_f64(&self, value: &Self::Value) -> f64 {
-        self.0.to_f64(value)
+        self.time.to_f64(value)
     }
 
     fn formatter(&self) -> &dyn ValueFormatter {
@@ -140,36 +1128,400 @@ impl Measurement for DecimalByteMeasurement {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-enum Multiple {
-    One,
-    Kilo,
-    Mega,
-    Giga,
-    Tera,
+impl<M> Drop for DecimalByteMeasurement<M> {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            self.check_throughput(context::take_missing_throughput());
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-enum Unit {
-    Byte,
-    Elem,
+impl<M> DecimalByteMeasurement<M> {
+    fn check_throughput(&self, missing: bool) {
+        if !missing {
+            return;
+        }
+        let message = "a benchmark was reported without throughput, \
+            set it with `BenchmarkGroup::throughput` to get decimal throughput in the report";
+        match self.missing_throughput {
+            MissingThroughput::Ignore => (),
+            MissingThroughput::Warn => eprintln!("warning: {}", message),
+            MissingThroughput::Panic => panic!("{}", message),
+        }
+    }
 }
 
-impl Multiple {
-    fn denominator(&self) -> f64 {
-        match *self {
-            Multiple::One => 1.0,
-            Multiple::Kilo => 1_000.0,
-            Multiple::Mega => 1_000_000.0,
-            Multiple::Giga => 1_000_000_000.0,
-            Multiple::Tera => 1_000_000_000_000.0,
+impl<M: Measurement> DecimalByteMeasurement<M> {
+    /// Scale throughput as in [`ValueFormatter::scale_throughputs`], with the label padded
+    /// as in Criterion.rs regardless of the [`LabelStyle`].
+    fn scale_padded_throughputs(
+        &self,
+        typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        context::report_throughput();
+        if self.unit_family == UnitFamily::Binary {
+            return self
+                .time
+                .formatter()
+                .scale_throughputs(typical_value, throughput, values);
+        }
+        if self.elements_as_bytes {
+            if let Some((bytes, factor)) = self.element_bytes(throughput) {
+                for val in values.iter_mut() {
+                    *val *= factor;
+                }
+                return self.scale_padded_throughputs(typical_value * factor, &bytes, values);
+            }
+        }
+
+        let count = exact::count(throughput);
+        let degenerate: Vec<_> = (0..values.len())
+            .filter(|i| !is_valid_time(values[*i]))
+            .collect();
+        if count == 0 || !is_valid_time(typical_value) || !degenerate.is_empty() {
+            self.check_degenerate(count, typical_value, values);
+        }
+        if count == 0 || !is_valid_time(typical_value) {
+            for val in values {
+                *val = if is_valid_time(*val) {
+                    exact::scaled_rate(count, *val, Period::Second, Multiple::One)
+                } else {
+                    0.0
+                };
+            }
+            return self.base_label(throughput);
+        }
+
+        for i in &degenerate {
+            values[*i] = typical_value;
+        }
+        let unit = self.scale_positive_throughputs(typical_value, throughput, values);
+        for i in degenerate {
+            values[i] = 0.0;
+        }
+        unit
+    }
+
+    /// The label in the configured [`LabelStyle`].
+    fn styled_label(&self, label: &'static str) -> &'static str {
+        match self.label_style {
+            LabelStyle::Padded => label,
+            LabelStyle::Compact => label.trim_start(),
+        }
+    }
+
+    /// Join a formatted number with its label in the configured [`LabelStyle`].
+    fn with_label(&self, number: &str, label: &str) -> String {
+        match self.label_style {
+            LabelStyle::Padded => format!("{:>6} {}", number, label),
+            LabelStyle::Compact => format!("{} {}", number, label.trim_start()),
+        }
+    }
+
+    fn format_number(&self, n: f64) -> String {
+        let number = match (self.significant_figures, self.rounding_mode) {
+            (Some(figures), mode) => {
+                format::significant(n, figures, mode.unwrap_or(RoundingMode::HalfAwayFromZero))
+            }
+            (None, Some(mode)) => format::short_rounded(n, mode),
+            (None, None) => format::short(n),
+        };
+
+        #[cfg(feature = "locale")]
+        if let Some(locale) = self.locale {
+            return locale.localize(&number);
+        }
+
+        number
+    }
+
+    /// The byte throughput derived from element throughput with the configured size of elements,
+    /// with the factor to multiply times by for it.
+    ///
+    /// Byte counts that do not fit in a `u64` are halved until they do, and the times with them,
+    /// so that the rate stays the same up to the dropped low bits of the count.
+    fn element_bytes(&self, throughput: &Throughput) -> Option<(Throughput, f64)> {
+        match (throughput, self.bytes_per_element) {
+            (Throughput::Elements(elements), Some(bytes))
+                if context::kind_units(throughput).is_none() =>
+            {
+                let bytes = u128::from(*elements) * u128::from(bytes);
+                let shift = (u128::BITS - bytes.leading_zeros()).saturating_sub(u64::BITS);
+                Some((
+                    Throughput::Bytes((bytes >> shift) as u64),
+                    0.5_f64.powi(shift as i32),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// The byte throughput [measured](crate#measured-bytes) for the benchmark being reported,
+    /// replacing the configured [`Throughput::Bytes`].
+    fn measured_throughput(&self, throughput: &Throughput) -> Option<Throughput> {
+        // Both are taken, so that neither carries over to the next benchmark.
+        let (observed, counted) = (
+            context::observed_bytes(self.id),
+            context::counted_bytes(self.id),
+        );
+        match throughput {
+            Throughput::Bytes(_) => observed.or(counted).map(Throughput::Bytes),
+            Throughput::Elements(_) => None,
+        }
+    }
+
+    fn unit_override(&self) -> Option<&UnitOverride> {
+        context::by_benchmark_id(|id| self.unit_overrides.get(id))
+    }
+
+    #[cfg(feature = "alloc_stats")]
+    fn format_allocation_rate(
+        &self,
+        bytes_per_second: f64,
+        throughput: &Throughput,
+        nanos: f64,
+    ) -> String {
+        let (scaled, prefix) = units::scale_decimal(bytes_per_second);
+        let units_per_second = exact::scaled_rate(
+            exact::count(throughput),
+            nanos,
+            Period::Second,
+            Multiple::One,
+        );
+        let unit = match Unit::of(throughput) {
+            Unit::Byte => "B",
+            Unit::Elem => "elem",
+        };
+
+        format!(
+            " ({} {}B allocated/s, {} B allocated/{})",
+            self.format_number(scaled),
+            prefix,
+            self.format_number(bytes_per_second / units_per_second),
+            unit
+        )
+    }
+
+    fn uses_scientific_notation(&self, throughput: &Throughput) -> bool {
+        if self.unit_override().is_some() {
+            return false;
+        }
+        let system = matches!(throughput, Throughput::Elements(_)) && self.element_units.is_some();
+        self.scientific && self.unit_family == UnitFamily::Decimal && (system || !self.inverse_rate)
+    }
+
+    fn format_scientific(&self, n: f64) -> String {
+        let figures = self.significant_figures.unwrap_or(4);
+        format!("{:.*e}", figures.saturating_sub(1), n)
+    }
+
+    fn scale_throughputs_in_system(
+        &self,
+        system: &dyn UnitSystem,
+        typical_value: f64,
+        count: u64,
+        values: &mut [f64],
+    ) -> &'static str {
+        let rate = exact::scaled_rate(count, typical_value, Period::Second, Multiple::One);
+        let (denominator, label) = if self.scientific {
+            *system
+                .ladder()
+                .first()
+                .expect("ladder of a unit system must not be empty")
+        } else {
+            units::select_in_system(system, rate, self.unit_threshold)
+        };
+
+        for val in values {
+            *val = exact::scaled_rate(count, *val, Period::Second, Multiple::One) / denominator;
+        }
+
+        label
+    }
+
+    fn scale_inverse_throughputs(
+        &self,
+        typical_value: f64,
+        throughput: &criterion::Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let total_units = units::total_units(throughput);
+        let nanos_per_unit = typical_value / total_units;
+        let multiple = Multiple::for_value_per_unit(nanos_per_unit);
+        let multiple = context::consistent_multiple(throughput, multiple);
+        let denominator = multiple.denominator();
+
+        for val in values {
+            let nanos_per_unit = *val / total_units;
+            *val = nanos_per_unit * denominator;
+        }
+
+        Unit::of(throughput).inverse_rate_label(multiple)
+    }
+}
+
+impl<M: Measurement> DecimalByteMeasurement<M> {
+    /// [`ValueFormatter::format_throughput`] without the [decorator](DecimalByteMeasurement::with_formatter_decorator).
+    pub(crate) fn format_throughput_undecorated(
+        &self,
+        throughput: &Throughput,
+        value: f64,
+    ) -> String {
+        context::report_throughput();
+        let value = value * context::fractional_time_factor(throughput).unwrap_or(1.0);
+        let measured = self.measured_throughput(throughput);
+        let throughput = measured.as_ref().unwrap_or(throughput);
+        let element_bytes = self.element_bytes(throughput);
+        let ((primary, primary_value), secondary) = match element_bytes {
+            Some((ref bytes, factor)) if self.elements_as_bytes => ((bytes, value * factor), None),
+            Some((bytes, factor)) => ((throughput, value), Some((bytes, value * factor))),
+            None => (
+                (throughput, value),
+                context::secondary_throughput(throughput).map(|secondary| (secondary, value)),
+            ),
+        };
+        let consistent = context::record_consistent_units(primary, primary_value);
+
+        let format_single = |throughput: &Throughput, value: f64| {
+            let mut values = [value];
+            let unit = self.scale_styled_throughputs(value, throughput, &mut values);
+            let number = if !is_valid_time(value) {
+                "n/a".to_owned()
+            } else if self.uses_scientific_notation(throughput) {
+                self.format_scientific(values[0])
+            } else {
+                self.format_number(values[0])
+            };
+            let mut formatted = self.with_label(&number, unit);
+
+            if let Throughput::Bytes(bytes) = throughput {
+                let bytes_per_second =
+                    exact::scaled_rate(*bytes, value, Period::Second, Multiple::One);
+                if self.binary_equivalent && self.unit_family == UnitFamily::Decimal {
+                    let (rate, label) = match self.cores.filter(|_| !self.inverse_rate) {
+                        Some(cores) => (bytes_per_second / cores as f64, "B/(core·s)"),
+                        None => (bytes_per_second, "B/s"),
+                    };
+                    let (scaled, prefix) = units::scale_binary(rate);
+                    formatted += &format!(" ({} {}{})", self.format_number(scaled), prefix, label);
+                }
+                if let Some(peak) = &self.peak {
+                    formatted += &format!(
+                        " ({:.1}% of {} {} peak)",
+                        100.0 * bytes_per_second / peak.bytes_per_second,
+                        format::units_per_second(&Throughput::Bytes(1), peak.bytes_per_second),
+                        peak.name
+                    );
+                }
+            }
+
+            formatted
+        };
+
+        #[allow(unused_mut)]
+        let mut formatted = match (context::write_throughput(primary), secondary) {
+            (Some(write), _) => format!(
+                "in {} / out {}",
+                format_single(primary, primary_value).trim_start(),
+                format_single(&write, primary_value).trim_start()
+            ),
+            (None, Some((secondary, secondary_value))) => {
+                format!(
+                    "{}, {}",
+                    format_single(primary, primary_value),
+                    format_single(&secondary, secondary_value)
+                )
+            }
+            (None, None) => format_single(primary, primary_value),
+        };
+
+        #[cfg(feature = "alloc_stats")]
+        if self.allocation_stats {
+            if let Some(bytes_per_second) = allocation::allocation_rate(self.id) {
+                formatted += &self.format_allocation_rate(bytes_per_second, primary, primary_value);
+            }
+        }
+
+        if consistent {
+            context::summarize_consistent_units(|throughput, nanos| {
+                self.format_consistent(throughput, nanos)
+            });
+        }
+
+        match context::aggregate_threads(throughput) {
+            Some(threads) => format!("{} (aggregate of {} threads)", formatted, threads),
+            None => formatted,
+        }
+    }
+
+    /// [`ValueFormatter::scale_values`] without the decorator.
+    pub(crate) fn scale_values_undecorated(
+        &self,
+        typical_value: f64,
+        values: &mut [f64],
+    ) -> &'static str {
+        context::report_values();
+        match self.time_unit {
+            Some(time_unit) => {
+                for val in values {
+                    *val /= time_unit.nanos();
+                }
+                time_unit.label()
+            }
+            None => self.time.formatter().scale_values(typical_value, values),
+        }
+    }
+
+    /// [`ValueFormatter::scale_throughputs`] without the decorator.
+    pub(crate) fn scale_throughputs_undecorated(
+        &self,
+        typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let factor = context::fractional_time_factor(throughput);
+        let typical_value = typical_value * factor.unwrap_or(1.0);
+        if let Some(factor) = factor {
+            for val in values.iter_mut() {
+                *val *= factor;
+            }
         }
+        let measured = self.measured_throughput(throughput);
+        let throughput = measured.as_ref().unwrap_or(throughput);
+
+        self.scale_styled_throughputs(typical_value, throughput, values)
+    }
+
+    /// [`ValueFormatter::scale_for_machines`] without the decorator.
+    pub(crate) fn scale_for_machines_undecorated(&self, _values: &mut [f64]) -> &'static str {
+        // Nanoseconds are already a decimal multiple of a second, so no scaling is needed.
+        // Use `scale_throughputs_for_machines` to get machine-readable throughput.
+        "ns"
     }
 }
 
-impl ValueFormatter for DecimalByteMeasurement {
+impl<M: Measurement> ValueFormatter for DecimalByteMeasurement<M> {
+    fn format_value(&self, value: f64) -> String {
+        match &self.decorator {
+            Some(decorator) => decorator.format_value(&Undecorated(self), value),
+            None => Undecorated(self).format_value(value),
+        }
+    }
+
+    fn format_throughput(&self, throughput: &Throughput, value: f64) -> String {
+        match &self.decorator {
+            Some(decorator) => decorator.format_throughput(&Undecorated(self), throughput, value),
+            None => self.format_throughput_undecorated(throughput, value),
+        }
+    }
+
     fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
-        self.0.formatter().scale_values(typical_value, values)
+        match &self.decorator {
+            Some(decorator) => decorator.scale_values(&Undecorated(self), typical_value, values),
+            None => self.scale_values_undecorated(typical_value, values),
+        }
     }
 
     fn scale_throughputs(
@@ -178,49 +1530,155 @@ impl ValueFormatter for DecimalByteMeasurement {
         throughput: &criterion::Throughput,
         values: &mut [f64],
     ) -> &'static str {
-        use Multiple::*;
-        use Throughput::*;
-        use Unit::*;
+        match &self.decorator {
+            Some(decorator) => {
+                decorator.scale_throughputs(&Undecorated(self), typical_value, throughput, values)
+            }
+            None => self.scale_throughputs_undecorated(typical_value, throughput, values),
+        }
+    }
 
-        let (total_units, unit) = match *throughput {
-            Bytes(bytes) => (bytes as f64, Byte),
-            Elements(elements) => (elements as f64, Elem),
+    fn scale_for_machines(&self, values: &mut [f64]) -> &'static str {
+        match &self.decorator {
+            Some(decorator) => decorator.scale_for_machines(&Undecorated(self), values),
+            None => self.scale_for_machines_undecorated(values),
+        }
+    }
+}
+
+impl<M: Measurement> DecimalByteMeasurement<M> {
+    /// Scale throughput per core if configured and style its label,
+    /// with times already adjusted for a fractional throughput.
+    fn scale_styled_throughputs(
+        &self,
+        typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let label = match self.cores {
+            Some(cores) if !self.inverse_rate => {
+                // Throughput per core is the throughput of the time multiplied by the cores.
+                let cores = cores as f64;
+                for val in values.iter_mut() {
+                    *val *= cores;
+                }
+                let label =
+                    self.scale_padded_throughputs(typical_value * cores, throughput, values);
+                units::per_core_label(label)
+            }
+            _ => self.scale_padded_throughputs(typical_value, throughput, values),
         };
-        let units_per_second = total_units * (1e9 / typical_value);
-        let multiple = if units_per_second >= 1e12 {
-            Tera
-        } else if units_per_second >= 1e9 {
-            Giga
-        } else if units_per_second >= 1e6 {
-            Mega
-        } else if units_per_second >= 1e3 {
-            Kilo
+        self.styled_label(label)
+    }
+}
+
+/// Whether `nanos` is a time throughput can be computed for, as opposed to e.g.
+/// a slope estimate of zero for a routine optimized away.
+fn is_valid_time(nanos: f64) -> bool {
+    nanos.is_finite() && nanos > 0.0
+}
+
+impl<M: Measurement> DecimalByteMeasurement<M> {
+    /// Scale throughput of a positive number of units in positive, finite times.
+    fn scale_positive_throughputs(
+        &self,
+        typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let typical_value = self.unit_statistic.select(typical_value, values);
+        match self.unit_override() {
+            Some(UnitOverride::Units(system)) => {
+                let count = exact::count(throughput);
+                let rate = exact::scaled_rate(count, typical_value, Period::Second, Multiple::One);
+                let (denominator, label) =
+                    units::select_in_system(system.as_ref(), rate, self.unit_threshold);
+                for val in values {
+                    *val = exact::scaled_rate(count, *val, Period::Second, Multiple::One)
+                        / denominator;
+                }
+                return label;
+            }
+            Some(UnitOverride::Multiple(multiple)) => {
+                let count = exact::count(throughput);
+                for val in values {
+                    *val = exact::scaled_rate(count, *val, Period::Second, *multiple);
+                }
+                return Unit::of(throughput).rate_label(*multiple);
+            }
+            None => (),
+        }
+        if let (Throughput::Elements(elements), Some(system)) =
+            (throughput, context::kind_units(throughput))
+        {
+            return self.scale_throughputs_in_system(&system, typical_value, *elements, values);
+        }
+        if let (Throughput::Elements(elements), Some(system)) = (throughput, &self.element_units) {
+            return self.scale_throughputs_in_system(
+                system.as_ref(),
+                typical_value,
+                *elements,
+                values,
+            );
+        }
+        if self.inverse_rate {
+            return self.scale_inverse_throughputs(typical_value, throughput, values);
+        }
+
+        let count = exact::count(throughput);
+        if self.scientific {
+            for val in values {
+                *val = exact::scaled_rate(count, *val, Period::Second, Multiple::One);
+            }
+            return Unit::of(throughput).rate_label(Multiple::One);
+        }
+        let multiple = exact::multiple_for_rate(count, typical_value, self.unit_threshold)
+            .clamp(self.min_unit, self.max_unit);
+        let multiple = context::consistent_multiple(throughput, multiple);
+        let period = if self.sub_unit_rates && multiple == Multiple::One {
+            exact::period_for_rate(count, typical_value)
         } else {
-            One
+            Period::Second
         };
-        let denominator = multiple.denominator();
 
         for val in values {
-            let units_per_second = total_units * (1e9 / *val);
-            *val = units_per_second / denominator;
+            *val = exact::scaled_rate(count, *val, period, multiple);
         }
 
-        match (unit, multiple) {
-            (Byte, One) => " B/s",
-            (Byte, Kilo) => "KB/s",
-            (Byte, Mega) => "MB/s",
-            (Byte, Giga) => "GB/s",
-            (Byte, Tera) => "TB/s",
-            (Elem, One) => " elem/s",
-            (Elem, Kilo) => "Kelem/s",
-            (Elem, Mega) => "Melem/s",
-            (Elem, Giga) => "Gelem/s",
-            (Elem, Tera) => "Telem/s",
+        match period {
+            Period::Second => Unit::of(throughput).rate_label(multiple),
+            _ => Unit::of(throughput).period_label(period),
         }
     }
 
-    fn scale_for_machines(&self, values: &mut [f64]) -> &'static str {
-        self.0.formatter().scale_for_machines(values)
+    /// Label of the throughput in base units per second, used for degenerate values.
+    fn base_label(&self, throughput: &Throughput) -> &'static str {
+        let system = match (self.unit_override(), throughput, &self.element_units) {
+            (Some(UnitOverride::Units(system)), _, _) => Some(system),
+            (Some(UnitOverride::Multiple(_)), _, _) => None,
+            (None, Throughput::Elements(_), Some(system)) => Some(system),
+            (None, _, _) => None,
+        };
+        match system {
+            Some(system) => {
+                system
+                    .ladder()
+                    .first()
+                    .expect("ladder of a unit system must not be empty")
+                    .1
+            }
+            None => Unit::of(throughput).rate_label(Multiple::One),
+        }
+    }
+
+    fn check_degenerate(&self, count: u64, typical_value: f64, values: &[f64]) {
+        if self.degenerate_checks {
+            debug_assert!(
+                false,
+                "degenerate throughput of {} units in {} ns, values {:?}",
+                count, typical_value, values
+            );
+        }
     }
 }
 
@@ -326,6 +1784,412 @@ mod test {
         assert_eq!(values, [10.0, 2.0, 1.000000001, 1.0, 0.999999999, 0.5, 0.1]);
     }
 
+    #[test]
+    fn scale_throughputs_binary_bytes() {
+        let thpt_config = Throughput::Bytes(1_048_576);
+        let typical = 1_000_000_000.0;
+        let mut values = [1_000_000_000.0, 2_000_000_000.0];
+
+        let measurement = DecimalByteMeasurement::new().with_unit_family(UnitFamily::Binary);
+        let result = measurement.scale_throughputs(typical, &thpt_config, &mut values);
+
+        assert_eq!(result, "MiB/s");
+        assert_eq!(values, [1.0, 0.5]);
+    }
+
+    #[test]
+    fn scale_throughputs_inverse_rate_bytes() {
+        let thpt_config = Throughput::Bytes(2_000_000);
+        let typical = 1_000_000.0;
+        let mut values = [1_000_000.0, 2_000_000.0, 4_000_000.0];
+
+        let measurement = DecimalByteMeasurement::new().with_inverse_rate(true);
+        let result = measurement.scale_throughputs(typical, &thpt_config, &mut values);
+
+        assert_eq!(result, "ns/KB");
+        assert_eq!(values, [500.0, 1_000.0, 2_000.0]);
+    }
+
+    #[test]
+    fn scale_throughputs_inverse_rate_elems() {
+        let thpt_config = Throughput::Elements(10);
+        let typical = 1_000.0;
+        let mut values = [1_000.0];
+
+        let measurement = DecimalByteMeasurement::new().with_inverse_rate(true);
+        let result = measurement.scale_throughputs(typical, &thpt_config, &mut values);
+
+        assert_eq!(result, "ns/elem");
+        assert_eq!(values, [100.0]);
+    }
+
+    #[test]
+    fn scale_throughputs_for_machines_elems() {
+        let thpt_config = Throughput::Elements(1_000_000_000_000);
+        let mut values = [1_000_000_000.0, 4_000_000_000.0];
+
+        let measurement = DecimalByteMeasurement::default();
+        let result = measurement.scale_throughputs_for_machines(&thpt_config, &mut values);
+
+        assert_eq!(result, "elem/s");
+        assert_eq!(values, [1e12, 2.5e11]);
+    }
+
+    #[test]
+    fn scale_throughputs_sub_unit_rates() {
+        let thpt_config = Throughput::Elements(2);
+        // 2 elements in 3 seconds is 40 elements per minute.
+        let typical = 3e9;
+        let mut values = [3e9, 6e9];
+
+        let measurement = DecimalByteMeasurement::new().with_sub_unit_rates(true);
+        let result = measurement.scale_throughputs(typical, &thpt_config, &mut values);
+
+        assert_eq!(result, " elem/min");
+        assert_eq!(values, [40.0, 20.0]);
+    }
+
+    #[test]
+    fn scale_throughputs_sub_unit_rates_per_hour() {
+        let thpt_config = Throughput::Bytes(1);
+        let typical = 360e9;
+        let mut values = [360e9];
+
+        let measurement = DecimalByteMeasurement::new().with_sub_unit_rates(true);
+        let result = measurement.scale_throughputs(typical, &thpt_config, &mut values);
+
+        assert_eq!(result, " B/h");
+        assert_eq!(values, [10.0]);
+    }
+
+    #[test]
+    fn format_throughput_significant_figures() {
+        let thpt_config = Throughput::Bytes(1_234_567);
+        let measurement = DecimalByteMeasurement::new().with_significant_figures(3);
+
+        let result = measurement.format_throughput(&thpt_config, 1_000_000.0);
+
+        assert_eq!(result, "  1.23 GB/s");
+    }
+
+    #[test]
+    fn format_throughput_per_core() {
+        let measurement = DecimalByteMeasurement::new()
+            .normalize_per_core(4)
+            .with_binary_equivalent(true);
+        let mut values = [1_000_000.0];
+
+        let unit =
+            measurement.scale_throughputs(1_000_000.0, &Throughput::Elements(2_000), &mut values);
+        let bytes = measurement.format_throughput(&Throughput::Bytes(8_000_000), 1_000_000.0);
+
+        assert_eq!(unit, "Kelem/(core·s)");
+        assert_eq!(values, [500.0]);
+        assert_eq!(bytes, "2.0000 GB/(core·s) (1.8626 GiB/(core·s))");
+    }
+
+    #[test]
+    fn compact_label_style_trims_labels() {
+        let measurement = DecimalByteMeasurement::new().with_label_style(LabelStyle::Compact);
+        let mut values = [1_000_000_000.0];
+
+        let unit =
+            measurement.scale_throughputs(1_000_000_000.0, &Throughput::Elements(2), &mut values);
+        let bytes = measurement.format_throughput(&Throughput::Bytes(2_000), 1_000_000.0);
+        let binary = DecimalByteMeasurement::new()
+            .with_label_style(LabelStyle::Compact)
+            .with_unit_family(UnitFamily::Binary)
+            .format_throughput(&Throughput::Bytes(2), 1_000_000_000.0);
+
+        assert_eq!(unit, "elem/s");
+        assert_eq!(bytes, "2.0000 MB/s");
+        assert_eq!(binary, "2.0000 B/s");
+    }
+
+    #[test]
+    fn format_throughput_binary_equivalent() {
+        let measurement = DecimalByteMeasurement::new().with_binary_equivalent(true);
+
+        let bytes = measurement.format_throughput(&Throughput::Bytes(2_000_000), 1_000_000.0);
+        let elems = measurement.format_throughput(&Throughput::Elements(2_000_000), 1_000_000.0);
+
+        assert_eq!(bytes, "2.0000 GB/s (1.8626 GiB/s)");
+        assert_eq!(elems, "2.0000 Gelem/s");
+    }
+
+    #[test]
+    fn scale_throughputs_clamped_units() {
+        let max_mega = DecimalByteMeasurement::new().with_max_unit(Multiple::Mega);
+        let min_giga = DecimalByteMeasurement::new().with_min_unit(Multiple::Giga);
+        let bytes = Throughput::Bytes(1_500_000);
+        let mut fast = [1_000_000.0];
+        let mut slow = [1e9];
+
+        let fast_unit = max_mega.scale_throughputs(fast[0], &bytes, &mut fast);
+        let slow_unit = min_giga.scale_throughputs(slow[0], &bytes, &mut slow);
+
+        assert_eq!(fast_unit, "MB/s");
+        assert_eq!(fast, [1_500.0]);
+        assert_eq!(slow_unit, "GB/s");
+        assert_eq!(slow, [0.0015]);
+    }
+
+    #[test]
+    #[should_panic(expected = "maximum unit must not be smaller")]
+    fn max_unit_below_min_unit_panics() {
+        let _ = DecimalByteMeasurement::new()
+            .with_min_unit(Multiple::Giga)
+            .with_max_unit(Multiple::Kilo);
+    }
+
+    #[test]
+    fn format_throughput_scientific_notation() {
+        let measurement = DecimalByteMeasurement::new().with_scientific_notation(true);
+        let three_figures = DecimalByteMeasurement::new()
+            .with_scientific_notation(true)
+            .with_significant_figures(3);
+
+        let result = measurement.format_throughput(&Throughput::Bytes(2_410_000), 1_000_000.0);
+        let short = three_figures.format_throughput(&Throughput::Elements(2_410), 1_000_000.0);
+
+        assert_eq!(result, "2.410e9  B/s");
+        assert_eq!(short, "2.41e6  elem/s");
+    }
+
+    #[test]
+    fn format_rate_is_stable() {
+        let measurement = DecimalByteMeasurement::new();
+
+        let cases = [
+            (Throughput::Bytes(1), 2e9, "0.5000  B/s"),
+            (Throughput::Bytes(1_000), 500.0, "2.0000 GB/s"),
+            (Throughput::Bytes(123_456_789), 1e9, "123.46 MB/s"),
+            (Throughput::Elements(42), 1e6, "42.000 Kelem/s"),
+            (
+                Throughput::Elements(5_000_000_000_000),
+                1e9,
+                "5.0000 Telem/s",
+            ),
+        ];
+
+        for (throughput, nanos, expected) in cases {
+            assert_eq!(measurement.format_rate(nanos, &throughput), expected);
+        }
+    }
+
+    #[test]
+    fn format_rates_share_unit() {
+        let measurement = DecimalByteMeasurement::new();
+
+        let result =
+            measurement.format_rates(1_000.0, &[1_000.0, 100_000.0], &Throughput::Elements(1_000));
+
+        assert_eq!(result, ["1.0000 Gelem/s", "0.0100 Gelem/s"]);
+    }
+
+    #[test]
+    fn format_throughput_unit_override_by_id() {
+        let measurement = DecimalByteMeasurement::new()
+            .with_unit_override(
+                "csv/rows",
+                UnitOverride::units(decimal_unit_system!("rows")),
+            )
+            .with_unit_override("csv/copy", UnitOverride::Multiple(Multiple::Mega));
+        let format = |id: &str| {
+            context::with_benchmark_id(Some(id.to_string()), || {
+                measurement.format_throughput(&Throughput::Bytes(2_000_000), 1_000_000.0)
+            })
+        };
+
+        assert_eq!(format("csv/rows"), "2.0000 Grows/s");
+        assert_eq!(format("csv/copy"), "2000.0 MB/s");
+        assert_eq!(format("json/copy"), "2.0000 GB/s");
+    }
+
+    #[test]
+    #[should_panic(expected = "a benchmark was reported without throughput")]
+    fn strict_panics_on_benchmark_without_throughput() {
+        let measurement = DecimalByteMeasurement::new().strict();
+        measurement.start();
+        measurement.scale_values(1_000.0, &mut [1_000.0]);
+
+        measurement.start();
+    }
+
+    #[test]
+    fn strict_accepts_benchmark_with_throughput() {
+        let measurement = DecimalByteMeasurement::new().strict();
+        measurement.start();
+        measurement.scale_values(1_000.0, &mut [1_000.0]);
+        measurement.format_throughput(&Throughput::Bytes(1_000), 1_000.0);
+
+        measurement.start();
+    }
+
+    #[test]
+    fn scale_throughputs_zero_units() {
+        let measurement = DecimalByteMeasurement::new();
+        let mut bytes = [1_000.0, 0.0];
+        let mut elements = [1_000.0];
+
+        let bytes_unit = measurement.scale_throughputs(1_000.0, &Throughput::Bytes(0), &mut bytes);
+        let elements_unit =
+            measurement.scale_throughputs(1_000.0, &Throughput::Elements(0), &mut elements);
+
+        assert_eq!(bytes_unit, " B/s");
+        assert_eq!(bytes, [0.0, 0.0]);
+        assert_eq!(elements_unit, " elem/s");
+        assert_eq!(elements, [0.0]);
+    }
+
+    #[test]
+    fn scale_throughputs_zero_time() {
+        let measurement = DecimalByteMeasurement::new();
+        let mut values = [0.0, f64::NAN, 2_000.0];
+
+        let unit = measurement.scale_throughputs(0.0, &Throughput::Bytes(1_000), &mut values);
+        let formatted = measurement.format_throughput(&Throughput::Bytes(1_000), 0.0);
+
+        assert_eq!(unit, " B/s");
+        assert_eq!(values, [0.0, 0.0, 500_000_000.0]);
+        assert_eq!(formatted, "   n/a  B/s");
+    }
+
+    #[test]
+    fn scale_throughputs_sub_nanosecond_typical_value() {
+        let measurement = DecimalByteMeasurement::new();
+        let mut values = [0.5, -1.0];
+
+        let unit = measurement.scale_throughputs(0.5, &Throughput::Bytes(1_000), &mut values);
+
+        assert_eq!(unit, "TB/s");
+        assert_eq!(values, [2.0, 0.0]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "degenerate throughput of 0 units")]
+    fn degenerate_value_checks_panic() {
+        let measurement = DecimalByteMeasurement::new().with_degenerate_value_checks(true);
+
+        measurement.format_throughput(&Throughput::Elements(0), 1_000.0);
+    }
+
+    #[test]
+    fn format_throughput_element_bytes_beyond_u64() {
+        let measurement = DecimalByteMeasurement::new().with_bytes_per_element(1_000);
+        let throughput = Throughput::Elements(u64::MAX);
+        let mut values = [1e12];
+
+        let result = measurement.format_throughput(&throughput, 1e12);
+        let unit = measurement.with_elements_as_bytes(true).scale_throughputs(
+            1e12,
+            &throughput,
+            &mut values,
+        );
+
+        assert_eq!(result, " 18447 Telem/s, 18446744 TB/s");
+        assert_eq!(unit, "TB/s");
+        assert_eq!(values, [18_446_744.073_709_55]);
+    }
+
+    #[test]
+    fn format_throughput_elements_as_bytes() {
+        let measurement = DecimalByteMeasurement::new()
+            .with_bytes_per_element(64)
+            .with_elements_as_bytes(true);
+        let mut values = [1_000_000.0];
+
+        let result = measurement.format_throughput(&Throughput::Elements(2_000), 1_000_000.0);
+        let unit =
+            measurement.scale_throughputs(1_000_000.0, &Throughput::Elements(2_000), &mut values);
+
+        assert_eq!(result, "128.00 MB/s");
+        assert_eq!(unit, "MB/s");
+        assert_eq!(values, [128.0]);
+    }
+
+    #[test]
+    fn scale_values_locked_time_unit() {
+        let measurement = DecimalByteMeasurement::new().with_time_unit(TimeUnit::Seconds);
+        let mut values = [2_500_000_000.0, 50.0];
+
+        let result = measurement.scale_values(50.0, &mut values);
+
+        assert_eq!(result, "s");
+        assert_eq!(values, [2.5, 5e-8]);
+    }
+
+    #[test]
+    fn format_throughput_peak_bandwidth() {
+        let measurement = DecimalByteMeasurement::new().with_peak_bandwidth(32e9, "DRAM");
+
+        let result = measurement.format_throughput(&Throughput::Bytes(2_400_000), 1_000_000.0);
+
+        assert_eq!(result, "2.4000 GB/s (7.5% of 32.000 GB/s DRAM peak)");
+    }
+
+    #[test]
+    fn scale_throughputs_element_units() {
+        let measurement =
+            DecimalByteMeasurement::new().with_element_units(decimal_unit_system!("px"));
+        let mut values = [1_000_000.0, 2_000_000.0];
+
+        let result = measurement.scale_throughputs(
+            1_000_000.0,
+            &Throughput::Elements(2_000_000),
+            &mut values,
+        );
+
+        assert_eq!(result, "Gpx/s");
+        assert_eq!(values, [2.0, 1.0]);
+    }
+
+    #[test]
+    fn scale_throughputs_highest_statistic_selects_upper_bound_multiple() {
+        let thpt_config = Throughput::Bytes(998);
+        let measurement = DecimalByteMeasurement::new().with_unit_statistic(Statistic::Highest);
+        // 998 MB/s with the upper bound of the confidence interval at 1 GB/s.
+        let mut values = [1_000.0, 998.0];
+
+        let result = measurement.scale_throughputs(1_000.0, &thpt_config, &mut values);
+
+        assert_eq!(result, "GB/s");
+        assert!((values[0] - 0.998).abs() < 1e-12);
+        assert!((values[1] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn scale_throughputs_unit_threshold() {
+        let thpt_config = Throughput::Bytes(999_700);
+        let measurement = DecimalByteMeasurement::new().with_unit_threshold(0.9995);
+        let mut values = [1_000_000.0];
+
+        let result = measurement.scale_throughputs(1_000_000.0, &thpt_config, &mut values);
+
+        assert_eq!(result, "GB/s");
+        assert_eq!(values, [0.9997]);
+    }
+
+    #[test]
+    fn scale_throughputs_unit_threshold_above_one() {
+        let thpt_config = Throughput::Bytes(1_500_000);
+        let measurement = DecimalByteMeasurement::new().with_unit_threshold(2.0);
+        let mut values = [1_000_000.0];
+
+        let result = measurement.scale_throughputs(1_000_000.0, &thpt_config, &mut values);
+
+        assert_eq!(result, "MB/s");
+        assert_eq!(values, [1_500.0]);
+    }
+
+    #[test]
+    fn unit_family_from_str() {
+        assert_eq!("decimal".parse(), Ok(UnitFamily::Decimal));
+        assert_eq!("Binary".parse(), Ok(UnitFamily::Binary));
+        assert!("kibi".parse::<UnitFamily>().is_err());
+    }
+
     #[test]
     fn scale_throughputs_elems_gives_correct_unit_regression1() {
         let elems = 13302377187617527;
@@ -340,4 +2204,25 @@ mod test {
 
         assert_eq!(result, target.expected_elems());
     }
+
+    #[cfg(feature = "macros")]
+    #[test]
+    fn decimal_bench_configures_throughput_from_input() {
+        use criterion::Bencher;
+
+        #[decimal_bench(elements = "input.len()", input = "vec![1_u32; 100]", group = "macros")]
+        fn sum(b: &mut Bencher<DecimalByteMeasurement>, input: &[u32]) {
+            b.iter(|| input.iter().sum::<u32>())
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut c = test_criterion(dir.path());
+
+        sum(&mut c);
+
+        let results = results::load_results(dir.path()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].full_id, "macros/sum");
+        assert_eq!(results[0].throughput, Some(Throughput::Elements(100)));
+    }
 }
@@ -0,0 +1,89 @@
+/// Separators used to format numbers for a particular audience.
+///
+/// Requires the `locale` feature.
+///
+/// # Example
+///
+/// ```
+/// use criterion::{measurement::ValueFormatter, Throughput};
+/// use criterion_decimal_throughput::{DecimalByteMeasurement, Locale};
+///
+/// let measurement = DecimalByteMeasurement::new()
+///     .with_inverse_rate(true)
+///     .with_locale(Locale::GERMAN);
+///
+/// // 1,234,560 ns per 1 byte.
+/// let result = measurement.format_throughput(&Throughput::Bytes(1), 1_234_560.0);
+///
+/// assert_eq!(result, "1.234.560 ns/B");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Locale {
+    decimal_separator: char,
+    thousands_separator: Option<char>,
+}
+
+impl Locale {
+    /// Decimal point and comma as thousands separator, so `1,234.56`.
+    pub const ENGLISH: Locale = Locale::new('.', Some(','));
+    /// Decimal comma and period as thousands separator, so `1.234,56`.
+    pub const GERMAN: Locale = Locale::new(',', Some('.'));
+    /// Decimal comma and narrow no-break space as thousands separator, so `1 234,56`.
+    pub const FRENCH: Locale = Locale::new(',', Some('\u{202f}'));
+
+    /// Create a locale with the given decimal separator and an optional thousands separator.
+    pub const fn new(decimal_separator: char, thousands_separator: Option<char>) -> Self {
+        Locale {
+            decimal_separator,
+            thousands_separator,
+        }
+    }
+
+    /// Apply the separators to a number formatted with the default Rust formatting,
+    /// so with `.` as the separator and no thousands separator.
+    pub(crate) fn localize(&self, number: &str) -> String {
+        let (sign, unsigned) = match number.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", number),
+        };
+        let (integer, fraction) = match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (unsigned, None),
+        };
+
+        let mut result = String::from(sign);
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                if let Some(separator) = self.thousands_separator {
+                    result.push(separator);
+                }
+            }
+            result.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            result.push(self.decimal_separator);
+            result.push_str(fraction);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn localize_german() {
+        assert_eq!(Locale::GERMAN.localize("1234.56"), "1.234,56");
+        assert_eq!(Locale::GERMAN.localize("-1234567"), "-1.234.567");
+        assert_eq!(Locale::GERMAN.localize("123.4"), "123,4");
+    }
+
+    #[test]
+    fn localize_without_thousands_separator() {
+        let locale = Locale::new(',', None);
+
+        assert_eq!(locale.localize("1234.56"), "1234,56");
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{DecimalByteMeasurement, IntoBenchmarkId};
+use criterion::{measurement::Measurement, Bencher, BenchmarkGroup, Throughput};
+
+/// Wrapper over [`criterion::BenchmarkGroup`] that sets the throughput of every benchmark
+/// from its input, e.g. for sweeps over sizes of the input.
+///
+/// The throughput is computed with the closure given when creating the group,
+/// so it cannot go out of sync with the inputs when they change.
+/// Configure the wrapped group with [`DecimalBenchmarkGroup::group_mut`].
+///
+/// # Example
+///
+/// ```
+/// use criterion::{BenchmarkId, Throughput};
+/// use criterion_decimal_throughput::{Criterion, DecimalBenchmarkGroup};
+///
+/// fn example_bench(c: &mut Criterion) {
+///     let mut group = DecimalBenchmarkGroup::new(c.benchmark_group("sum"), |input: &[u32]| {
+///         Throughput::Elements(input.len() as u64)
+///     });
+///     for size in [1_000, 1_000_000] {
+///         let input = vec![1_u32; size];
+///         group.bench_with_input(BenchmarkId::from_parameter(size), &input[..], |b, input| {
+///             b.iter(|| input.iter().sum::<u32>())
+///         });
+///     }
+///     group.finish();
+/// }
+/// ```
+pub struct DecimalBenchmarkGroup<'a, I: ?Sized, M: Measurement = DecimalByteMeasurement> {
+    group: BenchmarkGroup<'a, M>,
+    throughput: Box<dyn Fn(&I) -> Throughput + 'a>,
+}
+
+impl<'a, I: ?Sized, M: Measurement> DecimalBenchmarkGroup<'a, I, M> {
+    /// Wrap the group, computing the throughput of its benchmarks from their inputs with `throughput`.
+    pub fn new<T>(group: BenchmarkGroup<'a, M>, throughput: T) -> Self
+    where
+        T: Fn(&I) -> Throughput + 'a,
+    {
+        DecimalBenchmarkGroup {
+            group,
+            throughput: Box::new(throughput),
+        }
+    }
+
+    /// The wrapped group, e.g. to change its sample size.
+    pub fn group_mut(&mut self) -> &mut BenchmarkGroup<'a, M> {
+        &mut self.group
+    }
+
+    /// Set the throughput of the group for the given input and benchmark the function with it,
+    /// as with [`criterion::BenchmarkGroup::bench_with_input`].
+    pub fn bench_with_input<ID, F>(&mut self, id: ID, input: &I, f: F) -> &mut Self
+    where
+        ID: IntoBenchmarkId,
+        F: FnMut(&mut Bencher<'_, M>, &I),
+    {
+        self.group.throughput((self.throughput)(input));
+        id.bench_with_input(&mut self.group, input, f);
+        self
+    }
+
+    /// Consume the group and generate its summary report, as with [`criterion::BenchmarkGroup::finish`].
+    pub fn finish(self) {
+        self.group.finish();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{results, test_criterion};
+
+    #[test]
+    fn bench_with_input_sets_throughput_of_each_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut c = test_criterion(dir.path());
+        let mut group = DecimalBenchmarkGroup::new(c.benchmark_group("test"), |input: &[u8]| {
+            Throughput::Elements(input.len() as u64)
+        });
+
+        for input in [&[1_u8, 2][..], &[1, 2, 3, 4]] {
+            group.bench_with_input(input.len().to_string(), input, |b, input| {
+                b.iter(|| input.iter().sum::<u8>())
+            });
+        }
+        group.finish();
+
+        let throughputs: Vec<_> = results::load_results(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|result| result.throughput)
+            .collect();
+        assert_eq!(
+            throughputs,
+            [Some(Throughput::Elements(2)), Some(Throughput::Elements(4))]
+        );
+    }
+}
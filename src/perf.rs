@@ -0,0 +1,284 @@
+use crate::{
+    exact,
+    units::{Multiple, Unit},
+};
+use criterion::{
+    measurement::{Measurement, ValueFormatter},
+    Throughput,
+};
+use perf_event::{events::Hardware, Builder, Counter};
+use std::io;
+
+/// Hardware event counted by a [`DecimalPerfEventMeasurement`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PerfEvent {
+    /// Retired instructions, displayed as `instr`.
+    #[default]
+    Instructions,
+    /// CPU cycles, displayed as `cycle`.
+    CpuCycles,
+    /// Retired branch instructions, displayed as `branch`.
+    BranchInstructions,
+    /// Mispredicted branch instructions, displayed as `br-miss`.
+    BranchMisses,
+    /// Cache accesses, displayed as `cache-ref`.
+    CacheReferences,
+    /// Cache misses, displayed as `cache-miss`.
+    CacheMisses,
+}
+
+struct EventLabels {
+    name: &'static str,
+    counts: [&'static str; 5],
+    per_byte: [&'static str; 5],
+    per_elem: [&'static str; 5],
+}
+
+macro_rules! event_labels {
+    ($name:literal) => {
+        EventLabels {
+            name: $name,
+            counts: [
+                concat!(" ", $name),
+                concat!("K", $name),
+                concat!("M", $name),
+                concat!("G", $name),
+                concat!("T", $name),
+            ],
+            per_byte: [
+                concat!($name, "/B"),
+                concat!($name, "/KB"),
+                concat!($name, "/MB"),
+                concat!($name, "/GB"),
+                concat!($name, "/TB"),
+            ],
+            per_elem: [
+                concat!($name, "/elem"),
+                concat!($name, "/Kelem"),
+                concat!($name, "/Melem"),
+                concat!($name, "/Gelem"),
+                concat!($name, "/Telem"),
+            ],
+        }
+    };
+}
+
+impl PerfEvent {
+    fn hardware(self) -> Hardware {
+        match self {
+            PerfEvent::Instructions => Hardware::INSTRUCTIONS,
+            PerfEvent::CpuCycles => Hardware::CPU_CYCLES,
+            PerfEvent::BranchInstructions => Hardware::BRANCH_INSTRUCTIONS,
+            PerfEvent::BranchMisses => Hardware::BRANCH_MISSES,
+            PerfEvent::CacheReferences => Hardware::CACHE_REFERENCES,
+            PerfEvent::CacheMisses => Hardware::CACHE_MISSES,
+        }
+    }
+
+    fn labels(self) -> &'static EventLabels {
+        const INSTRUCTIONS: EventLabels = event_labels!("instr");
+        const CPU_CYCLES: EventLabels = event_labels!("cycle");
+        const BRANCH_INSTRUCTIONS: EventLabels = event_labels!("branch");
+        const BRANCH_MISSES: EventLabels = event_labels!("br-miss");
+        const CACHE_REFERENCES: EventLabels = event_labels!("cache-ref");
+        const CACHE_MISSES: EventLabels = event_labels!("cache-miss");
+
+        match self {
+            PerfEvent::Instructions => &INSTRUCTIONS,
+            PerfEvent::CpuCycles => &CPU_CYCLES,
+            PerfEvent::BranchInstructions => &BRANCH_INSTRUCTIONS,
+            PerfEvent::BranchMisses => &BRANCH_MISSES,
+            PerfEvent::CacheReferences => &CACHE_REFERENCES,
+            PerfEvent::CacheMisses => &CACHE_MISSES,
+        }
+    }
+}
+
+/// Measurement counting a hardware event with Linux `perf_event` instead of measuring time.
+///
+/// Counts are displayed in decimal multiples, e.g. `Ginstr`, and throughput as events per
+/// decimal multiple of the unit, e.g. `instr/B` or `br-miss/Kelem`. This tracks the efficiency
+/// of an algorithm independently of the clock speed of the machine.
+///
+/// Only events of the benchmarking thread in user space are counted.
+///
+/// Requires the `perf_event` feature and is available only on Linux.
+///
+/// # Example
+///
+/// ```no_run
+/// use criterion_decimal_throughput::{DecimalPerfEventMeasurement, PerfEvent};
+///
+/// let measurement = DecimalPerfEventMeasurement::try_new(PerfEvent::Instructions)
+///     .expect("perf events should be available");
+/// let config = criterion::Criterion::default().with_measurement(measurement);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DecimalPerfEventMeasurement {
+    event: PerfEvent,
+}
+
+impl DecimalPerfEventMeasurement {
+    /// Create a measurement counting the given event, checking that it can be counted on this machine.
+    ///
+    /// # Errors
+    ///
+    /// If opening a counter fails, e.g. because the CPU does not support the event
+    /// or access to performance events is restricted by `/proc/sys/kernel/perf_event_paranoid`.
+    pub fn try_new(event: PerfEvent) -> io::Result<Self> {
+        let measurement = DecimalPerfEventMeasurement { event };
+        measurement.open_counter()?;
+        Ok(measurement)
+    }
+
+    /// The counted event.
+    pub fn event(&self) -> PerfEvent {
+        self.event
+    }
+
+    fn open_counter(&self) -> io::Result<Counter> {
+        Builder::new().kind(self.event.hardware()).build()
+    }
+}
+
+impl Measurement for DecimalPerfEventMeasurement {
+    type Intermediate = Counter;
+
+    type Value = u64;
+
+    fn start(&self) -> Self::Intermediate {
+        let mut counter = self
+            .open_counter()
+            .expect("opening the perf event counter should succeed");
+        counter
+            .enable()
+            .expect("enabling the perf event counter should succeed");
+        counter
+    }
+
+    fn end(&self, mut i: Self::Intermediate) -> Self::Value {
+        i.disable()
+            .expect("disabling the perf event counter should succeed");
+        i.read()
+            .expect("reading the perf event counter should succeed")
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        v1 + v2
+    }
+
+    fn zero(&self) -> Self::Value {
+        0
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        self
+    }
+}
+
+impl ValueFormatter for DecimalPerfEventMeasurement {
+    fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+        let multiple = Multiple::for_value(typical_value);
+        let denominator = multiple.denominator();
+
+        for val in values {
+            *val /= denominator;
+        }
+
+        self.event.labels().counts[multiple as usize]
+    }
+
+    fn scale_throughputs(
+        &self,
+        typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let count = exact::count(throughput);
+        let multiple = if count != 0 && crate::is_valid_time(typical_value) {
+            exact::multiple_for_inverse_quotient(count, typical_value)
+        } else {
+            Multiple::One
+        };
+
+        for val in values {
+            *val = if count != 0 && crate::is_valid_time(*val) {
+                exact::scaled_inverse_quotient(count, *val, multiple)
+            } else {
+                0.0
+            };
+        }
+
+        let labels = self.event.labels();
+        match Unit::of(throughput) {
+            Unit::Byte => labels.per_byte[multiple as usize],
+            Unit::Elem => labels.per_elem[multiple as usize],
+        }
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        self.event.labels().name
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn scale_values_decimal_counts() {
+        let measurement = DecimalPerfEventMeasurement {
+            event: PerfEvent::Instructions,
+        };
+        let mut values = [2_000_000.0, 3_000_000.0];
+
+        let result = measurement.scale_values(2_000_000.0, &mut values);
+
+        assert_eq!(result, "Minstr");
+        assert_eq!(values, [2.0, 3.0]);
+    }
+
+    #[test]
+    fn scale_throughputs_events_per_byte() {
+        let measurement = DecimalPerfEventMeasurement {
+            event: PerfEvent::Instructions,
+        };
+        let mut values = [3_000.0];
+
+        let result = measurement.scale_throughputs(3_000.0, &Throughput::Bytes(1_000), &mut values);
+
+        assert_eq!(result, "instr/B");
+        assert_eq!(values, [3.0]);
+    }
+
+    #[test]
+    fn scale_throughputs_zero_events_scale_to_zero() {
+        let measurement = DecimalPerfEventMeasurement {
+            event: PerfEvent::Instructions,
+        };
+        let mut values = [0.0, 3_000.0];
+
+        let result = measurement.scale_throughputs(0.0, &Throughput::Bytes(1_000), &mut values);
+
+        assert_eq!(result, "instr/B");
+        assert_eq!(values, [0.0, 3.0]);
+    }
+
+    #[test]
+    fn scale_throughputs_events_per_kilo_elem() {
+        let measurement = DecimalPerfEventMeasurement {
+            event: PerfEvent::BranchMisses,
+        };
+        let mut values = [500.0];
+
+        let result =
+            measurement.scale_throughputs(500.0, &Throughput::Elements(10_000), &mut values);
+
+        assert_eq!(result, "br-miss/Kelem");
+        assert_eq!(values, [50.0]);
+    }
+}
@@ -0,0 +1,375 @@
+//! Plots of throughput with decimal axis labels.
+//!
+//! The plots in the HTML report of Criterion.rs show the time of an iteration, and the
+//! plotting backend cannot be customized. The functions in this module render
+//! the corresponding charts of throughput instead, with axes in the same decimal units
+//! as the console output, e.g. `GB/s`. Call them after the benchmarks finish.
+//!
+//! Requires the `plots` feature.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use criterion_decimal_throughput::{plots, results};
+//!
+//! // Writes `decimal_violin.svg` and `decimal_lines.svg` next to the reports of Criterion.rs.
+//! plots::render_reports(results::default_output_directory())?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::{
+    results::{self, BenchmarkResult},
+    units::{Multiple, Unit},
+};
+use plotters::prelude::*;
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Name of the violin plot written by [`render_reports`].
+const VIOLIN_FILE: &str = "decimal_violin.svg";
+/// Name of the line chart written by [`render_reports`].
+const LINES_FILE: &str = "decimal_lines.svg";
+/// Name of the directory where Criterion.rs saves the reports of a group.
+const REPORT_DIRECTORY: &str = "report";
+const KDE_POINTS: usize = 100;
+const ROW_HEIGHT: u32 = 60;
+
+/// Render a violin plot of the throughput of each sample of the given benchmarks as an SVG file.
+///
+/// Results without throughput, or with a different unit than the first one, are skipped.
+///
+/// # Errors
+///
+/// Returns an error if a sample cannot be loaded or the plot cannot be written.
+pub fn violin<P: AsRef<Path>>(title: &str, results: &[BenchmarkResult], path: P) -> io::Result<()> {
+    let (unit, results) = comparable(results);
+    let mut distributions = vec![];
+    for result in &results {
+        let sample = result.load_sample()?;
+        let rates: Vec<f64> = sample
+            .nanos_per_iteration()
+            .filter_map(|nanos| result.units_per_second(nanos))
+            .collect();
+        distributions.push((result.full_id.as_str(), rates));
+    }
+
+    let max_rate = distributions
+        .iter()
+        .flat_map(|(_, rates)| rates)
+        .fold(0.0, |max: f64, rate| max.max(*rate));
+    let multiple = Multiple::for_value(max_rate);
+    let names: Vec<&str> = distributions.iter().map(|(name, _)| *name).collect();
+    let rows = distributions.len().max(1);
+
+    let root =
+        SVGBackend::new(path.as_ref(), (960, 80 + ROW_HEIGHT * rows as u32)).into_drawing_area();
+    root.fill(&WHITE).map_err(plot_error)?;
+    let mut chart = ChartBuilder::on(&root)
+        .caption(format!("{}: Violin plot", title), ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(200)
+        .build_cartesian_2d(
+            0.0..max_rate * 1.1 / multiple.denominator(),
+            -0.5..rows as f64 - 0.5,
+        )
+        .map_err(plot_error)?;
+    chart
+        .configure_mesh()
+        .x_desc(axis_label(unit, multiple))
+        .y_labels(2 * rows + 1)
+        .y_label_formatter(&|y| row_name(&names, *y))
+        .disable_y_mesh()
+        .draw()
+        .map_err(plot_error)?;
+
+    for (row, (_, rates)) in distributions.iter().enumerate() {
+        let scaled: Vec<f64> = rates.iter().map(|r| r / multiple.denominator()).collect();
+        let center = row as f64;
+        let outline = kde(&scaled);
+        let points = outline
+            .iter()
+            .map(|&(x, density)| (x, center + 0.45 * density))
+            .chain(
+                outline
+                    .iter()
+                    .rev()
+                    .map(|&(x, density)| (x, center - 0.45 * density)),
+            );
+        chart
+            .draw_series(std::iter::once(Polygon::new(
+                points.collect::<Vec<_>>(),
+                BLUE.mix(0.5),
+            )))
+            .map_err(plot_error)?;
+    }
+
+    root.present().map_err(plot_error)
+}
+
+/// Render a line chart of the typical throughput of the given benchmarks against their numeric
+/// parameter as an SVG file, with one line per benchmark function.
+///
+/// Results without throughput, with a different unit than the first one,
+/// or without a numeric parameter are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the plot cannot be written.
+pub fn lines<P: AsRef<Path>>(title: &str, results: &[BenchmarkResult], path: P) -> io::Result<()> {
+    let (unit, results) = comparable(results);
+    let mut series: BTreeMap<&str, Vec<(f64, f64)>> = BTreeMap::new();
+    for result in &results {
+        let parameter = match result.value_str.as_deref().and_then(|v| v.parse().ok()) {
+            Some(parameter) => parameter,
+            None => continue,
+        };
+        if let Some(rate) = result.typical_units_per_second() {
+            series
+                .entry(result.function_id.as_deref().unwrap_or(&result.group_id))
+                .or_default()
+                .push((parameter, rate));
+        }
+    }
+
+    let points = series.values().flatten();
+    let max_parameter = points.clone().fold(0.0, |max: f64, (x, _)| max.max(*x));
+    let max_rate = points.fold(0.0, |max: f64, (_, y)| max.max(*y));
+    let multiple = Multiple::for_value(max_rate);
+
+    let root = SVGBackend::new(path.as_ref(), (960, 540)).into_drawing_area();
+    root.fill(&WHITE).map_err(plot_error)?;
+    let mut chart = ChartBuilder::on(&root)
+        .caption(format!("{}: Comparison", title), ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(80)
+        .build_cartesian_2d(
+            0.0..max_parameter * 1.05,
+            0.0..max_rate * 1.1 / multiple.denominator(),
+        )
+        .map_err(plot_error)?;
+    chart
+        .configure_mesh()
+        .x_desc("Input")
+        .y_desc(axis_label(unit, multiple))
+        .draw()
+        .map_err(plot_error)?;
+
+    for (index, (name, mut points)) in series.into_iter().enumerate() {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let color = Palette99::pick(index).to_rgba();
+        chart
+            .draw_series(LineSeries::new(
+                points
+                    .into_iter()
+                    .map(|(x, y)| (x, y / multiple.denominator())),
+                color.stroke_width(2),
+            ))
+            .map_err(plot_error)?
+            .label(name)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    }
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(plot_error)?;
+
+    root.present().map_err(plot_error)
+}
+
+/// Render the throughput plots of every group in the output directory of Criterion.rs
+/// into the report directory of the group, next to the plots of Criterion.rs.
+///
+/// Every group gets a [violin plot](violin) in `decimal_violin.svg`. Groups with numeric
+/// parameters also get a [line chart](lines) in `decimal_lines.svg`.
+/// Returns the paths of the written files.
+///
+/// # Errors
+///
+/// Returns an error if the results cannot be loaded or a plot cannot be written.
+pub fn render_reports<P: AsRef<Path>>(output_directory: P) -> io::Result<Vec<PathBuf>> {
+    let mut groups: BTreeMap<PathBuf, Vec<BenchmarkResult>> = BTreeMap::new();
+    for result in results::load_results(output_directory)? {
+        if result.throughput.is_some() {
+            if let Some(directory) = group_directory(&result) {
+                groups.entry(directory).or_default().push(result);
+            }
+        }
+    }
+
+    let mut written = vec![];
+    for (directory, results) in groups {
+        let report_directory = directory.join(REPORT_DIRECTORY);
+        std::fs::create_dir_all(&report_directory)?;
+        let title = &results[0].group_id;
+
+        let violin_path = report_directory.join(VIOLIN_FILE);
+        violin(title, &results, &violin_path)?;
+        written.push(violin_path);
+
+        let numeric = results.iter().any(|r| {
+            r.value_str
+                .as_deref()
+                .is_some_and(|v| v.parse::<f64>().is_ok())
+        });
+        if numeric {
+            let lines_path = report_directory.join(LINES_FILE);
+            lines(title, &results, &lines_path)?;
+            written.push(lines_path);
+        }
+    }
+
+    Ok(written)
+}
+
+/// Results with throughput in the same unit as the first of them.
+fn comparable(results: &[BenchmarkResult]) -> (Unit, Vec<&BenchmarkResult>) {
+    let unit = results
+        .iter()
+        .find_map(|r| r.throughput.as_ref())
+        .map_or(Unit::Byte, Unit::of);
+    let results = results
+        .iter()
+        .filter(|r| r.throughput.as_ref().map(Unit::of) == Some(unit))
+        .collect();
+    (unit, results)
+}
+
+/// The directory of the group of a result, which is above the directories
+/// of the function and the parameter.
+fn group_directory(result: &BenchmarkResult) -> Option<PathBuf> {
+    let depth =
+        1 + usize::from(result.function_id.is_some()) + usize::from(result.value_str.is_some());
+    result.directory.ancestors().nth(depth).map(Path::to_owned)
+}
+
+fn axis_label(unit: Unit, multiple: Multiple) -> String {
+    format!("Throughput ({})", unit.rate_label(multiple).trim())
+}
+
+fn row_name(names: &[&str], y: f64) -> String {
+    let row = y.floor();
+    if row < 0.0 || y - row != 0.5 {
+        return String::new();
+    }
+    names
+        .get(row as usize)
+        .map_or_else(String::new, |n| n.to_string())
+}
+
+/// Gaussian kernel density estimate of the values, normalized to a maximum of one.
+fn kde(values: &[f64]) -> Vec<(f64, f64)> {
+    let n = values.len() as f64;
+    if values.is_empty() {
+        return vec![];
+    }
+    let mean = values.iter().sum::<f64>() / n;
+    let std_dev = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+    // Silverman's rule of thumb, with a fallback for constant values.
+    let bandwidth = if std_dev > 0.0 {
+        1.06 * std_dev * n.powf(-0.2)
+    } else {
+        mean.abs().max(1.0) * 1e-3
+    };
+
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min) - 3.0 * bandwidth;
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max) + 3.0 * bandwidth;
+    let step = (max - min) / (KDE_POINTS - 1) as f64;
+
+    let densities: Vec<(f64, f64)> = (0..KDE_POINTS)
+        .map(|i| {
+            let x = min + step * i as f64;
+            let density = values
+                .iter()
+                .map(|v| (-0.5 * ((x - v) / bandwidth).powi(2)).exp())
+                .sum::<f64>();
+            (x, density)
+        })
+        .collect();
+    let max_density = densities.iter().fold(0.0, |max: f64, (_, d)| max.max(*d));
+
+    densities
+        .into_iter()
+        .map(|(x, d)| (x, d / max_density))
+        .collect()
+}
+
+fn plot_error<E: std::error::Error + Send + Sync>(err: DrawingAreaErrorKind<E>) -> io::Error {
+    io::Error::other(err.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::results::test::{save_result, save_sample};
+    use criterion::Throughput;
+    use std::fs;
+
+    #[test]
+    fn render_reports_writes_plots_with_decimal_axes() {
+        let dir = tempfile::tempdir().unwrap();
+        for (full_id, nanos) in [("group/copy/1000", 500.0), ("group/copy/2000", 800.0)] {
+            save_result(
+                dir.path(),
+                "new",
+                full_id,
+                Some(Throughput::Bytes(1_000_000)),
+                nanos,
+            );
+            save_sample(
+                dir.path(),
+                "new",
+                full_id,
+                &[1.0, 2.0, 3.0],
+                &[nanos, 2.0 * nanos * 1.1, 3.0 * nanos * 0.9],
+            );
+        }
+
+        let written = render_reports(dir.path()).unwrap();
+
+        let report = dir.path().join("group").join("report");
+        assert_eq!(written, [report.join(VIOLIN_FILE), report.join(LINES_FILE)]);
+        let violin = fs::read_to_string(&written[0]).unwrap();
+        assert!(violin.contains("Throughput (TB/s)"));
+        assert!(violin.contains("group/copy/1000"));
+        let lines = fs::read_to_string(&written[1]).unwrap();
+        assert!(lines.contains("Throughput (TB/s)"));
+    }
+
+    #[test]
+    fn render_reports_skips_lines_without_numeric_parameters() {
+        let dir = tempfile::tempdir().unwrap();
+        save_result(
+            dir.path(),
+            "new",
+            "group/copy",
+            Some(Throughput::Elements(1_000)),
+            1_000.0,
+        );
+        save_sample(dir.path(), "new", "group/copy", &[1.0], &[1_000.0]);
+
+        let written = render_reports(dir.path()).unwrap();
+
+        assert_eq!(
+            written,
+            [dir.path().join("group").join("report").join(VIOLIN_FILE)]
+        );
+        let violin = fs::read_to_string(&written[0]).unwrap();
+        assert!(violin.contains("Throughput (Gelem/s)"));
+    }
+
+    #[test]
+    fn kde_is_normalized() {
+        let result = kde(&[1.0, 2.0, 2.0, 3.0]);
+
+        assert_eq!(result.len(), KDE_POINTS);
+        let max = result.iter().fold(0.0, |max: f64, (_, d)| max.max(*d));
+        assert_eq!(max, 1.0);
+    }
+}
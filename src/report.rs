@@ -0,0 +1,1199 @@
+//! Reports of benchmark results expressed in decimal throughput.
+//!
+//! Criterion.rs reports changes against the baseline as relative changes of time.
+//! The functions in this module express them in throughput instead, based on the
+//! results it saved in its output directory (see [`crate::results`]).
+//! Call them after the benchmark group finishes.
+
+#[cfg(feature = "github_action_benchmark")]
+use crate::units::Multiple;
+#[cfg(any(feature = "github_action_benchmark", feature = "openmetrics"))]
+use crate::units::Unit;
+use crate::{
+    format::{self, Align},
+    results::{self, BenchmarkResult},
+};
+use criterion::{measurement::ValueFormatter, Throughput};
+#[cfg(feature = "cargo_criterion")]
+use std::io::BufRead;
+#[cfg(feature = "openmetrics")]
+use std::time::Duration;
+use std::{
+    borrow::Cow,
+    fmt::{self, Display},
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
+
+/// Change of throughput of a single benchmark compared to the baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThroughputChange {
+    /// Full identifier of the benchmark, e.g. `group/function`.
+    pub full_id: String,
+    /// Throughput configured for the benchmark.
+    pub throughput: Throughput,
+    /// Throughput in the baseline, in base units per second.
+    pub before: f64,
+    /// Throughput in the latest run, in base units per second.
+    pub after: f64,
+}
+
+impl ThroughputChange {
+    /// Relative change of throughput, where e.g. `0.16` means it is 16% higher than in the baseline.
+    pub fn relative_change(&self) -> f64 {
+        self.after / self.before - 1.0
+    }
+}
+
+impl Display for ThroughputChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: was {}, now {} ({:+.2}%)",
+            self.full_id,
+            format::units_per_second(&self.throughput, self.before),
+            format::units_per_second(&self.throughput, self.after),
+            self.relative_change() * 100.0
+        )
+    }
+}
+
+/// Throughput changes of the given results compared to the baseline Criterion.rs compared them to.
+///
+/// This uses the relative change of the mean computed by Criterion.rs during the run,
+/// so it works with the default behavior of overwriting the baseline after each run.
+/// Results without throughput or without a comparison are skipped.
+pub fn throughput_changes(results: &[BenchmarkResult]) -> Vec<ThroughputChange> {
+    results.iter().filter_map(throughput_change).collect()
+}
+
+fn throughput_change(result: &BenchmarkResult) -> Option<ThroughputChange> {
+    let change = result.change.as_ref()?.mean.point_estimate;
+    let after_nanos = result.estimates.mean.point_estimate;
+    let before_nanos = after_nanos / (1.0 + change);
+
+    Some(ThroughputChange {
+        full_id: result.full_id.clone(),
+        throughput: result.throughput.clone()?,
+        before: result.units_per_second(before_nanos)?,
+        after: result.units_per_second(after_nanos)?,
+    })
+}
+
+/// Throughput changes between two sets of results, e.g. loaded with [`results::load_baseline`]
+/// and [`results::load_results`].
+///
+/// Benchmarks are matched by their full id and compared by their typical estimates.
+/// Results without throughput or without a counterpart in the baseline are skipped.
+pub fn compare_throughputs(
+    baseline: &[BenchmarkResult],
+    results: &[BenchmarkResult],
+) -> Vec<ThroughputChange> {
+    results
+        .iter()
+        .filter_map(|result| {
+            let base = baseline.iter().find(|b| b.full_id == result.full_id)?;
+
+            Some(ThroughputChange {
+                full_id: result.full_id.clone(),
+                throughput: result.throughput.clone()?,
+                before: base.typical_units_per_second()?,
+                after: result.typical_units_per_second()?,
+            })
+        })
+        .collect()
+}
+
+/// Print the throughput changes of all benchmarks in the given group to stdout,
+/// one line per benchmark, e.g. `parse/simd: was 1.8000 GB/s, now 2.1000 GB/s (+16.67%)`.
+///
+/// Results are read from the [default output directory](results::default_output_directory).
+///
+/// # Errors
+///
+/// Returns an error if the results could not be read.
+///
+/// # Example
+///
+/// ```
+/// use criterion::Throughput;
+/// use criterion_decimal_throughput::{report, Criterion};
+///
+/// fn example_bench(c: &mut Criterion) {
+///     let mut group = c.benchmark_group("example_name");
+///     group.throughput(Throughput::Bytes(1_000_000));
+///     // Add your benchmarks to the group here...
+///     group.finish();
+///
+///     report::print_throughput_changes("example_name").unwrap();
+/// }
+/// ```
+pub fn print_throughput_changes(group_id: &str) -> io::Result<()> {
+    let results: Vec<_> = results::load_results(results::default_output_directory())?
+        .into_iter()
+        .filter(|result| result.group_id == group_id)
+        .collect();
+
+    for change in throughput_changes(&results) {
+        println!("{}", change);
+    }
+
+    Ok(())
+}
+
+/// Render the given results as an aligned plain text table with the benchmark name, its typical
+/// throughput in decimal units, and the change of throughput compared to the baseline, e.g.:
+///
+/// ```text
+/// Benchmark     Throughput   Change
+/// group/rows   2.4272 GB/s  +11.11%
+/// group/setup  1.0000 MB/s        -
+/// ```
+///
+/// The change is the one computed by Criterion.rs during the run, see [`throughput_changes`].
+/// Results without throughput are skipped.
+pub fn throughput_table(results: &[BenchmarkResult]) -> String {
+    let mut rows = vec![["Benchmark", "Throughput", "Change"].map(str::to_owned)];
+    rows.extend(results.iter().filter_map(|result| {
+        let throughput = result.throughput.as_ref()?;
+        let formatted = format::Plain
+            .format_throughput(throughput, result.estimates.typical().point_estimate)
+            .trim_start()
+            .to_owned();
+        let change = throughput_change(result).map_or_else(
+            || "-".to_owned(),
+            |change| format!("{:+.2}%", change.relative_change() * 100.0),
+        );
+        Some([result.full_id.clone(), formatted, change])
+    }));
+    format::align_columns(&mut rows, [Align::Left, Align::Right, Align::Right]);
+
+    format::join_rows(&rows)
+}
+
+/// Render the given results as a markdown table with the benchmark name, its typical throughput
+/// in decimal units, and the change of throughput compared to the baseline.
+///
+/// The change is the one computed by Criterion.rs during the run, see [`throughput_changes`].
+/// Results without throughput are skipped.
+///
+/// # Example
+///
+/// ```no_run
+/// use criterion_decimal_throughput::{report, results};
+///
+/// let results = results::load_results(results::default_output_directory())?;
+/// std::fs::write("throughput.md", report::markdown_table(&results))?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn markdown_table(results: &[BenchmarkResult]) -> String {
+    let mut table = String::from("| Benchmark | Throughput | Change |\n|---|--:|--:|\n");
+
+    for result in results {
+        let (throughput, units_per_second) = match (
+            result.throughput.as_ref(),
+            result.typical_units_per_second(),
+        ) {
+            (Some(throughput), Some(units_per_second)) => (throughput, units_per_second),
+            _ => continue,
+        };
+        let change = throughput_change(result).map_or_else(
+            || "-".to_owned(),
+            |change| format!("{:+.2}%", change.relative_change() * 100.0),
+        );
+
+        table.push_str(&format!(
+            "| {} | {} | {} |\n",
+            format::markdown_cell(&result.full_id),
+            format::units_per_second(throughput, units_per_second),
+            change
+        ));
+    }
+
+    table
+}
+
+/// Print a [markdown table](markdown_table) of all benchmarks in the given group to stdout.
+///
+/// Results are read from the [default output directory](results::default_output_directory).
+///
+/// # Errors
+///
+/// Returns an error if the results could not be read.
+pub fn print_markdown_table(group_id: &str) -> io::Result<()> {
+    let results: Vec<_> = results::load_results(results::default_output_directory())?
+        .into_iter()
+        .filter(|result| result.group_id == group_id)
+        .collect();
+
+    print!("{}", markdown_table(&results));
+    Ok(())
+}
+
+/// Write the given results as CSV, one row per benchmark, with throughput in decimal units.
+///
+/// The columns are `group`, `id`, `mean`, `median`, `lower_bound`, `upper_bound`, and `unit`.
+/// The bounds are of the confidence interval of the mean. All values in a row are scaled
+/// to the same unit, chosen based on the mean, e.g. `MB/s`.
+/// Results without throughput are skipped.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+///
+/// # Example
+///
+/// ```no_run
+/// use criterion_decimal_throughput::{report, results};
+/// use std::fs::File;
+///
+/// let results = results::load_results(results::default_output_directory())?;
+/// report::write_csv(&results, File::create("throughput.csv")?)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn write_csv<W: Write>(results: &[BenchmarkResult], mut writer: W) -> io::Result<()> {
+    let formatter = format::Plain;
+    writeln!(writer, "group,id,mean,median,lower_bound,upper_bound,unit")?;
+
+    for result in results {
+        let throughput = match result.throughput.as_ref() {
+            Some(throughput) => throughput,
+            None => continue,
+        };
+        let mean = &result.estimates.mean;
+        // A longer time means lower throughput, so the bounds swap places.
+        let mut values = [
+            mean.point_estimate,
+            result.estimates.median.point_estimate,
+            mean.confidence_interval.upper_bound,
+            mean.confidence_interval.lower_bound,
+        ];
+        let unit = formatter.scale_throughputs(mean.point_estimate, throughput, &mut values);
+
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{}",
+            csv_field(&result.group_id),
+            csv_field(&result.full_id),
+            values[0],
+            values[1],
+            values[2],
+            values[3],
+            unit.trim()
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Format of raw samples written by [`export_samples`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SampleFormat {
+    /// CSV, written by [`write_sample_csv`].
+    Csv,
+    /// Newline-delimited JSON, written by [`write_sample_ndjson`].
+    Ndjson,
+}
+
+/// Write the raw measurements of the given result as CSV, one row per measurement,
+/// with the throughput derived from its average time of an iteration.
+///
+/// The columns are `iterations`, `elapsed_ns`, `throughput`, and `unit`. Throughput is
+/// in base units per second, e.g. `B/s`, as with
+/// [`DecimalByteMeasurement::scale_throughputs_for_machines`](crate::DecimalByteMeasurement::scale_throughputs_for_machines).
+/// The last two columns are empty if the benchmark had no throughput.
+///
+/// # Errors
+///
+/// Returns an error if the sample of the result cannot be read or writing fails.
+///
+/// # Example
+///
+/// ```no_run
+/// use criterion_decimal_throughput::{report, results};
+/// use std::fs::File;
+///
+/// let results = results::load_results(results::default_output_directory())?;
+/// report::write_sample_csv(&results[0], File::create("sample.csv")?)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn write_sample_csv<W: Write>(result: &BenchmarkResult, mut writer: W) -> io::Result<()> {
+    writeln!(writer, "iterations,elapsed_ns,throughput,unit")?;
+
+    for row in sample_rows(result)? {
+        match row.throughput {
+            Some((throughput, unit)) => writeln!(
+                writer,
+                "{},{},{},{}",
+                row.iterations, row.elapsed_ns, throughput, unit
+            )?,
+            None => writeln!(writer, "{},{},,", row.iterations, row.elapsed_ns)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Write the raw measurements of the given result as newline-delimited JSON, one object per
+/// measurement, with the same fields as the columns of [`write_sample_csv`].
+///
+/// The `throughput` and `unit` fields are `null` if the benchmark had no throughput.
+///
+/// # Errors
+///
+/// Returns an error if the sample of the result cannot be read or writing fails.
+pub fn write_sample_ndjson<W: Write>(result: &BenchmarkResult, mut writer: W) -> io::Result<()> {
+    for row in sample_rows(result)? {
+        let (throughput, unit) = row.throughput.unzip();
+        let line = serde_json::json!({
+            "iterations": row.iterations,
+            "elapsed_ns": row.elapsed_ns,
+            "throughput": throughput,
+            "unit": unit,
+        });
+        writeln!(writer, "{}", line)?;
+    }
+
+    Ok(())
+}
+
+/// Write the raw measurements of every result with throughput next to the ones saved by Criterion.rs,
+/// e.g. to `target/criterion/group/function/new/throughput_sample.csv`.
+///
+/// Returns the paths of the written files.
+///
+/// # Errors
+///
+/// Returns an error if a sample cannot be read or a file cannot be written.
+///
+/// # Example
+///
+/// ```no_run
+/// use criterion_decimal_throughput::{report, results};
+///
+/// let results = results::load_results(results::default_output_directory())?;
+/// report::export_samples(&results, report::SampleFormat::Ndjson)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn export_samples(
+    results: &[BenchmarkResult],
+    format: SampleFormat,
+) -> io::Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+
+    for result in results.iter().filter(|r| r.throughput.is_some()) {
+        let file_name = match format {
+            SampleFormat::Csv => "throughput_sample.csv",
+            SampleFormat::Ndjson => "throughput_sample.ndjson",
+        };
+        let path = result.directory.join(file_name);
+        let writer = BufWriter::new(File::create(&path)?);
+        match format {
+            SampleFormat::Csv => write_sample_csv(result, writer)?,
+            SampleFormat::Ndjson => write_sample_ndjson(result, writer)?,
+        }
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+/// Raw measurement with the derived throughput and its unit.
+struct SampleRow {
+    iterations: f64,
+    elapsed_ns: f64,
+    throughput: Option<(f64, &'static str)>,
+}
+
+fn sample_rows(result: &BenchmarkResult) -> io::Result<Vec<SampleRow>> {
+    let sample = result.load_sample()?;
+    let mut rates: Vec<_> = sample.nanos_per_iteration().collect();
+    let unit = result
+        .throughput
+        .as_ref()
+        .map(|throughput| format::throughputs_for_machines(throughput, &mut rates));
+
+    Ok(sample
+        .iters
+        .iter()
+        .zip(&sample.times)
+        .zip(rates)
+        .map(|((iterations, elapsed_ns), rate)| SampleRow {
+            iterations: *iterations,
+            elapsed_ns: *elapsed_ns,
+            throughput: unit.map(|unit| (rate, unit)),
+        })
+        .collect())
+}
+
+/// Write the given results in the `customBiggerIsBetter` JSON format of
+/// [github-action-benchmark](https://github.com/benchmark-action/github-action-benchmark),
+/// with throughput in decimal units.
+///
+/// Each benchmark is one entry named by its full id, with the value being its typical throughput
+/// in base units per second, i.e. `B/s` or `elem/s`, and the range being the half-width of its
+/// confidence interval. The action compares only the values of entries with the same name,
+/// so the unit does not depend on the throughput, e.g. switch from `MB/s` to `GB/s`,
+/// which would chart as a regression of three orders of magnitude.
+/// Results without throughput are skipped.
+///
+/// Requires the `github_action_benchmark` feature.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+///
+/// # Example
+///
+/// ```no_run
+/// use criterion_decimal_throughput::{report, results};
+/// use std::fs::File;
+///
+/// let results = results::load_results(results::default_output_directory())?;
+/// report::write_github_action_benchmark_json(&results, File::create("output.json")?)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[cfg(feature = "github_action_benchmark")]
+pub fn write_github_action_benchmark_json<W: Write>(
+    results: &[BenchmarkResult],
+    writer: W,
+) -> io::Result<()> {
+    let mut entries = vec![];
+
+    for result in results {
+        let throughput = match result.throughput.as_ref() {
+            Some(throughput) => throughput,
+            None => continue,
+        };
+        let typical = result.estimates.typical();
+        let rate = |nanos| {
+            result
+                .units_per_second(nanos)
+                .expect("results without throughput are skipped")
+        };
+        let (value, slowest, fastest) = (
+            rate(typical.point_estimate),
+            rate(typical.confidence_interval.upper_bound),
+            rate(typical.confidence_interval.lower_bound),
+        );
+        let unit = Unit::of(throughput).rate_label(Multiple::One);
+
+        entries.push(serde_json::json!({
+            "name": result.full_id,
+            "unit": unit.trim(),
+            "value": value,
+            "range": format!("± {}", (fastest - slowest) / 2.0),
+        }));
+    }
+
+    serde_json::to_writer_pretty(writer, &entries)?;
+    Ok(())
+}
+
+/// Write the typical throughput of the given results as gauges in the
+/// [OpenMetrics](https://openmetrics.io) text format.
+///
+/// Throughput is written in base units per second, in the `criterion_throughput_bytes_per_second`
+/// and `criterion_throughput_elements_per_second` families, labeled with the `group` and the full `id`
+/// of the benchmark. Results without throughput are skipped.
+///
+/// Requires the `openmetrics` feature.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+///
+/// # Example
+///
+/// ```no_run
+/// use criterion_decimal_throughput::{report, results};
+/// use std::fs::File;
+///
+/// let results = results::load_results(results::default_output_directory())?;
+/// report::write_openmetrics(&results, File::create("throughput.prom")?)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[cfg(feature = "openmetrics")]
+pub fn write_openmetrics<W: Write>(results: &[BenchmarkResult], mut writer: W) -> io::Result<()> {
+    let families = [("bytes", Unit::Byte), ("elements", Unit::Elem)];
+
+    for (name, unit) in families {
+        let samples: Vec<_> = results
+            .iter()
+            .filter(|r| r.throughput.as_ref().map(Unit::of) == Some(unit))
+            .filter_map(|r| Some((r, r.typical_units_per_second()?)))
+            .collect();
+        if samples.is_empty() {
+            continue;
+        }
+
+        let family = format!("criterion_throughput_{}_per_second", name);
+        writeln!(writer, "# TYPE {} gauge", family)?;
+        writeln!(writer, "# UNIT {} {}_per_second", family, name)?;
+        writeln!(
+            writer,
+            "# HELP {} Typical throughput in {} per second.",
+            family, name
+        )?;
+        for (result, units_per_second) in samples {
+            writeln!(
+                writer,
+                "{}{{group=\"{}\",id=\"{}\"}} {}",
+                family,
+                label_value(&result.group_id),
+                label_value(&result.full_id),
+                units_per_second
+            )?;
+        }
+    }
+
+    writeln!(writer, "# EOF")
+}
+
+/// Push the typical throughput of the given results to a
+/// [Prometheus Pushgateway](https://github.com/prometheus/pushgateway) under the given job,
+/// in the format of [`write_openmetrics`].
+///
+/// The address is the host and port of the gateway, e.g. `localhost:9091`.
+/// Metrics previously pushed for the job are replaced. Connecting to the gateway,
+/// sending the metrics and receiving its response each time out after 10 seconds,
+/// see [`push_to_gateway_with_timeout`].
+///
+/// Requires the `openmetrics` feature.
+///
+/// # Errors
+///
+/// Returns an error if connecting to the gateway fails or times out,
+/// or it does not accept the metrics.
+///
+/// # Example
+///
+/// ```no_run
+/// use criterion_decimal_throughput::{report, results};
+///
+/// let results = results::load_results(results::default_output_directory())?;
+/// report::push_to_gateway(&results, "localhost:9091", "benchmarks")?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[cfg(feature = "openmetrics")]
+pub fn push_to_gateway(results: &[BenchmarkResult], address: &str, job: &str) -> io::Result<()> {
+    push_to_gateway_with_timeout(results, address, job, Duration::from_secs(10))
+}
+
+/// Push the typical throughput of the given results to a Prometheus Pushgateway
+/// as with [`push_to_gateway`], timing out after the given duration instead.
+///
+/// Requires the `openmetrics` feature.
+///
+/// # Errors
+///
+/// Returns an error if connecting to the gateway fails or times out,
+/// or it does not accept the metrics. A timeout is an error of the [`io::ErrorKind::TimedOut`] kind.
+///
+/// # Panics
+///
+/// If the timeout is zero.
+#[cfg(feature = "openmetrics")]
+pub fn push_to_gateway_with_timeout(
+    results: &[BenchmarkResult],
+    address: &str,
+    job: &str,
+    timeout: Duration,
+) -> io::Result<()> {
+    use std::{
+        io::Read,
+        net::{TcpStream, ToSocketAddrs},
+    };
+
+    assert!(!timeout.is_zero(), "timeout must be positive");
+    let timed_out = |err: io::Error| match err.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("pushgateway at {} did not respond in time", address),
+        ),
+        _ => err,
+    };
+
+    let mut body = vec![];
+    write_openmetrics(results, &mut body)?;
+
+    let mut last_err = None;
+    let mut stream = None;
+    for addr in address.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(connected) => {
+                stream = Some(connected);
+                break;
+            }
+            Err(err) => last_err = Some(err),
+        }
+    }
+    let mut stream = match (stream, last_err) {
+        (Some(stream), _) => stream,
+        (None, Some(err)) => return Err(timed_out(err)),
+        (None, None) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("pushgateway address {} did not resolve", address),
+            ))
+        }
+    };
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    write!(
+        stream,
+        "PUT /metrics/job/{} HTTP/1.1\r\n\
+         Host: {}\r\n\
+         Content-Type: application/openmetrics-text; version=1.0.0; charset=utf-8\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        path_segment(job),
+        address,
+        body.len()
+    )
+    .map_err(timed_out)?;
+    stream.write_all(&body).map_err(timed_out)?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).map_err(timed_out)?;
+    let status = response.split_whitespace().nth(1).unwrap_or_default();
+    if status.starts_with('2') {
+        Ok(())
+    } else {
+        let status_line = response.lines().next().unwrap_or_default();
+        Err(io::Error::other(format!(
+            "pushgateway rejected the metrics: {}",
+            status_line
+        )))
+    }
+}
+
+/// Emit a [`tracing`] event for each of the given results, with throughput in decimal units.
+///
+/// Events are emitted at the `INFO` level with the `criterion_decimal_throughput` target and
+/// the fields `group`, `id`, `value`, `lower_bound`, `upper_bound`, and `unit`, where the values
+/// are the typical throughput and its confidence interval scaled to the unit, e.g. `MB/s`,
+/// as in the console output. The unscaled throughput is in `units_per_second`.
+/// Results without throughput are skipped.
+///
+/// Requires the `tracing` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// use criterion_decimal_throughput::{report, results};
+///
+/// let results = results::load_results(results::default_output_directory())?;
+/// report::trace_results(&results);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[cfg(feature = "tracing")]
+pub fn trace_results(results: &[BenchmarkResult]) {
+    let formatter = format::Plain;
+
+    for result in results {
+        let (throughput, units_per_second) = match (
+            result.throughput.as_ref(),
+            result.typical_units_per_second(),
+        ) {
+            (Some(throughput), Some(units_per_second)) => (throughput, units_per_second),
+            _ => continue,
+        };
+        let typical = result.estimates.typical();
+        let mut values = [
+            typical.point_estimate,
+            typical.confidence_interval.upper_bound,
+            typical.confidence_interval.lower_bound,
+        ];
+        let unit = formatter.scale_throughputs(typical.point_estimate, throughput, &mut values);
+
+        tracing::info!(
+            target: "criterion_decimal_throughput",
+            group = %result.group_id,
+            id = %result.full_id,
+            value = values[0],
+            lower_bound = values[1],
+            upper_bound = values[2],
+            unit = unit.trim(),
+            units_per_second,
+            "{}: {}",
+            result.full_id,
+            format::units_per_second(throughput, units_per_second),
+        );
+    }
+}
+
+/// Add decimal throughput to the JSON messages of `cargo criterion --message-format=json`.
+///
+/// Under cargo-criterion the console output is formatted by the measurement, but the
+/// `benchmark-complete` messages only carry the raw throughput per iteration. Every such message
+/// read from `reader` gets a `decimal_throughput` field with one entry per throughput,
+/// with the `estimate`, `lower_bound`, and `upper_bound` scaled the same way as the console output
+/// and the decimal `unit`, e.g. `"GB/s"`. All other lines are copied to `writer` unchanged.
+///
+/// Requires the `cargo_criterion` feature.
+///
+/// # Errors
+///
+/// Returns an error if reading or writing fails.
+///
+/// # Example
+///
+/// Read the messages from the standard input, e.g. in a binary run as
+/// `cargo criterion --message-format=json | decimal-messages > messages.json`.
+///
+/// ```no_run
+/// use criterion_decimal_throughput::report;
+/// use std::io;
+///
+/// report::add_decimal_throughput_to_messages(io::stdin().lock(), io::stdout().lock())?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[cfg(feature = "cargo_criterion")]
+pub fn add_decimal_throughput_to_messages<R: BufRead, W: Write>(
+    reader: R,
+    mut writer: W,
+) -> io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if let Ok(mut message) = serde_json::from_str::<serde_json::Value>(&line) {
+            if add_decimal_throughput(&mut message) {
+                serde_json::to_writer(&mut writer, &message)?;
+                writeln!(writer)?;
+                continue;
+            }
+        }
+        writeln!(writer, "{}", line)?;
+    }
+    Ok(())
+}
+
+/// Add the `decimal_throughput` field to a `benchmark-complete` message,
+/// returning whether the message was changed.
+#[cfg(feature = "cargo_criterion")]
+fn add_decimal_throughput(message: &mut serde_json::Value) -> bool {
+    if message["reason"] != "benchmark-complete" || message["typical"]["unit"] != "ns" {
+        return false;
+    }
+    let typical = &message["typical"];
+    let (estimate, lower_bound, upper_bound) = match (
+        typical["estimate"].as_f64(),
+        typical["lower_bound"].as_f64(),
+        typical["upper_bound"].as_f64(),
+    ) {
+        (Some(estimate), Some(lower_bound), Some(upper_bound)) => {
+            (estimate, lower_bound, upper_bound)
+        }
+        _ => return false,
+    };
+    let throughputs = match message["throughput"].as_array() {
+        Some(throughputs) if !throughputs.is_empty() => throughputs,
+        _ => return false,
+    };
+
+    let formatter = format::Plain;
+    let mut decimal = vec![];
+    for entry in throughputs {
+        let throughput = match (entry["per_iteration"].as_u64(), entry["unit"].as_str()) {
+            (Some(count), Some("bytes")) => Throughput::Bytes(count),
+            (Some(count), Some("elements")) => Throughput::Elements(count),
+            _ => continue,
+        };
+        // Longer times mean lower throughput, so the bounds swap.
+        let mut values = [estimate, upper_bound, lower_bound];
+        let unit = formatter.scale_throughputs(estimate, &throughput, &mut values);
+
+        decimal.push(serde_json::json!({
+            "estimate": values[0],
+            "lower_bound": values[1],
+            "upper_bound": values[2],
+            "unit": unit.trim(),
+        }));
+    }
+
+    message["decimal_throughput"] = serde_json::Value::Array(decimal);
+    true
+}
+
+#[cfg(feature = "openmetrics")]
+fn label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(feature = "openmetrics")]
+fn path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::results::test::{save_change, save_result, save_sample};
+
+    #[test]
+    fn throughput_changes_from_criterion_comparison() {
+        let dir = tempfile::tempdir().unwrap();
+        save_result(
+            dir.path(),
+            "new",
+            "group/a",
+            Some(Throughput::Bytes(2_000)),
+            1_000.0,
+        );
+        save_change(dir.path(), "group/a", -0.2);
+        let results = results::load_results(dir.path()).unwrap();
+
+        let changes = throughput_changes(&results);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            changes[0].to_string(),
+            "group/a: was 1.6000 GB/s, now 2.0000 GB/s (+25.00%)"
+        );
+    }
+
+    #[test]
+    fn compare_throughputs_matches_by_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let bytes = Some(Throughput::Bytes(1_000));
+        save_result(dir.path(), "new", "group/a", bytes.clone(), 1_000.0);
+        save_result(dir.path(), "new", "group/b", bytes.clone(), 1_000.0);
+        save_result(dir.path(), "main", "group/a", bytes, 2_000.0);
+        let baseline = results::load_baseline(dir.path(), "main").unwrap();
+        let results = results::load_results(dir.path()).unwrap();
+
+        let changes = compare_throughputs(&baseline, &results);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].full_id, "group/a");
+        assert_eq!(changes[0].relative_change(), 1.0);
+    }
+
+    #[cfg(feature = "github_action_benchmark")]
+    #[test]
+    fn write_github_action_benchmark_json_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        save_result(
+            dir.path(),
+            "new",
+            "group/a",
+            Some(Throughput::Elements(1_000)),
+            1_000.0,
+        );
+        let results = results::load_results(dir.path()).unwrap();
+        let mut output = vec![];
+
+        write_github_action_benchmark_json(&results, &mut output).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        assert_eq!(json[0]["name"], "group/a");
+        assert_eq!(json[0]["unit"], "elem/s");
+        assert_eq!(json[0]["value"], 1e9);
+    }
+
+    #[cfg(feature = "cargo_criterion")]
+    #[test]
+    fn add_decimal_throughput_to_benchmark_complete_messages() {
+        let input = concat!(
+            r#"{"reason":"benchmark-complete","id":"group/a","throughput":[{"per_iteration":2000,"unit":"bytes"}],"#,
+            r#""typical":{"estimate":1000.0,"lower_bound":800.0,"upper_bound":1250.0,"unit":"ns"}}"#,
+            "\n",
+            r#"{"reason":"group-complete","group_name":"group","benchmarks":["group/a"]}"#,
+            "\n",
+        );
+        let mut output = vec![];
+
+        add_decimal_throughput_to_messages(input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        let message: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+
+        assert_eq!(
+            message["decimal_throughput"],
+            serde_json::json!([{
+                "estimate": 2.0,
+                "lower_bound": 1.6,
+                "upper_bound": 2.5,
+                "unit": "GB/s",
+            }])
+        );
+        assert_eq!(
+            lines[1],
+            r#"{"reason":"group-complete","group_name":"group","benchmarks":["group/a"]}"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "openmetrics")]
+    fn write_openmetrics_gauges() {
+        let dir = tempfile::tempdir().unwrap();
+        save_result(
+            dir.path(),
+            "new",
+            "group/a\"b",
+            Some(Throughput::Bytes(2_000)),
+            1_000.0,
+        );
+        save_result(dir.path(), "new", "group/c", None, 1_000.0);
+        let results = results::load_results(dir.path()).unwrap();
+        let mut output = vec![];
+
+        write_openmetrics(&results, &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# TYPE criterion_throughput_bytes_per_second gauge\n\
+             # UNIT criterion_throughput_bytes_per_second bytes_per_second\n\
+             # HELP criterion_throughput_bytes_per_second Typical throughput in bytes per second.\n\
+             criterion_throughput_bytes_per_second{group=\"group\",id=\"group/a\\\"b\"} 2000000000\n\
+             # EOF\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "openmetrics")]
+    fn push_to_gateway_puts_metrics() {
+        use std::{io::Read, net::TcpListener, thread};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![0; 4096];
+            let read = stream.read(&mut request).unwrap();
+            stream.write_all(b"HTTP/1.1 200 OK\r\n\r\n").unwrap();
+            String::from_utf8_lossy(&request[..read]).into_owned()
+        });
+
+        push_to_gateway(&[], &address, "my job").unwrap();
+        let request = server.join().unwrap();
+
+        assert!(request.starts_with("PUT /metrics/job/my%20job HTTP/1.1\r\n"));
+        assert!(request.contains(
+            "\r\nContent-Type: application/openmetrics-text; version=1.0.0; charset=utf-8\r\n"
+        ));
+        assert!(request.ends_with("\r\n\r\n# EOF\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "openmetrics")]
+    fn push_to_gateway_times_out_on_silent_gateway() {
+        use std::{net::TcpListener, sync::mpsc, thread};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let (done, wait) = mpsc::channel::<()>();
+        let server = thread::spawn(move || {
+            // Accept the connection, but never respond.
+            let _stream = listener.accept().unwrap();
+            wait.recv().ok();
+        });
+
+        let err = push_to_gateway_with_timeout(&[], &address, "job", Duration::from_millis(100))
+            .unwrap_err();
+        done.send(()).unwrap();
+        server.join().unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[test]
+    #[cfg(feature = "tracing")]
+    fn trace_results_emits_events() {
+        use std::sync::{Arc, Mutex};
+        use tracing::{
+            field::{Field, Visit},
+            span, Event, Metadata, Subscriber,
+        };
+
+        #[derive(Default)]
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+
+        impl Visit for Recorder {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("{}={:?}", field.name(), value));
+            }
+        }
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+                span::Id::from_u64(1)
+            }
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+            fn event(&self, event: &Event<'_>) {
+                event.record(&mut Recorder(self.0.clone()));
+            }
+            fn enter(&self, _: &span::Id) {}
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        save_result(
+            dir.path(),
+            "new",
+            "group/a",
+            Some(Throughput::Bytes(2_000)),
+            1_000.0,
+        );
+        let results = results::load_results(dir.path()).unwrap();
+        let fields = Arc::new(Mutex::new(vec![]));
+
+        tracing::subscriber::with_default(Recorder(fields.clone()), || trace_results(&results));
+
+        let fields = fields.lock().unwrap();
+        assert!(fields.contains(&"id=group/a".to_owned()));
+        assert!(fields.contains(&"value=2.0".to_owned()));
+        assert!(fields.contains(&"unit=\"GB/s\"".to_owned()));
+    }
+
+    #[test]
+    fn markdown_table_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let bytes = Some(Throughput::Bytes(2_000));
+        save_result(dir.path(), "new", "group/a", bytes.clone(), 1_000.0);
+        save_change(dir.path(), "group/a", -0.2);
+        save_result(dir.path(), "new", "group/b", bytes, 2_000.0);
+        let results = results::load_results(dir.path()).unwrap();
+
+        let table = markdown_table(&results);
+
+        assert_eq!(
+            table,
+            "| Benchmark | Throughput | Change |\n\
+             |---|--:|--:|\n\
+             | group/a | 2.0000 GB/s | +25.00% |\n\
+             | group/b | 1.0000 GB/s | - |\n"
+        );
+    }
+
+    #[test]
+    fn throughput_table_aligns_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let bytes = Some(Throughput::Bytes(2_000));
+        save_result(dir.path(), "new", "group/a", bytes.clone(), 1_000.0);
+        save_change(dir.path(), "group/a", -0.2);
+        save_result(dir.path(), "new", "group/bc", bytes, 2e6);
+        let results = results::load_results(dir.path()).unwrap();
+
+        let table = throughput_table(&results);
+
+        assert_eq!(
+            table,
+            "Benchmark   Throughput   Change\n\
+             group/a    2.0000 GB/s  +25.00%\n\
+             group/bc   1.0000 MB/s        -\n"
+        );
+    }
+
+    #[test]
+    fn write_csv_scales_all_values_to_one_unit() {
+        let dir = tempfile::tempdir().unwrap();
+        save_result(
+            dir.path(),
+            "new",
+            "group/a,b",
+            Some(Throughput::Bytes(2_000)),
+            1_000.0,
+        );
+        save_result(dir.path(), "new", "group/c", None, 1_000.0);
+        let results = results::load_results(dir.path()).unwrap();
+        let mut output = vec![];
+
+        write_csv(&results, &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "group,id,mean,median,lower_bound,upper_bound,unit\n\
+             group,\"group/a,b\",2,2,1.8181818181818181,2.2222222222222223,GB/s\n"
+        );
+    }
+
+    #[test]
+    fn write_sample_csv_derives_throughput() {
+        let dir = tempfile::tempdir().unwrap();
+        save_result(
+            dir.path(),
+            "new",
+            "group/a",
+            Some(Throughput::Bytes(1_000)),
+            1_000.0,
+        );
+        save_sample(
+            dir.path(),
+            "new",
+            "group/a",
+            &[1.0, 4.0],
+            &[1_000.0, 2_000.0],
+        );
+        let results = results::load_results(dir.path()).unwrap();
+        let mut output = vec![];
+
+        write_sample_csv(&results[0], &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "iterations,elapsed_ns,throughput,unit\n1,1000,1000000000,B/s\n4,2000,2000000000,B/s\n"
+        );
+    }
+
+    #[test]
+    fn export_samples_writes_ndjson_next_to_results() {
+        let dir = tempfile::tempdir().unwrap();
+        save_result(
+            dir.path(),
+            "new",
+            "group/a",
+            Some(Throughput::Elements(10)),
+            1_000.0,
+        );
+        save_sample(dir.path(), "new", "group/a", &[2.0], &[1_000.0]);
+        save_result(dir.path(), "new", "group/b", None, 1_000.0);
+        let results = results::load_results(dir.path()).unwrap();
+
+        let paths = export_samples(&results, SampleFormat::Ndjson).unwrap();
+
+        assert_eq!(
+            paths,
+            [dir.path().join("group/a/new/throughput_sample.ndjson")]
+        );
+        let line: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&paths[0]).unwrap()).unwrap();
+        assert_eq!(
+            line,
+            serde_json::json!({
+                "iterations": 2.0,
+                "elapsed_ns": 1_000.0,
+                "throughput": 20_000_000.0,
+                "unit": "elem/s",
+            })
+        );
+    }
+}
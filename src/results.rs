@@ -0,0 +1,552 @@
+//! Reading benchmark results saved by Criterion.rs.
+//!
+//! Criterion.rs saves the estimates of every benchmark in its output directory,
+//! by default `target/criterion`. This module loads them back, which is the basis
+//! for checks and reports that run after the benchmarks finish, such as [`ThroughputGate`](crate::ThroughputGate).
+
+use crate::{
+    exact,
+    units::{Multiple, Period},
+};
+use criterion::Throughput;
+use serde::Deserialize;
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+/// Name of the directory where Criterion.rs saves results of the latest run.
+const NEW_DIRECTORY: &str = "new";
+const BENCHMARK_FILE: &str = "benchmark.json";
+const ESTIMATES_FILE: &str = "estimates.json";
+const SAMPLE_FILE: &str = "sample.json";
+/// Name of the directory where Criterion.rs saves the comparison of the latest run to the baseline.
+const CHANGE_DIRECTORY: &str = "change";
+/// File modification times are coarser than the system clock,
+/// so a result saved right after a report was created can appear to be older.
+const FRESHNESS_TOLERANCE: Duration = Duration::from_secs(1);
+
+/// Results of a single benchmark, as saved by Criterion.rs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkResult {
+    /// Name of the benchmark group.
+    pub group_id: String,
+    /// Name of the benchmark function, if any.
+    pub function_id: Option<String>,
+    /// Parameter of the benchmark, if any.
+    pub value_str: Option<String>,
+    /// Full identifier of the benchmark, as printed by Criterion.rs, e.g. `group/function/parameter`.
+    pub full_id: String,
+    /// Throughput configured for the benchmark, if any.
+    pub throughput: Option<Throughput>,
+    /// Statistical estimates of the time of a single iteration, in nanoseconds.
+    pub estimates: Estimates,
+    /// Time at which the estimates were saved.
+    pub saved_at: SystemTime,
+    /// Relative change compared to the baseline, as computed by Criterion.rs during the run.
+    ///
+    /// Only available for results of the latest run that were compared against a baseline.
+    pub change: Option<ChangeEstimates>,
+    /// Directory the result was loaded from, e.g. `target/criterion/group/function/new`.
+    pub directory: PathBuf,
+}
+
+/// Raw measurements of a benchmark, as saved by Criterion.rs.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Sample {
+    /// Number of iterations in each measurement.
+    pub iters: Vec<f64>,
+    /// Total time of the iterations of each measurement, in nanoseconds.
+    pub times: Vec<f64>,
+}
+
+/// Relative change of a benchmark's time compared to the baseline,
+/// where e.g. `0.1` means that an iteration took 10% longer.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChangeEstimates {
+    /// Estimate of the change of the mean.
+    pub mean: Estimate,
+    /// Estimate of the change of the median.
+    pub median: Estimate,
+}
+
+/// Statistical estimates of a benchmark, in nanoseconds per iteration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Estimates {
+    /// Estimate of the mean.
+    pub mean: Estimate,
+    /// Estimate of the median.
+    pub median: Estimate,
+    /// Estimate of the median absolute deviation.
+    pub median_abs_dev: Estimate,
+    /// Estimate of the slope of the linear regression, if it was computed.
+    pub slope: Option<Estimate>,
+    /// Estimate of the standard deviation.
+    pub std_dev: Estimate,
+}
+
+/// A single point estimate with its confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct Estimate {
+    /// The confidence interval of the estimate.
+    pub confidence_interval: ConfidenceInterval,
+    /// The point estimate.
+    pub point_estimate: f64,
+    /// The standard error of the estimate.
+    pub standard_error: f64,
+}
+
+/// Confidence interval of an [`Estimate`].
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ConfidenceInterval {
+    /// Confidence level, e.g. 0.95.
+    pub confidence_level: f64,
+    /// Lower bound of the interval.
+    pub lower_bound: f64,
+    /// Upper bound of the interval.
+    pub upper_bound: f64,
+}
+
+#[derive(Deserialize)]
+struct SavedBenchmarkId {
+    group_id: String,
+    function_id: Option<String>,
+    value_str: Option<String>,
+    throughput: Option<Throughput>,
+    full_id: String,
+}
+
+impl Estimates {
+    /// The estimate Criterion.rs reports as the result of the benchmark,
+    /// so the slope if it was computed, and mean otherwise.
+    pub fn typical(&self) -> &Estimate {
+        self.slope.as_ref().unwrap_or(&self.mean)
+    }
+}
+
+impl BenchmarkResult {
+    /// Convert the time of a single iteration in nanoseconds into throughput
+    /// in base units per second (bytes or elements, depending on the [`Throughput`]).
+    ///
+    /// Returns [`None`] if the benchmark had no throughput configured.
+    pub fn units_per_second(&self, nanos: f64) -> Option<f64> {
+        let count = exact::count(self.throughput.as_ref()?);
+        Some(exact::scaled_rate(
+            count,
+            nanos,
+            Period::Second,
+            Multiple::One,
+        ))
+    }
+
+    /// Typical throughput of the benchmark in base units per second.
+    ///
+    /// Returns [`None`] if the benchmark had no throughput configured.
+    pub fn typical_units_per_second(&self) -> Option<f64> {
+        self.units_per_second(self.estimates.typical().point_estimate)
+    }
+
+    /// Load the raw measurements of the benchmark.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sample file cannot be read or is malformed.
+    pub fn load_sample(&self) -> io::Result<Sample> {
+        Ok(serde_json::from_slice(&fs::read(
+            self.directory.join(SAMPLE_FILE),
+        )?)?)
+    }
+
+    /// Whether the result was saved during the run of a report created at `created_at`,
+    /// as opposed to being left over from a previous run.
+    pub(crate) fn is_fresh(&self, created_at: SystemTime) -> bool {
+        self.saved_at >= created_at - FRESHNESS_TOLERANCE
+    }
+}
+
+impl Sample {
+    /// Average time of a single iteration in each measurement, in nanoseconds.
+    pub fn nanos_per_iteration(&self) -> impl Iterator<Item = f64> + '_ {
+        self.iters.iter().zip(&self.times).map(|(i, t)| t / i)
+    }
+
+    /// Time of a single iteration at the given percentile of the measurements, in nanoseconds,
+    /// e.g. the median for `50.0`. Interpolated linearly between the closest measurements,
+    /// the same way Criterion.rs computes percentiles.
+    ///
+    /// Returns [`None`] if there are no measurements.
+    ///
+    /// # Panics
+    ///
+    /// If `percentile` is not between 0 and 100.
+    pub fn percentile(&self, percentile: f64) -> Option<f64> {
+        assert!(
+            (0.0..=100.0).contains(&percentile),
+            "percentile must be between 0 and 100"
+        );
+        let mut nanos: Vec<_> = self.nanos_per_iteration().collect();
+        nanos.sort_by(f64::total_cmp);
+
+        let rank = percentile / 100.0 * (nanos.len().checked_sub(1)? as f64);
+        let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+        Some(nanos[lower] + (nanos[upper] - nanos[lower]) * (rank - lower as f64))
+    }
+}
+
+/// The directory where Criterion.rs saves its results by default.
+///
+/// Resolved the same way Criterion.rs does it, so in order of preference:
+/// `$CRITERION_HOME`, `$CARGO_TARGET_DIR/criterion`, the target directory reported by `cargo metadata`,
+/// and finally `target/criterion`.
+pub fn default_output_directory() -> PathBuf {
+    if let Some(value) = env::var_os("CRITERION_HOME") {
+        PathBuf::from(value)
+    } else if let Some(path) = cargo_target_directory() {
+        path.join("criterion")
+    } else {
+        PathBuf::from("target/criterion")
+    }
+}
+
+fn cargo_target_directory() -> Option<PathBuf> {
+    #[derive(Deserialize)]
+    struct Metadata {
+        target_directory: PathBuf,
+    }
+
+    env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .or_else(|| {
+            let output = Command::new(env::var_os("CARGO")?)
+                .args(["metadata", "--format-version", "1", "--no-deps"])
+                .output()
+                .ok()?;
+            let metadata: Metadata = serde_json::from_slice(&output.stdout).ok()?;
+            Some(metadata.target_directory)
+        })
+}
+
+/// Load results of the latest run of all benchmarks saved in the given output directory.
+///
+/// The results are sorted by [`BenchmarkResult::full_id`].
+/// A nonexistent directory is treated as containing no results.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be read or contains malformed result files.
+pub fn load_results<P: AsRef<Path>>(output_directory: P) -> io::Result<Vec<BenchmarkResult>> {
+    load_baseline(output_directory, NEW_DIRECTORY)
+}
+
+/// Load results saved under the given baseline name in the output directory.
+///
+/// Criterion.rs saves the previous run under the `base` baseline by default,
+/// and under a custom name with `--save-baseline`.
+/// The results are sorted by [`BenchmarkResult::full_id`].
+/// A nonexistent directory is treated as containing no results.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be read or contains malformed result files.
+pub fn load_baseline<P: AsRef<Path>>(
+    output_directory: P,
+    baseline: &str,
+) -> io::Result<Vec<BenchmarkResult>> {
+    let mut results = vec![];
+    let output_directory = output_directory.as_ref();
+
+    if output_directory.is_dir() {
+        collect_results(output_directory, baseline, &mut results)?;
+    }
+
+    results.sort_by(|a, b| a.full_id.cmp(&b.full_id));
+    Ok(results)
+}
+
+fn collect_results(
+    directory: &Path,
+    baseline: &str,
+    results: &mut Vec<BenchmarkResult>,
+) -> io::Result<()> {
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let path = entry.path();
+
+        if entry.file_name() == baseline {
+            if let Some(result) = load_result(&path, baseline == NEW_DIRECTORY)? {
+                results.push(result);
+            }
+        } else {
+            collect_results(&path, baseline, results)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn load_result(directory: &Path, load_change: bool) -> io::Result<Option<BenchmarkResult>> {
+    let benchmark_path = directory.join(BENCHMARK_FILE);
+    let estimates_path = directory.join(ESTIMATES_FILE);
+
+    if !benchmark_path.is_file() || !estimates_path.is_file() {
+        return Ok(None);
+    }
+
+    let id: SavedBenchmarkId = serde_json::from_slice(&fs::read(benchmark_path)?)?;
+    let estimates: Estimates = serde_json::from_slice(&fs::read(&estimates_path)?)?;
+    let saved_at = fs::metadata(estimates_path)?.modified()?;
+    let change = if load_change {
+        load_change_estimates(directory, saved_at)?
+    } else {
+        None
+    };
+
+    Ok(Some(BenchmarkResult {
+        group_id: id.group_id,
+        function_id: id.function_id,
+        value_str: id.value_str,
+        full_id: id.full_id,
+        throughput: id.throughput,
+        estimates,
+        saved_at,
+        change,
+        directory: directory.to_owned(),
+    }))
+}
+
+fn load_change_estimates(
+    new_directory: &Path,
+    saved_at: SystemTime,
+) -> io::Result<Option<ChangeEstimates>> {
+    let change_path = match new_directory.parent() {
+        Some(parent) => parent.join(CHANGE_DIRECTORY).join(ESTIMATES_FILE),
+        None => return Ok(None),
+    };
+    if !change_path.is_file() {
+        return Ok(None);
+    }
+
+    // Criterion.rs saves the change after the new estimates. If the benchmark was not compared
+    // in the latest run, the file is a leftover from an earlier one.
+    if fs::metadata(&change_path)?.modified()? < saved_at {
+        return Ok(None);
+    }
+
+    Ok(Some(serde_json::from_slice(&fs::read(change_path)?)?))
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+
+    /// Save a result in the same layout Criterion.rs uses.
+    pub(crate) fn save_result(
+        output_directory: &Path,
+        baseline: &str,
+        full_id: &str,
+        throughput: Option<Throughput>,
+        point_estimate: f64,
+    ) {
+        let mut parts = full_id.split('/');
+        let group_id = parts.next().unwrap();
+        let function_id = parts.next();
+        let value_str = parts.next();
+        let directory = output_directory.join(full_id).join(baseline);
+        fs::create_dir_all(&directory).unwrap();
+
+        let estimate = serde_json::json!({
+            "confidence_interval": {
+                "confidence_level": 0.95,
+                "lower_bound": point_estimate * 0.9,
+                "upper_bound": point_estimate * 1.1,
+            },
+            "point_estimate": point_estimate,
+            "standard_error": 1.0,
+        });
+        let benchmark = serde_json::json!({
+            "group_id": group_id,
+            "function_id": function_id,
+            "value_str": value_str,
+            "throughput": throughput,
+            "full_id": full_id,
+            "directory_name": full_id,
+            "title": full_id,
+        });
+        let estimates = serde_json::json!({
+            "mean": estimate,
+            "median": estimate,
+            "median_abs_dev": estimate,
+            "slope": null,
+            "std_dev": estimate,
+        });
+
+        fs::write(directory.join(BENCHMARK_FILE), benchmark.to_string()).unwrap();
+        fs::write(directory.join(ESTIMATES_FILE), estimates.to_string()).unwrap();
+    }
+
+    /// Save raw measurements in the same layout Criterion.rs uses.
+    pub(crate) fn save_sample(
+        output_directory: &Path,
+        baseline: &str,
+        full_id: &str,
+        iters: &[f64],
+        times: &[f64],
+    ) {
+        let directory = output_directory.join(full_id).join(baseline);
+        fs::create_dir_all(&directory).unwrap();
+
+        let sample = serde_json::json!({
+            "sampling_mode": "Linear",
+            "iters": iters,
+            "times": times,
+        });
+
+        fs::write(directory.join(SAMPLE_FILE), sample.to_string()).unwrap();
+    }
+
+    /// Save a relative change of the mean in the same layout Criterion.rs uses.
+    pub(crate) fn save_change(output_directory: &Path, full_id: &str, mean_change: f64) {
+        let directory = output_directory.join(full_id).join(CHANGE_DIRECTORY);
+        fs::create_dir_all(&directory).unwrap();
+
+        let estimate = serde_json::json!({
+            "confidence_interval": {
+                "confidence_level": 0.95,
+                "lower_bound": mean_change,
+                "upper_bound": mean_change,
+            },
+            "point_estimate": mean_change,
+            "standard_error": 0.0,
+        });
+        let change = serde_json::json!({
+            "mean": estimate,
+            "median": estimate,
+        });
+
+        fs::write(directory.join(ESTIMATES_FILE), change.to_string()).unwrap();
+    }
+
+    #[test]
+    fn load_results_reads_saved_results() {
+        let dir = tempfile::tempdir().unwrap();
+        save_result(dir.path(), "new", "group/b", None, 10.0);
+        save_result(
+            dir.path(),
+            "new",
+            "group/a",
+            Some(Throughput::Bytes(1_000)),
+            1_000.0,
+        );
+        save_result(
+            dir.path(),
+            "base",
+            "group/a",
+            Some(Throughput::Bytes(1_000)),
+            2_000.0,
+        );
+
+        let results = load_results(dir.path()).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].full_id, "group/a");
+        assert_eq!(results[0].function_id.as_deref(), Some("a"));
+        assert_eq!(results[0].typical_units_per_second(), Some(1e9));
+        assert_eq!(results[1].full_id, "group/b");
+        assert_eq!(results[1].typical_units_per_second(), None);
+    }
+
+    #[test]
+    fn units_per_second_matches_console_output() {
+        let dir = tempfile::tempdir().unwrap();
+        save_result(
+            dir.path(),
+            "new",
+            "group/a",
+            Some(Throughput::Bytes(1_000)),
+            13.0,
+        );
+        let results = load_results(dir.path()).unwrap();
+
+        // Computing `1_000.0 * (1e9 / 13.0)` rounds to 76923076923.07693.
+        assert_eq!(
+            results[0].units_per_second(13.0),
+            Some(76_923_076_923.076_92)
+        );
+    }
+
+    #[test]
+    fn load_results_reads_change() {
+        let dir = tempfile::tempdir().unwrap();
+        save_result(dir.path(), "new", "group/a", None, 1_000.0);
+        save_change(dir.path(), "group/a", -0.5);
+
+        let results = load_results(dir.path()).unwrap();
+
+        assert_eq!(
+            results[0].change.as_ref().unwrap().mean.point_estimate,
+            -0.5
+        );
+    }
+
+    #[test]
+    fn load_baseline_reads_only_baseline() {
+        let dir = tempfile::tempdir().unwrap();
+        save_result(dir.path(), "new", "group/a", None, 1_000.0);
+        save_result(dir.path(), "base", "group/a", None, 2_000.0);
+
+        let results = load_baseline(dir.path(), "base").unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].estimates.typical().point_estimate, 2_000.0);
+    }
+
+    #[test]
+    fn load_sample_reads_saved_sample() {
+        let dir = tempfile::tempdir().unwrap();
+        save_result(dir.path(), "new", "group/a", None, 1_000.0);
+        save_sample(
+            dir.path(),
+            "new",
+            "group/a",
+            &[1.0, 2.0],
+            &[1_000.0, 3_000.0],
+        );
+        let results = load_results(dir.path()).unwrap();
+
+        let sample = results[0].load_sample().unwrap();
+
+        assert_eq!(
+            sample.nanos_per_iteration().collect::<Vec<_>>(),
+            [1_000.0, 1_500.0]
+        );
+    }
+
+    #[test]
+    fn percentile_interpolates_between_measurements() {
+        let sample = Sample {
+            iters: vec![1.0, 1.0, 2.0, 1.0],
+            times: vec![4_000.0, 1_000.0, 4_000.0, 3_000.0],
+        };
+        let empty = Sample {
+            iters: vec![],
+            times: vec![],
+        };
+
+        assert_eq!(sample.percentile(0.0), Some(1_000.0));
+        assert_eq!(sample.percentile(50.0), Some(2_500.0));
+        assert_eq!(sample.percentile(100.0), Some(4_000.0));
+        assert_eq!(empty.percentile(50.0), None);
+    }
+
+    #[test]
+    fn load_results_nonexistent_directory_is_empty() {
+        let results = load_results("this/directory/does/not/exist").unwrap();
+
+        assert!(results.is_empty());
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{
+    format::{self, Align},
+    results::{self, BenchmarkResult},
+    units::{self, Unit},
+    DecimalByteMeasurement,
+};
+use criterion::{
+    measurement::{Measurement, ValueFormatter},
+    Throughput,
+};
+use std::{
+    cell::Cell,
+    io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    thread,
+    time::SystemTime,
+};
+
+/// When the first [`DecimalByteMeasurement`] of the process was created.
+static RUN_STARTED_AT: OnceLock<SystemTime> = OnceLock::new();
+
+/// Called when a measurement is created, marking the start of the run.
+pub(crate) fn register_start() {
+    RUN_STARTED_AT.get_or_init(SystemTime::now);
+}
+
+/// Single table of the throughput of all benchmarks of the run, across all groups,
+/// with the total throughput of the run.
+///
+/// The report includes every result saved since the first [`DecimalByteMeasurement`] of the process
+/// was created, so it can be created at any point of the run, e.g. in the last benchmark target.
+/// Like [`CompactReport`](crate::CompactReport), it reads the results Criterion.rs saved in its
+/// output directory.
+///
+/// The table is printed when [`RunReport::finalize`] is called, or when the report is dropped
+/// without calling it, e.g. at the end of a custom `main`. The total of each kind of [`Throughput`]
+/// is the throughput of running every benchmark with it once, so the sum of their units over
+/// the sum of their typical times, e.g.:
+///
+/// ```text
+/// Group  Benchmark      Throughput
+/// parse  rows          2.4272 GB/s
+/// parse  setup                   -
+/// write  rows          1.0000 GB/s
+/// total  2 benchmarks  1.4164 GB/s
+/// ```
+///
+/// # Example
+///
+/// ```
+/// use criterion::Throughput;
+/// use criterion_decimal_throughput::{decimal_byte_measurement, RunReport};
+///
+/// let mut c = decimal_byte_measurement();
+/// let report = RunReport::new();
+///
+/// for group in ["parse", "write"] {
+///     let mut group = c.benchmark_group(group);
+///     group.throughput(Throughput::Bytes(1_000_000));
+///     // Add your benchmarks to the group here...
+///     group.finish();
+/// }
+///
+/// report.finalize().unwrap();
+/// ```
+pub struct RunReport {
+    output_directory: PathBuf,
+    formatter: Box<dyn ValueFormatter>,
+    started_at: SystemTime,
+    finalized: Cell<bool>,
+}
+
+impl Default for RunReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunReport {
+    /// Create a new report formatting with the default options of [`DecimalByteMeasurement`] and reading results
+    /// from the [default output directory](results::default_output_directory).
+    pub fn new() -> Self {
+        RunReport {
+            output_directory: results::default_output_directory(),
+            formatter: Box::new(format::Plain),
+            started_at: *RUN_STARTED_AT.get_or_init(SystemTime::now),
+            finalized: Cell::new(false),
+        }
+    }
+
+    /// Read the results from the given directory instead of the default one.
+    ///
+    /// Use this if you changed it with [`criterion::Criterion::output_directory`].
+    pub fn output_directory<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.output_directory = path.as_ref().to_owned();
+        self
+    }
+
+    /// Format throughput with the given measurement, e.g. the one the groups were run with.
+    pub fn with_measurement<M: Measurement + 'static>(
+        mut self,
+        measurement: DecimalByteMeasurement<M>,
+    ) -> Self {
+        self.formatter = Box::new(format::Detached(measurement));
+        self
+    }
+
+    /// Aligned table of the throughput of all benchmarks of the run, sorted by their full id,
+    /// followed by the totals.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the results could not be read.
+    pub fn table(&self) -> io::Result<String> {
+        let results: Vec<_> = results::load_results(&self.output_directory)?
+            .into_iter()
+            .filter(|r| r.is_fresh(self.started_at))
+            .collect();
+
+        let mut rows = vec![[
+            "Group".to_owned(),
+            "Benchmark".to_owned(),
+            "Throughput".to_owned(),
+        ]];
+        rows.extend(results.iter().map(|r| {
+            [
+                r.group_id.clone(),
+                crate::compact::name(r).to_owned(),
+                self.throughput(r),
+            ]
+        }));
+        for unit in [Unit::Byte, Unit::Elem] {
+            if let Some(total) = self.total(&results, unit) {
+                rows.push(total);
+            }
+        }
+
+        format::align_columns(&mut rows, [Align::Left, Align::Left, Align::Right]);
+
+        Ok(format::join_rows(&rows))
+    }
+
+    /// Print the [table](RunReport::table) to the standard output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the results could not be read.
+    pub fn finalize(&self) -> io::Result<()> {
+        self.finalized.set(true);
+        print!("{}", self.table()?);
+        Ok(())
+    }
+
+    /// Formatted typical throughput of the benchmark, or `-` if it has none.
+    fn throughput(&self, result: &BenchmarkResult) -> String {
+        match &result.throughput {
+            Some(throughput) => self
+                .formatter
+                .format_throughput(throughput, result.estimates.typical().point_estimate)
+                .trim_start()
+                .to_owned(),
+            None => "-".to_owned(),
+        }
+    }
+
+    /// Row of the total throughput of the results with throughput in the given unit, if there are any.
+    fn total(&self, results: &[BenchmarkResult], unit: Unit) -> Option<[String; 3]> {
+        let (count, total_units, total_nanos) = results
+            .iter()
+            .filter_map(|r| Some((r.throughput.as_ref()?, r.estimates.typical().point_estimate)))
+            .filter(|(throughput, _)| Unit::of(throughput) == unit)
+            .fold(
+                (0, 0.0, 0.0),
+                |(count, units, nanos), (throughput, time)| {
+                    (
+                        count + 1,
+                        units + units::total_units(throughput),
+                        nanos + time,
+                    )
+                },
+            );
+        if count == 0 {
+            return None;
+        }
+
+        // Criterion.rs throughput is integral, so the total is scaled to the time of one unit.
+        let throughput = match unit {
+            Unit::Byte => Throughput::Bytes(1),
+            Unit::Elem => Throughput::Elements(1),
+        };
+        let formatted = self
+            .formatter
+            .format_throughput(&throughput, total_nanos / total_units);
+        let benchmarks = if count == 1 {
+            "benchmark"
+        } else {
+            "benchmarks"
+        };
+
+        Some([
+            "total".to_owned(),
+            format!("{} {}", count, benchmarks),
+            formatted.trim_start().to_owned(),
+        ])
+    }
+}
+
+impl Drop for RunReport {
+    fn drop(&mut self) {
+        if self.finalized.get() || thread::panicking() {
+            return;
+        }
+        if let Err(err) = self.finalize() {
+            eprintln!("failed to print the run report: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::results::test::save_result;
+
+    #[test]
+    fn table_shows_all_groups_and_totals() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = RunReport::new().output_directory(dir.path());
+        save_result(
+            dir.path(),
+            "new",
+            "parse/rows",
+            Some(Throughput::Bytes(1_000_000)),
+            412_000.0,
+        );
+        save_result(dir.path(), "new", "parse/setup", None, 1_500.0);
+        save_result(
+            dir.path(),
+            "new",
+            "write/rows",
+            Some(Throughput::Bytes(1_000_000)),
+            1_000_000.0,
+        );
+
+        let table = report.table().unwrap();
+        report.finalized.set(true);
+
+        assert_eq!(
+            table,
+            "Group  Benchmark      Throughput\n\
+             parse  rows          2.4272 GB/s\n\
+             parse  setup                   -\n\
+             write  rows          1.0000 GB/s\n\
+             total  2 benchmarks  1.4164 GB/s\n"
+        );
+    }
+}
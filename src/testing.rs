@@ -0,0 +1,138 @@
+//! Helpers for testing code formatting throughput with this crate, e.g. custom reports.
+//!
+//! [`nanos_for`] constructs the time of an iteration for which a [`Throughput`] has a given rate,
+//! and the assertions check how a [`ValueFormatter`] formats or scales it outside of a benchmark,
+//! so that they do not depend on or affect the benchmark running them.
+//!
+//! Requires the `test_util` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use criterion::Throughput;
+//! use criterion_decimal_throughput::{testing, units::Multiple, DecimalByteMeasurement};
+//!
+//! let measurement = DecimalByteMeasurement::new();
+//! let throughput = Throughput::Bytes(1_000_000);
+//! let nanos = testing::nanos_for(&throughput, 2.4, Multiple::Giga);
+//!
+//! testing::assert_formats(&measurement, &throughput, nanos, "2.4000 GB/s");
+//! testing::assert_scales(&measurement, &throughput, nanos, 2.4, "GB/s");
+//! ```
+
+use crate::{
+    context,
+    units::{total_units, Multiple},
+};
+use criterion::{measurement::ValueFormatter, Throughput};
+
+/// Relative tolerance of [`assert_scales`], covering the rounding error of scaling.
+const TOLERANCE: f64 = 1e-9;
+
+/// Time of an iteration in nanoseconds for which `throughput` is `value` in the given multiple
+/// of units per second, e.g. `2.4` GB/s.
+///
+/// # Panics
+///
+/// If `value` is not positive.
+pub fn nanos_for(throughput: &Throughput, value: f64, multiple: Multiple) -> f64 {
+    assert!(value > 0.0, "throughput must be positive");
+    total_units(throughput) * 1e9 / (value * multiple.denominator())
+}
+
+/// Assert that the formatter formats `throughput` of an iteration taking `nanos` as `expected`,
+/// ignoring the padding at the start.
+///
+/// # Panics
+///
+/// If the formatted throughput is different.
+#[track_caller]
+pub fn assert_formats<F: ValueFormatter + ?Sized>(
+    formatter: &F,
+    throughput: &Throughput,
+    nanos: f64,
+    expected: &str,
+) {
+    let formatted = context::detached(|| formatter.format_throughput(throughput, nanos));
+
+    assert_eq!(
+        formatted.trim_start(),
+        expected,
+        "unexpected format of {:?} in {} ns",
+        throughput,
+        nanos
+    );
+}
+
+/// Assert that the formatter scales `throughput` of an iteration taking `nanos` to `expected_value`
+/// in `expected_unit`, ignoring the padding of the unit label.
+///
+/// The value is compared with a relative tolerance of `1e-9`, since scaling is not exact.
+///
+/// # Panics
+///
+/// If the scaled value or the unit is different.
+#[track_caller]
+pub fn assert_scales<F: ValueFormatter + ?Sized>(
+    formatter: &F,
+    throughput: &Throughput,
+    nanos: f64,
+    expected_value: f64,
+    expected_unit: &str,
+) {
+    let mut values = [nanos];
+    let unit = context::detached(|| formatter.scale_throughputs(nanos, throughput, &mut values));
+
+    assert_eq!(
+        unit.trim_start(),
+        expected_unit,
+        "unexpected unit of {:?} in {} ns",
+        throughput,
+        nanos
+    );
+    assert!(
+        (values[0] - expected_value).abs() <= expected_value.abs() * TOLERANCE,
+        "unexpected scaled value of {:?} in {} ns: expected {}, got {}",
+        throughput,
+        nanos,
+        expected_value,
+        values[0]
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::DecimalByteMeasurement;
+
+    #[test]
+    fn nanos_for_gives_throughput_in_multiple() {
+        let measurement = DecimalByteMeasurement::new();
+
+        for multiple in [Multiple::One, Multiple::Kilo, Multiple::Tera] {
+            let throughput = Throughput::Elements(12_345);
+            let nanos = nanos_for(&throughput, 3.5, multiple);
+
+            assert_scales(
+                &measurement,
+                &throughput,
+                nanos,
+                3.5,
+                &format!("{}elem/s", multiple.prefix()),
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected format of Bytes(1000) in 1000 ns")]
+    fn assert_formats_mismatch_panics() {
+        let measurement = DecimalByteMeasurement::new();
+
+        assert_formats(
+            &measurement,
+            &Throughput::Bytes(1_000),
+            1_000.0,
+            "1.0000 MB/s",
+        );
+    }
+}
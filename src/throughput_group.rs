@@ -0,0 +1,636 @@
+use crate::{
+    context::{self, ConsistentUnits, Decoration, GroupScope},
+    units::ThroughputKind,
+    CombinedThroughput, DecimalByteMeasurement, DirectionalThroughput, FractionalThroughput,
+    GroupThroughputExt, IntoBenchmarkId, SampleVolume,
+};
+use criterion::{
+    measurement::{Measurement, WallTime},
+    Bencher, BenchmarkGroup, Criterion, Throughput,
+};
+use std::{io, path::Path};
+
+/// Wrapper over [`criterion::BenchmarkGroup`] for throughput that needs more than a [`Throughput`]
+/// to be displayed, e.g. both bytes and elements or the aggregate of multiple threads.
+///
+/// Criterion.rs gives the formatter nothing but the [`Throughput`] of a benchmark, so the group
+/// makes its additional information known to the formatter while it runs its benchmarks,
+/// and only then. It does not apply to other groups, nor to the following benchmarks of the group
+/// after its throughput is changed.
+/// Configure the wrapped group with [`ThroughputGroup::group_mut`], but set its throughput
+/// through the wrapper.
+///
+/// # Example
+///
+/// ```
+/// use criterion_decimal_throughput::{Criterion, CombinedThroughput, ThroughputGroup};
+///
+/// fn example_bench(c: &mut Criterion) {
+///     let input = "[1, 2, 3, 4]";
+///     let mut group = ThroughputGroup::new(c, "parse");
+///     group.throughput_combined(CombinedThroughput {
+///         bytes: input.len() as u64,
+///         elements: 4,
+///     });
+///     group.bench_function("json", |b| b.iter(|| input.trim().len()));
+///     group.finish();
+/// }
+/// ```
+pub struct ThroughputGroup<'a, M: Measurement = WallTime> {
+    group: BenchmarkGroup<'a, DecimalByteMeasurement<M>>,
+    name: String,
+    scope: GroupScope,
+}
+
+impl<'a, M: Measurement> ThroughputGroup<'a, M> {
+    /// Create a group with the given name, as with [`criterion::Criterion::benchmark_group`].
+    ///
+    /// The group makes the full ids of its benchmarks, e.g. `parse/json`, known to the measurement,
+    /// so that their [unit overrides](DecimalByteMeasurement::with_unit_override) apply.
+    pub fn new<S: Into<String>>(
+        criterion: &'a mut Criterion<DecimalByteMeasurement<M>>,
+        name: S,
+    ) -> Self {
+        let name = name.into();
+        ThroughputGroup {
+            group: criterion.benchmark_group(name.clone()),
+            name,
+            scope: GroupScope::default(),
+        }
+    }
+
+    /// The wrapped group, e.g. to change its sample size.
+    pub fn group_mut(&mut self) -> &mut BenchmarkGroup<'a, DecimalByteMeasurement<M>> {
+        &mut self.group
+    }
+
+    /// Set the throughput of the group, as with [`criterion::BenchmarkGroup::throughput`].
+    pub fn throughput(&mut self, throughput: Throughput) -> &mut Self {
+        self.decorate(throughput, None)
+    }
+
+    /// Set the throughput of the group to the count of a domain quantity, e.g. pixels,
+    /// displayed in the units of its [`ThroughputKind`], e.g. `Mpx/s`.
+    ///
+    /// The group's [`Throughput`] is set to [`Throughput::Elements`] of the count.
+    /// See [`ThroughputKind`] for an example.
+    pub fn throughput_of_kind<K: ThroughputKind + ?Sized>(&mut self, kind: &K) -> &mut Self {
+        let throughput = Throughput::Elements(kind.count());
+        let decoration = Decoration::Kind(throughput.clone(), kind.units());
+        self.decorate(throughput, Some(decoration))
+    }
+
+    /// Set the throughput of the group to a fractional number of bytes, e.g. the amortized
+    /// 1.5 bytes of header per packet.
+    ///
+    /// Criterion.rs only accepts integral counts, so the group's [`Throughput`] is set to
+    /// [`Throughput::Bytes`] of the count rounded to the nearest positive integer, and the formatter
+    /// scales the rate to the exact count. Reports reading the saved results of Criterion.rs,
+    /// e.g. [`CompactReport`](crate::CompactReport), see the rounded count.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion_decimal_throughput::{decimal_byte_measurement, FractionalThroughput, ThroughputGroup};
+    ///
+    /// let mut c = decimal_byte_measurement();
+    /// let mut group = ThroughputGroup::new(&mut c, "headers");
+    /// group.throughput_fractional_bytes(FractionalThroughput(1.5));
+    ///
+    /// // Add your benchmarks to the group here...
+    ///
+    /// group.finish();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the count is not positive and finite.
+    pub fn throughput_fractional_bytes(&mut self, bytes: FractionalThroughput) -> &mut Self {
+        let placeholder = Throughput::Bytes(bytes.rounded());
+        let decoration = Decoration::Fractional(placeholder.clone(), bytes.0);
+        self.decorate(placeholder, Some(decoration))
+    }
+
+    /// Set the throughput of the group to a fractional number of elements,
+    /// as with [`throughput_fractional_bytes`](ThroughputGroup::throughput_fractional_bytes).
+    ///
+    /// # Panics
+    ///
+    /// If the count is not positive and finite.
+    pub fn throughput_fractional_elements(&mut self, elements: FractionalThroughput) -> &mut Self {
+        let placeholder = Throughput::Elements(elements.rounded());
+        let decoration = Decoration::Fractional(placeholder.clone(), elements.0);
+        self.decorate(placeholder, Some(decoration))
+    }
+
+    /// Set the throughput of the group to both a byte and an element count.
+    ///
+    /// The group's [`Throughput`] is set to [`Throughput::Bytes`], and the report
+    /// shows the element throughput next to it, e.g. `1.2000 GB/s, 350.00 Melem/s`.
+    /// See [`ThroughputGroup`] for an example.
+    pub fn throughput_combined(&mut self, throughput: CombinedThroughput) -> &mut Self {
+        let decoration = Decoration::Combined(throughput);
+        self.decorate(Throughput::Bytes(throughput.bytes), Some(decoration))
+    }
+
+    /// Set the throughput of the group to separate numbers of bytes read and written,
+    /// e.g. the ingress and egress of a proxy.
+    ///
+    /// The group's [`Throughput`] is set to [`Throughput::Bytes`] of the read bytes, and the report
+    /// shows both rates, e.g. `in 1.2000 GB/s / out 900.00 MB/s`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion_decimal_throughput::{decimal_byte_measurement, DirectionalThroughput, ThroughputGroup};
+    ///
+    /// let mut c = decimal_byte_measurement();
+    /// let mut group = ThroughputGroup::new(&mut c, "proxy");
+    /// group.throughput_directional(DirectionalThroughput {
+    ///     read: 1_200,
+    ///     write: 900,
+    /// });
+    ///
+    /// // Add your benchmarks to the group here...
+    ///
+    /// group.finish();
+    /// ```
+    pub fn throughput_directional(&mut self, throughput: DirectionalThroughput) -> &mut Self {
+        let decoration = Decoration::Directional(throughput);
+        self.decorate(Throughput::Bytes(throughput.read), Some(decoration))
+    }
+
+    /// Set the throughput of the group to the aggregate of the given per-thread throughput
+    /// over `threads` threads, for benchmarks that run the workload on multiple threads
+    /// in each iteration.
+    ///
+    /// The report is labeled accordingly, e.g. `8.0000 GB/s (aggregate of 4 threads)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion::Throughput;
+    /// use criterion_decimal_throughput::{decimal_byte_measurement, ThroughputGroup};
+    ///
+    /// let mut c = decimal_byte_measurement();
+    /// let mut group = ThroughputGroup::new(&mut c, "parallel_hash");
+    /// // Every one of the 4 threads hashes 1 MB in each iteration.
+    /// group.throughput_parallel(Throughput::Bytes(1_000_000), 4);
+    ///
+    /// // Add your benchmarks to the group here...
+    ///
+    /// group.finish();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `threads` is zero or the aggregate overflows a [`u64`].
+    pub fn throughput_parallel(&mut self, per_thread: Throughput, threads: u64) -> &mut Self {
+        assert!(threads > 0, "number of threads must be positive");
+        let aggregate = |units: u64| {
+            units
+                .checked_mul(threads)
+                .expect("aggregate throughput must fit in a u64")
+        };
+        let total = match per_thread {
+            Throughput::Bytes(bytes) => Throughput::Bytes(aggregate(bytes)),
+            Throughput::Elements(elements) => Throughput::Elements(aggregate(elements)),
+        };
+
+        let decoration = Decoration::Aggregate(total.clone(), threads);
+        self.decorate(total, Some(decoration))
+    }
+
+    /// Summarize all benchmarks in the group using the same multiple for each unit,
+    /// e.g. all byte throughput in MB/s, even if some of them are closer to GB/s,
+    /// so that the steps of a sweep over input sizes are easy to compare.
+    ///
+    /// The multiple of a unit is the one selected for the group-wide typical value, which is
+    /// the median of the typical values of the benchmarks reported in it, so it does not depend
+    /// on their order. That value is only known once all benchmarks ran, so each benchmark is
+    /// reported as usual while it runs and the summary is printed when the group finishes,
+    /// with a line for each benchmark identified by a string or a [`DecimalBenchmarkId`](crate::DecimalBenchmarkId).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use criterion_decimal_throughput::{Criterion, GroupThroughputExt, ThroughputGroup};
+    ///
+    /// fn example_bench(c: &mut Criterion) {
+    ///     let mut group = ThroughputGroup::new(c, "sum");
+    ///     group.consistent_units();
+    ///     for size in [1_000, 1_000_000] {
+    ///         let input = vec![1_u8; size];
+    ///         group.bench_with_input_throughput(
+    ///             size.to_string(),
+    ///             &input,
+    ///             |input| input.len() as u64,
+    ///             |b, input| b.iter(|| input.iter().map(|&x| u64::from(x)).sum::<u64>()),
+    ///         );
+    ///     }
+    ///     group.finish();
+    /// }
+    /// ```
+    pub fn consistent_units(&mut self) -> &mut Self {
+        self.scope
+            .consistent_units
+            .get_or_insert_with(ConsistentUnits::default);
+        self
+    }
+
+    /// Benchmark the function, as with [`criterion::BenchmarkGroup::bench_function`].
+    pub fn bench_function<ID, F>(&mut self, id: ID, f: F) -> &mut Self
+    where
+        ID: IntoBenchmarkId,
+        F: FnMut(&mut Bencher<'_, DecimalByteMeasurement<M>>),
+    {
+        let full_id = self.full_id(&id);
+        let group = &mut self.group;
+        context::with_group_scope(&mut self.scope, || {
+            context::with_benchmark_id(full_id, || id.bench_function(group, f))
+        });
+        self
+    }
+
+    /// Benchmark the function with the input, as with [`criterion::BenchmarkGroup::bench_with_input`].
+    pub fn bench_with_input<ID, I, F>(&mut self, id: ID, input: &I, f: F) -> &mut Self
+    where
+        ID: IntoBenchmarkId,
+        I: ?Sized,
+        F: FnMut(&mut Bencher<'_, DecimalByteMeasurement<M>>, &I),
+    {
+        let full_id = self.full_id(&id);
+        let group = &mut self.group;
+        context::with_group_scope(&mut self.scope, || {
+            context::with_benchmark_id(full_id, || id.bench_with_input(group, input, f))
+        });
+        self
+    }
+
+    /// Consume the group and generate its summary report, as with [`criterion::BenchmarkGroup::finish`].
+    ///
+    /// With [consistent units](ThroughputGroup::consistent_units), also print their summary.
+    pub fn finish(mut self) {
+        let group = self.group;
+        context::with_group_scope(&mut self.scope, || group.finish());
+        if let Some(units) = self
+            .scope
+            .consistent_units
+            .filter(|u| !u.summary.is_empty())
+        {
+            println!("{} in consistent units:", self.name);
+            for line in units.summary {
+                println!("  {}", line);
+            }
+        }
+    }
+
+    /// Full id of the benchmark with the given id in the group, if its parts are known.
+    fn full_id<ID: IntoBenchmarkId>(&self, id: &ID) -> Option<String> {
+        id.key().map(|key| format!("{}/{}", self.name, key))
+    }
+
+    fn decorate(&mut self, throughput: Throughput, decoration: Option<Decoration>) -> &mut Self {
+        self.scope.decoration = decoration;
+        self.group.throughput(throughput);
+        self
+    }
+}
+
+/// The methods set the throughput of the group without any decoration.
+impl<'a, M: Measurement> GroupThroughputExt for ThroughputGroup<'a, M> {
+    type Measurement = DecimalByteMeasurement<M>;
+
+    fn throughput_of<T: AsRef<[u8]> + ?Sized>(&mut self, input: &T) -> &mut Self {
+        self.throughput(Throughput::Bytes(input.as_ref().len() as u64))
+    }
+
+    fn throughput_of_str(&mut self, input: &str) -> &mut Self {
+        self.throughput_of(input)
+    }
+
+    fn throughput_of_file<P: AsRef<Path>>(&mut self, path: P) -> io::Result<&mut Self> {
+        self.group.throughput_of_file(path)?;
+        self.scope.decoration = None;
+        Ok(self)
+    }
+
+    fn throughput_bytes<N: TryInto<u64>>(&mut self, bytes: N) -> &mut Self {
+        self.group.throughput_bytes(bytes);
+        self.scope.decoration = None;
+        self
+    }
+
+    fn throughput_elements<N: TryInto<u64>>(&mut self, elements: N) -> &mut Self {
+        self.group.throughput_elements(elements);
+        self.scope.decoration = None;
+        self
+    }
+
+    fn throughput_with_sample_volume(&mut self, bytes: u64, volume: SampleVolume) -> &mut Self {
+        self.group.throughput_with_sample_volume(bytes, volume);
+        self.scope.decoration = None;
+        self
+    }
+
+    fn bench_with_input_throughput<ID, I, T, F>(
+        &mut self,
+        id: ID,
+        input: &I,
+        bytes: T,
+        f: F,
+    ) -> &mut Self
+    where
+        ID: IntoBenchmarkId,
+        I: ?Sized,
+        T: FnOnce(&I) -> u64,
+        F: FnMut(&mut Bencher<'_, Self::Measurement>, &I),
+    {
+        self.throughput(Throughput::Bytes(bytes(input)));
+        self.bench_with_input(id, input, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        decimal_byte_measurement, test_criterion,
+        units::{Multiple, UnitOverride},
+        DecimalBenchmarkId,
+    };
+    use criterion::{measurement::ValueFormatter, BenchmarkId};
+    use std::cell::RefCell;
+
+    /// Benchmark that records how the throughput is formatted while it runs.
+    fn formatting_bench<'a>(
+        formatted: &'a RefCell<Option<String>>,
+        throughput: Throughput,
+        nanos: f64,
+    ) -> impl FnMut(&mut Bencher<'_, DecimalByteMeasurement>) + 'a {
+        move |b| {
+            let measurement = DecimalByteMeasurement::new();
+            *formatted.borrow_mut() = Some(measurement.format_throughput(&throughput, nanos));
+            b.iter(|| 2 + 2)
+        }
+    }
+
+    #[test]
+    fn throughput_combined_formats_both_throughputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut c = test_criterion(dir.path());
+        let formatted = RefCell::new(None);
+        let mut group = ThroughputGroup::new(&mut c, "test");
+        group.throughput_combined(CombinedThroughput {
+            bytes: 2_000_000,
+            elements: 3_000,
+        });
+
+        group.bench_function(
+            "combined",
+            formatting_bench(&formatted, Throughput::Bytes(2_000_000), 1_000_000.0),
+        );
+        group.finish();
+
+        assert_eq!(
+            formatted.into_inner().unwrap(),
+            "2.0000 GB/s, 3.0000 Melem/s"
+        );
+    }
+
+    #[test]
+    fn throughput_of_kind_uses_its_units() {
+        struct Flops(u64);
+
+        impl ThroughputKind for Flops {
+            fn units(&self) -> crate::units::DecimalUnitSystem {
+                crate::decimal_unit_system!("FLOP")
+            }
+
+            fn count(&self) -> u64 {
+                self.0
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut c = test_criterion(dir.path());
+        let (kind, other) = (RefCell::new(None), RefCell::new(None));
+        let mut group = ThroughputGroup::new(&mut c, "test");
+        group.throughput_of_kind(&Flops(2 * 64 * 64 * 64));
+
+        group.bench_function("matmul", |b| {
+            let measurement = DecimalByteMeasurement::new().with_bytes_per_element(8);
+            *kind.borrow_mut() =
+                Some(measurement.format_throughput(&Throughput::Elements(524_288), 1_000.0));
+            *other.borrow_mut() =
+                Some(measurement.format_throughput(&Throughput::Elements(1_000), 1_000.0));
+            b.iter(|| 2 + 2)
+        });
+        group.finish();
+
+        assert_eq!(kind.into_inner().unwrap(), "524.29 GFLOP/s");
+        assert_eq!(other.into_inner().unwrap(), "1.0000 Gelem/s, 8.0000 GB/s");
+    }
+
+    #[test]
+    fn throughput_fractional_scales_to_exact_count() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut c = test_criterion(dir.path());
+        let scaled = RefCell::new(None);
+        let mut group = ThroughputGroup::new(&mut c, "test");
+        group.throughput_fractional_bytes(FractionalThroughput(1.5));
+
+        group.bench_function("headers", |b| {
+            let measurement = DecimalByteMeasurement::new();
+            let result = measurement.format_throughput(&Throughput::Bytes(2), 1.0);
+            let mut values = [1.0, 2.0];
+            let unit = measurement.scale_throughputs(1.0, &Throughput::Bytes(2), &mut values);
+            *scaled.borrow_mut() = Some((result, unit, values));
+            b.iter(|| 2 + 2)
+        });
+        group.finish();
+
+        let (result, unit, values) = scaled.into_inner().unwrap();
+        assert_eq!(result, "1.5000 GB/s");
+        assert_eq!(unit, "GB/s");
+        assert_eq!(values, [1.5, 0.75]);
+    }
+
+    #[test]
+    #[should_panic(expected = "fractional throughput must be positive and finite")]
+    fn throughput_fractional_zero_panics() {
+        let mut c = decimal_byte_measurement();
+        let mut group = ThroughputGroup::new(&mut c, "test");
+
+        group.throughput_fractional_elements(FractionalThroughput(0.0));
+    }
+
+    #[test]
+    fn throughput_directional_formats_both_directions() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut c = test_criterion(dir.path());
+        let formatted = RefCell::new(None);
+        let mut group = ThroughputGroup::new(&mut c, "test");
+        group.throughput_directional(DirectionalThroughput {
+            read: 1_200_000,
+            write: 900_000,
+        });
+
+        group.bench_function(
+            "proxy",
+            formatting_bench(&formatted, Throughput::Bytes(1_200_000), 1_000_000.0),
+        );
+        group.finish();
+
+        assert_eq!(
+            formatted.into_inner().unwrap(),
+            "in 1.2000 GB/s / out 900.00 MB/s"
+        );
+    }
+
+    #[test]
+    fn throughput_parallel_labels_aggregate() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut c = test_criterion(dir.path());
+        let formatted = RefCell::new(None);
+        let mut group = ThroughputGroup::new(&mut c, "test");
+        group.throughput_parallel(Throughput::Bytes(2_000_000), 4);
+
+        group.bench_function(
+            "hash",
+            formatting_bench(&formatted, Throughput::Bytes(8_000_000), 1_000_000.0),
+        );
+        group.finish();
+
+        assert_eq!(
+            formatted.into_inner().unwrap(),
+            "8.0000 GB/s (aggregate of 4 threads)"
+        );
+    }
+
+    #[test]
+    fn decorations_do_not_leak_into_other_groups_and_benchmarks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut c = test_criterion(dir.path());
+        let formatted = [(); 3].map(|_| RefCell::new(None));
+        let throughput = Throughput::Bytes(1_000);
+
+        let mut decorated = ThroughputGroup::new(&mut c, "decorated");
+        decorated.throughput_combined(CombinedThroughput {
+            bytes: 1_000,
+            elements: 5,
+        });
+        decorated.bench_function(
+            "combined",
+            formatting_bench(&formatted[0], throughput.clone(), 1_000.0),
+        );
+        decorated.throughput(throughput.clone());
+        decorated.bench_function(
+            "plain",
+            formatting_bench(&formatted[1], throughput.clone(), 1_000.0),
+        );
+        decorated.finish();
+
+        let mut plain = c.benchmark_group("plain");
+        plain.throughput(throughput.clone());
+        plain.bench_function(
+            "plain",
+            formatting_bench(&formatted[2], throughput.clone(), 1_000.0),
+        );
+        plain.finish();
+
+        let after = DecimalByteMeasurement::new().format_throughput(&throughput, 1_000.0);
+
+        assert_eq!(
+            formatted.map(|f| f.into_inner().unwrap()),
+            ["1.0000 GB/s, 5.0000 Melem/s", "1.0000 GB/s", "1.0000 GB/s"]
+        );
+        assert_eq!(after, "1.0000 GB/s");
+    }
+
+    /// Summary of a group with consistent units in which benchmarks of the given sizes
+    /// were reported in the given order, each with the same times.
+    fn consistent_summary(sizes: &[u64]) -> Vec<String> {
+        let measurement = DecimalByteMeasurement::new();
+        let mut scope = GroupScope {
+            decoration: None,
+            consistent_units: Some(ConsistentUnits::default()),
+        };
+        context::with_group_scope(&mut scope, || {
+            for size in sizes {
+                context::with_benchmark_id(Some(format!("sweep/{}", size)), || {
+                    for nanos in [1_250.0, 1_000.0, 800.0] {
+                        measurement.format_throughput(&Throughput::Bytes(*size), nanos);
+                    }
+                });
+            }
+        });
+        scope.consistent_units.unwrap().summary
+    }
+
+    #[test]
+    fn consistent_units_are_chosen_from_the_group_wide_typical_value() {
+        let summary = consistent_summary(&[100, 1_000, 10_000]);
+
+        assert_eq!(
+            summary,
+            [
+                "sweep/100    thrpt:  [0.0800 GB/s 0.1000 GB/s 0.1250 GB/s]",
+                "sweep/1000   thrpt:  [0.8000 GB/s 1.0000 GB/s 1.2500 GB/s]",
+                "sweep/10000  thrpt:  [8.0000 GB/s 10.000 GB/s 12.500 GB/s]",
+            ]
+        );
+    }
+
+    #[test]
+    fn consistent_units_do_not_depend_on_order() {
+        let mut reversed = consistent_summary(&[10_000, 1_000, 100]);
+        reversed.reverse();
+
+        assert_eq!(reversed, consistent_summary(&[100, 1_000, 10_000]));
+    }
+
+    /// Benchmark that records how a measurement with unit overrides formats the throughput.
+    fn overridden_bench<'a>(
+        formatted: &'a RefCell<Option<String>>,
+    ) -> impl FnMut(&mut Bencher<'_, DecimalByteMeasurement>) + 'a {
+        move |b| {
+            let measurement = DecimalByteMeasurement::new()
+                .with_unit_override("rows/parse", UnitOverride::Multiple(Multiple::Mega))
+                .with_unit_override("rows/fold/10", UnitOverride::Multiple(Multiple::Mega));
+            *formatted.borrow_mut() =
+                Some(measurement.format_throughput(&Throughput::Bytes(2_000_000), 1_000_000.0));
+            b.iter(|| 2 + 2)
+        }
+    }
+
+    #[test]
+    fn unit_overrides_apply_by_full_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut c = test_criterion(dir.path());
+        let formatted = [(); 4].map(|_| RefCell::new(None));
+
+        let mut rows = ThroughputGroup::new(&mut c, "rows");
+        rows.throughput(Throughput::Bytes(2_000_000));
+        rows.bench_function("parse", overridden_bench(&formatted[0]));
+        rows.bench_with_input(DecimalBenchmarkId::new("fold", 10), &10, {
+            let mut bench = overridden_bench(&formatted[1]);
+            move |b, _| bench(b)
+        });
+        rows.bench_with_input(BenchmarkId::new("sum", 10), &10, {
+            let mut bench = overridden_bench(&formatted[2]);
+            move |b, _| bench(b)
+        });
+        rows.finish();
+
+        let mut other = ThroughputGroup::new(&mut c, "other");
+        other.throughput(Throughput::Bytes(2_000_000));
+        other.bench_function("parse", overridden_bench(&formatted[3]));
+        other.finish();
+
+        assert_eq!(
+            formatted.map(|f| f.into_inner().unwrap()),
+            ["2000.0 MB/s", "2000.0 MB/s", "2.0000 GB/s", "2.0000 GB/s"]
+        );
+    }
+}
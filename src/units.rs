@@ -0,0 +1,727 @@
+//! Decimal multiples and units used by this crate, and helpers to scale values with them.
+//!
+//! These are the building blocks of [`DecimalByteMeasurement`], exposed for use in custom reports.
+//!
+//! # Example
+//!
+//! ```
+//! use criterion::Throughput;
+//! use criterion_decimal_throughput::units::{self, Multiple, Unit};
+//!
+//! assert_eq!(units::scale_decimal(2_500_000.0), (2.5, "M"));
+//! assert_eq!(Unit::Byte.rate_label(Multiple::Giga), "GB/s");
+//! // 1 MB in 500 µs.
+//! assert_eq!(units::format_throughput(500_000.0, &Throughput::Bytes(1_000_000)), "2.0000 GB/s");
+//! ```
+
+use crate::format;
+#[cfg(doc)]
+use crate::DecimalByteMeasurement;
+use criterion::{measurement::ValueFormatter, Throughput};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+    sync::Mutex,
+};
+
+/// Decimal multiple of a unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Multiple {
+    /// No multiple, 1.
+    One,
+    /// 10^3.
+    Kilo,
+    /// 10^6.
+    Mega,
+    /// 10^9.
+    Giga,
+    /// 10^12.
+    Tera,
+}
+
+/// Base unit of throughput.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Unit {
+    /// Bytes, from [`Throughput::Bytes`].
+    Byte,
+    /// Elements, from [`Throughput::Elements`].
+    Elem,
+}
+
+/// Period of time a rate is expressed per.
+///
+/// Rates are expressed per [`Period::Second`] by default. Longer periods are useful for
+/// rates below one unit per second, see [`DecimalByteMeasurement::with_sub_unit_rates`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Period {
+    /// Per second.
+    Second,
+    /// Per minute.
+    Minute,
+    /// Per hour.
+    Hour,
+}
+
+/// Statistic of the scaled values used to select the multiple of throughput,
+/// see [`DecimalByteMeasurement::with_unit_statistic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Statistic {
+    /// The typical value reported by Criterion.rs, usually the point estimate.
+    #[default]
+    Typical,
+    /// The lowest throughput among the values, e.g. the lower bound of the confidence interval.
+    Lowest,
+    /// The median throughput among the values.
+    Median,
+    /// The highest throughput among the values, e.g. the upper bound of the confidence interval.
+    Highest,
+}
+
+impl Statistic {
+    /// The time in nanoseconds to select the multiple of throughput for, out of the given values,
+    /// or `typical_value` if there are none.
+    pub fn select(self, typical_value: f64, values: &[f64]) -> f64 {
+        let mut times: Vec<f64> = values.iter().copied().filter(|v| !v.is_nan()).collect();
+        if times.is_empty() {
+            return typical_value;
+        }
+        times.sort_by(f64::total_cmp);
+
+        // Throughput is inversely proportional to time.
+        match self {
+            Statistic::Typical => typical_value,
+            Statistic::Lowest => times[times.len() - 1],
+            Statistic::Median => times[(times.len() - 1) / 2],
+            Statistic::Highest => times[0],
+        }
+    }
+}
+
+/// Unit of time used to display the measured times, see [`DecimalByteMeasurement::with_time_unit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimeUnit {
+    /// Nanoseconds, `ns`.
+    Nanoseconds,
+    /// Microseconds, `µs`.
+    Microseconds,
+    /// Milliseconds, `ms`.
+    Milliseconds,
+    /// Seconds, `s`.
+    Seconds,
+}
+
+impl TimeUnit {
+    /// Length of the unit in nanoseconds.
+    pub fn nanos(self) -> f64 {
+        match self {
+            TimeUnit::Nanoseconds => 1.0,
+            TimeUnit::Microseconds => 1e3,
+            TimeUnit::Milliseconds => 1e6,
+            TimeUnit::Seconds => 1e9,
+        }
+    }
+
+    /// Label of the unit, the same as the one used by Criterion.rs, e.g. `"ms"`.
+    pub fn label(self) -> &'static str {
+        match self {
+            TimeUnit::Nanoseconds => "ns",
+            TimeUnit::Microseconds => "µs",
+            TimeUnit::Milliseconds => "ms",
+            TimeUnit::Seconds => "s",
+        }
+    }
+}
+
+impl Period {
+    /// Length of the period in seconds.
+    pub fn seconds(self) -> f64 {
+        match self {
+            Period::Second => 1.0,
+            Period::Minute => 60.0,
+            Period::Hour => 3_600.0,
+        }
+    }
+
+    /// The shortest period in which the given rate per second amounts to at least one unit,
+    /// or [`Period::Hour`] if there is no such period.
+    pub fn for_rate(units_per_second: f64) -> Period {
+        if units_per_second >= 1.0 {
+            Period::Second
+        } else if units_per_second * 60.0 >= 1.0 {
+            Period::Minute
+        } else {
+            Period::Hour
+        }
+    }
+}
+
+impl Multiple {
+    /// All multiples in ascending order.
+    pub const ALL: [Multiple; 5] = [
+        Multiple::One,
+        Multiple::Kilo,
+        Multiple::Mega,
+        Multiple::Giga,
+        Multiple::Tera,
+    ];
+
+    /// The value a quantity is divided by when expressed in this multiple.
+    pub fn denominator(self) -> f64 {
+        match self {
+            Multiple::One => 1.0,
+            Multiple::Kilo => 1_000.0,
+            Multiple::Mega => 1_000_000.0,
+            Multiple::Giga => 1_000_000_000.0,
+            Multiple::Tera => 1_000_000_000_000.0,
+        }
+    }
+
+    /// The SI prefix of this multiple, e.g. `"M"` for [`Multiple::Mega`].
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Multiple::One => "",
+            Multiple::Kilo => "K",
+            Multiple::Mega => "M",
+            Multiple::Giga => "G",
+            Multiple::Tera => "T",
+        }
+    }
+
+    /// The largest multiple in which `value` is at least one, or [`Multiple::One`] for values below one.
+    pub fn for_value(value: f64) -> Multiple {
+        if value >= 1e12 {
+            Multiple::Tera
+        } else if value >= 1e9 {
+            Multiple::Giga
+        } else if value >= 1e6 {
+            Multiple::Mega
+        } else if value >= 1e3 {
+            Multiple::Kilo
+        } else {
+            Multiple::One
+        }
+    }
+
+    /// The smallest multiple of the divisor in which a value per unit is at least one,
+    /// e.g. [`Multiple::Kilo`] for 0.5 ns per byte, which is 500 ns per kilobyte.
+    pub fn for_value_per_unit(value_per_unit: f64) -> Multiple {
+        if value_per_unit >= 1.0 {
+            Multiple::One
+        } else if value_per_unit >= 1e-3 {
+            Multiple::Kilo
+        } else if value_per_unit >= 1e-6 {
+            Multiple::Mega
+        } else if value_per_unit >= 1e-9 {
+            Multiple::Giga
+        } else {
+            Multiple::Tera
+        }
+    }
+}
+
+/// Error returned when parsing a [`Multiple`] from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMultipleError(String);
+
+impl FromStr for Multiple {
+    type Err = ParseMultipleError;
+
+    /// Parse the name of the multiple, e.g. `mega`, or its prefix, e.g. `M`, case-insensitively.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "one" => Ok(Multiple::One),
+            "kilo" | "k" => Ok(Multiple::Kilo),
+            "mega" | "m" => Ok(Multiple::Mega),
+            "giga" | "g" => Ok(Multiple::Giga),
+            "tera" | "t" => Ok(Multiple::Tera),
+            _ => Err(ParseMultipleError(s.to_owned())),
+        }
+    }
+}
+
+impl Display for ParseMultipleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid multiple '{}', expected one of 'one', 'kilo', 'mega', 'giga', or 'tera'",
+            self.0
+        )
+    }
+}
+
+impl Error for ParseMultipleError {}
+
+impl Unit {
+    /// The unit of the given throughput.
+    pub fn of(throughput: &Throughput) -> Unit {
+        match throughput {
+            Throughput::Bytes(_) => Unit::Byte,
+            Throughput::Elements(_) => Unit::Elem,
+        }
+    }
+
+    /// Label of this unit per second in the given multiple, e.g. `"MB/s"`.
+    ///
+    /// Labels without a multiple start with a space, so that they are aligned in the console output.
+    pub fn rate_label(self, multiple: Multiple) -> &'static str {
+        use Multiple::*;
+        use Unit::*;
+
+        match (self, multiple) {
+            (Byte, One) => " B/s",
+            (Byte, Kilo) => "KB/s",
+            (Byte, Mega) => "MB/s",
+            (Byte, Giga) => "GB/s",
+            (Byte, Tera) => "TB/s",
+            (Elem, One) => " elem/s",
+            (Elem, Kilo) => "Kelem/s",
+            (Elem, Mega) => "Melem/s",
+            (Elem, Giga) => "Gelem/s",
+            (Elem, Tera) => "Telem/s",
+        }
+    }
+
+    /// Label of this unit per CPU cycle in the given multiple, e.g. `"KB/cycle"`.
+    pub fn per_cycle_label(self, multiple: Multiple) -> &'static str {
+        use Multiple::*;
+        use Unit::*;
+
+        match (self, multiple) {
+            (Byte, One) => " B/cycle",
+            (Byte, Kilo) => "KB/cycle",
+            (Byte, Mega) => "MB/cycle",
+            (Byte, Giga) => "GB/cycle",
+            (Byte, Tera) => "TB/cycle",
+            (Elem, One) => " elem/cycle",
+            (Elem, Kilo) => "Kelem/cycle",
+            (Elem, Mega) => "Melem/cycle",
+            (Elem, Giga) => "Gelem/cycle",
+            (Elem, Tera) => "Telem/cycle",
+        }
+    }
+
+    /// Label of this unit per joule in the given multiple, e.g. `"MB/J"`.
+    pub fn per_joule_label(self, multiple: Multiple) -> &'static str {
+        use Multiple::*;
+        use Unit::*;
+
+        match (self, multiple) {
+            (Byte, One) => " B/J",
+            (Byte, Kilo) => "KB/J",
+            (Byte, Mega) => "MB/J",
+            (Byte, Giga) => "GB/J",
+            (Byte, Tera) => "TB/J",
+            (Elem, One) => " elem/J",
+            (Elem, Kilo) => "Kelem/J",
+            (Elem, Mega) => "Melem/J",
+            (Elem, Giga) => "Gelem/J",
+            (Elem, Tera) => "Telem/J",
+        }
+    }
+
+    /// Label of this unit per the given period without a multiple, e.g. `" elem/min"`.
+    pub fn period_label(self, period: Period) -> &'static str {
+        use Period::*;
+        use Unit::*;
+
+        match (self, period) {
+            (Byte, Second) => " B/s",
+            (Byte, Minute) => " B/min",
+            (Byte, Hour) => " B/h",
+            (Elem, Second) => " elem/s",
+            (Elem, Minute) => " elem/min",
+            (Elem, Hour) => " elem/h",
+        }
+    }
+
+    /// Label of nanoseconds per this unit in the given multiple, e.g. `"ns/MB"`.
+    pub fn inverse_rate_label(self, multiple: Multiple) -> &'static str {
+        use Multiple::*;
+        use Unit::*;
+
+        match (self, multiple) {
+            (Byte, One) => "ns/B",
+            (Byte, Kilo) => "ns/KB",
+            (Byte, Mega) => "ns/MB",
+            (Byte, Giga) => "ns/GB",
+            (Byte, Tera) => "ns/TB",
+            (Elem, One) => "ns/elem",
+            (Elem, Kilo) => "ns/Kelem",
+            (Elem, Mega) => "ns/Melem",
+            (Elem, Giga) => "ns/Gelem",
+            (Elem, Tera) => "ns/Telem",
+        }
+    }
+}
+
+/// Ladder of multiples of a custom unit, e.g. pixels or frames, used to display
+/// [`Throughput::Elements`] with [`DecimalByteMeasurement::with_element_units`].
+///
+/// The ladder is a list of denominators with the labels of rates in them, in ascending order.
+/// The largest multiple in which the rate is at least one is used, or the first one for
+/// rates below all of them. Use [`decimal_unit_system!`](crate::decimal_unit_system)
+/// for a decimal ladder of a named unit.
+///
+/// # Example
+///
+/// ```
+/// use criterion_decimal_throughput::units::UnitSystem;
+///
+/// /// Frames, displayed in frames per second or thousands of them.
+/// struct Frames;
+///
+/// impl UnitSystem for Frames {
+///     fn ladder(&self) -> &[(f64, &'static str)] {
+///         &[(1.0, "fps"), (1e3, "Kfps")]
+///     }
+/// }
+/// ```
+pub trait UnitSystem {
+    /// Denominators of the multiples with their rate labels, in ascending order, e.g. `(1e3, "Kpx/s")`.
+    fn ladder(&self) -> &[(f64, &'static str)];
+}
+
+/// [`UnitSystem`] of all decimal [`Multiple`]s of a unit.
+///
+/// Usually created with [`decimal_unit_system!`](crate::decimal_unit_system).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecimalUnitSystem {
+    ladder: [(f64, &'static str); 5],
+}
+
+impl DecimalUnitSystem {
+    /// Create a system from the rate labels in all [`Multiple::ALL`], e.g. `" px/s"`, `"Kpx/s"`, etc.
+    pub const fn from_labels(labels: [&'static str; 5]) -> Self {
+        DecimalUnitSystem {
+            ladder: [
+                (1.0, labels[0]),
+                (1e3, labels[1]),
+                (1e6, labels[2]),
+                (1e9, labels[3]),
+                (1e12, labels[4]),
+            ],
+        }
+    }
+}
+
+impl UnitSystem for DecimalUnitSystem {
+    fn ladder(&self) -> &[(f64, &'static str)] {
+        &self.ladder
+    }
+}
+
+/// Domain quantity processed by a benchmark, e.g. pixels of an image, audio samples,
+/// or floating-point operations of a matrix multiplication, displayed in its own [`UnitSystem`].
+///
+/// Set the throughput of a group to a quantity with
+/// [`ThroughputGroup::throughput_of_kind`](crate::ThroughputGroup::throughput_of_kind),
+/// which scales it through the [`DecimalUnitSystem`] of the kind instead of `elem/s`.
+///
+/// # Example
+///
+/// ```
+/// use criterion_decimal_throughput::{
+///     decimal_unit_system, units::{DecimalUnitSystem, ThroughputKind}, ThroughputGroup,
+/// };
+///
+/// /// Pixels of an image, displayed in `Mpx/s`, `Gpx/s`, etc.
+/// struct Pixels {
+///     width: u64,
+///     height: u64,
+/// }
+///
+/// impl ThroughputKind for Pixels {
+///     fn units(&self) -> DecimalUnitSystem {
+///         decimal_unit_system!("px")
+///     }
+///
+///     fn count(&self) -> u64 {
+///         self.width * self.height
+///     }
+/// }
+///
+/// let mut c = criterion_decimal_throughput::decimal_byte_measurement();
+/// let mut group = ThroughputGroup::new(&mut c, "blur");
+/// group.throughput_of_kind(&Pixels { width: 1920, height: 1080 });
+///
+/// // Add your benchmarks to the group here...
+///
+/// group.finish();
+/// ```
+pub trait ThroughputKind {
+    /// Units the rates of the quantity are displayed in.
+    fn units(&self) -> DecimalUnitSystem;
+
+    /// Number of units of the quantity processed in one iteration.
+    fn count(&self) -> u64;
+}
+
+/// Create a [`DecimalUnitSystem`] for the unit with the given label.
+///
+/// # Example
+///
+/// ```
+/// use criterion_decimal_throughput::{decimal_unit_system, units::{DecimalUnitSystem, UnitSystem}};
+///
+/// const PIXELS: DecimalUnitSystem = decimal_unit_system!("px");
+///
+/// assert_eq!(PIXELS.ladder()[2], (1e6, "Mpx/s"));
+/// ```
+#[macro_export]
+macro_rules! decimal_unit_system {
+    ($label:literal) => {
+        $crate::units::DecimalUnitSystem::from_labels([
+            concat!(" ", $label, "/s"),
+            concat!("K", $label, "/s"),
+            concat!("M", $label, "/s"),
+            concat!("G", $label, "/s"),
+            concat!("T", $label, "/s"),
+        ])
+    };
+}
+
+/// Ready-made [`UnitSystem`]s for common kinds of elements.
+///
+/// # Example
+///
+/// ```
+/// use criterion::{measurement::ValueFormatter, Throughput};
+/// use criterion_decimal_throughput::{units::Preset, DecimalByteMeasurement};
+///
+/// let measurement = DecimalByteMeasurement::preset(Preset::Iops);
+///
+/// let result = measurement.format_throughput(&Throughput::Elements(2_000), 1_000_000.0);
+///
+/// assert_eq!(result, "2.0000 Mops/s");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Preset {
+    /// Operations, e.g. I/O operations, in `ops/s`, `Kops/s`, etc.
+    Iops,
+    /// Queries in `qps`, `Kqps`, etc.
+    Qps,
+    /// Frames in `fps`, `Kfps`, etc.
+    Fps,
+    /// Network packets in `pps`, `Kpps`, `Mpps`, etc.
+    Pps,
+}
+
+const IOPS: DecimalUnitSystem = decimal_unit_system!("ops");
+const QPS: DecimalUnitSystem =
+    DecimalUnitSystem::from_labels([" qps", "Kqps", "Mqps", "Gqps", "Tqps"]);
+const FPS: DecimalUnitSystem =
+    DecimalUnitSystem::from_labels([" fps", "Kfps", "Mfps", "Gfps", "Tfps"]);
+const PPS: DecimalUnitSystem =
+    DecimalUnitSystem::from_labels([" pps", "Kpps", "Mpps", "Gpps", "Tpps"]);
+
+impl UnitSystem for Preset {
+    fn ladder(&self) -> &[(f64, &'static str)] {
+        match self {
+            Preset::Iops => IOPS.ladder(),
+            Preset::Qps => QPS.ladder(),
+            Preset::Fps => FPS.ladder(),
+            Preset::Pps => PPS.ladder(),
+        }
+    }
+}
+
+/// Unit used for a single benchmark instead of the configured ones, registered with
+/// [`DecimalByteMeasurement::with_unit_override`].
+///
+/// # Example
+///
+/// ```
+/// use criterion_decimal_throughput::{decimal_unit_system, units::{Multiple, UnitOverride}, DecimalByteMeasurement};
+///
+/// let measurement = DecimalByteMeasurement::new()
+///     .with_unit_override("csv/parse_rows", UnitOverride::units(decimal_unit_system!("rows")))
+///     .with_unit_override("csv/copy", UnitOverride::Multiple(Multiple::Mega));
+/// ```
+#[non_exhaustive]
+pub enum UnitOverride {
+    /// Display the throughput in the given unit system, e.g. `Mrows/s`,
+    /// regardless of whether it is given in bytes or in elements.
+    Units(Box<dyn UnitSystem>),
+    /// Always display the throughput in units per second with the given multiple, e.g. in `MB/s`.
+    Multiple(Multiple),
+}
+
+impl UnitOverride {
+    /// Create a [`UnitOverride::Units`] override with the given unit system.
+    pub fn units<U: UnitSystem + 'static>(units: U) -> Self {
+        UnitOverride::Units(Box::new(units))
+    }
+}
+
+/// Scale the rate to the largest multiple of the ladder of `system` in which it is at least
+/// `threshold`, returning the scaled value and the label.
+///
+/// # Panics
+///
+/// If the ladder is empty.
+pub fn scale_in_system(system: &dyn UnitSystem, rate: f64, threshold: f64) -> (f64, &'static str) {
+    let (denominator, label) = select_in_system(system, rate, threshold);
+    (rate / denominator, label)
+}
+
+/// Select the denominator and label of the multiple to use for the rate, see [`scale_in_system`].
+pub(crate) fn select_in_system(
+    system: &dyn UnitSystem,
+    rate: f64,
+    threshold: f64,
+) -> (f64, &'static str) {
+    let ladder = system.ladder();
+    *ladder
+        .iter()
+        .rev()
+        .find(|(denominator, _)| rate >= denominator * threshold)
+        .or_else(|| ladder.first())
+        .expect("ladder of a unit system must not be empty")
+}
+
+/// Number of bytes or elements in the given throughput.
+pub fn total_units(throughput: &Throughput) -> f64 {
+    match *throughput {
+        Throughput::Bytes(bytes) => bytes as f64,
+        Throughput::Elements(elements) => elements as f64,
+    }
+}
+
+/// Scale the value to the largest decimal [`Multiple`] in which it is at least one,
+/// returning the scaled value and the SI prefix.
+pub fn scale_decimal(value: f64) -> (f64, &'static str) {
+    let multiple = Multiple::for_value(value);
+    (value / multiple.denominator(), multiple.prefix())
+}
+
+/// Label of a rate per core, so `"GB/s"` becomes `"GB/(core·s)"`.
+///
+/// Criterion.rs requires unit labels to be `&'static str`, so the labels are leaked
+/// once for each distinct label.
+pub(crate) fn per_core_label(label: &'static str) -> &'static str {
+    static LABELS: Mutex<BTreeMap<&'static str, &'static str>> = Mutex::new(BTreeMap::new());
+
+    let mut labels = LABELS.lock().unwrap_or_else(|err| err.into_inner());
+    labels.entry(label).or_insert_with(|| {
+        let per_core = match label.rsplit_once('/') {
+            Some((unit, period)) => format!("{}/(core·{})", unit, period),
+            None => format!("{}/core", label),
+        };
+        Box::leak(per_core.into_boxed_str())
+    })
+}
+
+/// Scale the value to the largest binary multiple (1024, 1024^2, etc.) in which it is at least one,
+/// up to 1024^4, returning the scaled value and the IEC prefix.
+pub fn scale_binary(value: f64) -> (f64, &'static str) {
+    const PREFIXES: [&str; 5] = ["", "Ki", "Mi", "Gi", "Ti"];
+    let mut scaled = value;
+    let mut power = 0;
+    while scaled >= 1024.0 && power < PREFIXES.len() - 1 {
+        scaled /= 1024.0;
+        power += 1;
+    }
+    (scaled, PREFIXES[power])
+}
+
+/// Format the throughput of an iteration that took `nanos` nanoseconds the same way
+/// [`DecimalByteMeasurement`] with the default options does in the console output,
+/// without the padding, e.g. `"2.0000 GB/s"`.
+///
+/// Only the throughput itself is formatted, regardless of the benchmark being run,
+/// so e.g. decorations of a [`ThroughputGroup`](crate::ThroughputGroup) do not apply.
+pub fn format_throughput(nanos: f64, throughput: &Throughput) -> String {
+    format::Plain
+        .format_throughput(throughput, nanos)
+        .trim()
+        .to_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn for_value_picks_largest_multiple() {
+        assert_eq!(Multiple::for_value(0.5), Multiple::One);
+        assert_eq!(Multiple::for_value(999.9), Multiple::One);
+        assert_eq!(Multiple::for_value(1_000.0), Multiple::Kilo);
+        assert_eq!(Multiple::for_value(1e15), Multiple::Tera);
+    }
+
+    #[test]
+    fn parse_multiple_from_name_or_prefix() {
+        assert_eq!("mega".parse(), Ok(Multiple::Mega));
+        assert_eq!(" G ".parse(), Ok(Multiple::Giga));
+        assert_eq!(
+            "milli".parse::<Multiple>().unwrap_err().to_string(),
+            "invalid multiple 'milli', expected one of 'one', 'kilo', 'mega', 'giga', or 'tera'"
+        );
+    }
+
+    #[test]
+    fn scale_decimal_values() {
+        assert_eq!(scale_decimal(12.0), (12.0, ""));
+        assert_eq!(scale_decimal(3_000_000_000.0), (3.0, "G"));
+    }
+
+    #[test]
+    fn scale_in_decimal_system() {
+        let pixels = decimal_unit_system!("px");
+
+        assert_eq!(scale_in_system(&pixels, 2_500_000.0, 1.0), (2.5, "Mpx/s"));
+        assert_eq!(scale_in_system(&pixels, 0.5, 1.0), (0.5, " px/s"));
+    }
+
+    #[test]
+    fn scale_in_preset_systems() {
+        assert_eq!(
+            scale_in_system(&Preset::Iops, 2_000.0, 1.0),
+            (2.0, "Kops/s")
+        );
+        assert_eq!(scale_in_system(&Preset::Qps, 2_000.0, 1.0), (2.0, "Kqps"));
+        assert_eq!(scale_in_system(&Preset::Fps, 60.0, 1.0), (60.0, " fps"));
+        assert_eq!(scale_in_system(&Preset::Pps, 1.5e7, 1.0), (15.0, "Mpps"));
+    }
+
+    #[test]
+    fn time_units_match_their_labels() {
+        assert_eq!(TimeUnit::Microseconds.nanos(), 1e3);
+        assert_eq!(TimeUnit::Microseconds.label(), "µs");
+        assert_eq!(TimeUnit::Seconds.nanos(), 1e9);
+        assert_eq!(TimeUnit::Seconds.label(), "s");
+    }
+
+    #[test]
+    fn statistic_selects_time_of_throughput() {
+        let values = [300.0, 100.0, 200.0];
+
+        assert_eq!(Statistic::Typical.select(150.0, &values), 150.0);
+        assert_eq!(Statistic::Lowest.select(150.0, &values), 300.0);
+        assert_eq!(Statistic::Median.select(150.0, &values), 200.0);
+        assert_eq!(Statistic::Highest.select(150.0, &values), 100.0);
+        assert_eq!(Statistic::Highest.select(150.0, &[]), 150.0);
+    }
+
+    #[test]
+    fn scale_binary_values() {
+        assert_eq!(scale_binary(1_000.0), (1_000.0, ""));
+        assert_eq!(scale_binary(3_221_225_472.0), (3.0, "Gi"));
+        assert_eq!(scale_binary(2_f64.powi(50)), (1_024.0, "Ti"));
+    }
+
+    #[test]
+    fn format_throughput_ignores_state_of_the_benchmark() {
+        let measurement = crate::DecimalByteMeasurement::new();
+        criterion::measurement::Measurement::start(&measurement);
+        crate::context::record_observed_bytes(5_000);
+
+        let formatted = format_throughput(1_000.0, &Throughput::Bytes(1_000));
+
+        assert_eq!(formatted, "1.0000 GB/s");
+        assert_eq!(crate::context::observed_bytes(measurement.id), Some(5_000));
+    }
+}
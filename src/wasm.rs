@@ -0,0 +1,173 @@
+use crate::DecimalByteMeasurement;
+use criterion::measurement::{Measurement, ValueFormatter, WallTime};
+use std::time::Duration;
+
+/// [`DecimalByteMeasurement`] measuring wall time with a clock available on `wasm32-unknown-unknown`.
+///
+/// Requires the `wasm` feature.
+///
+/// # Example
+///
+/// ```
+/// use criterion_decimal_throughput::DecimalByteMeasurement;
+///
+/// let config = criterion::Criterion::default().with_measurement(DecimalByteMeasurement::wasm_time());
+/// ```
+pub type DecimalByteWasmTime = DecimalByteMeasurement<WasmTime>;
+
+/// Measurement of wall time in nanoseconds that works both natively and in wasm runners.
+///
+/// On `wasm32-unknown-unknown`, where [`std::time::Instant`] is not available, the time is read from
+/// `performance.now()` of the JavaScript host, falling back to `Date.now()` if it does not have one.
+/// On all other targets it is the same clock as [`WallTime`], so the same benchmarks can run in both.
+/// Values are displayed the same way as for [`WallTime`].
+///
+/// Note that Criterion.rs itself uses [`std::time::Instant`] to time its warm-up,
+/// so the host has to provide it as well, as do WASI runtimes.
+///
+/// Requires the `wasm` feature.
+pub struct WasmTime {
+    wall_time: WallTime,
+}
+
+impl WasmTime {
+    /// Create a new [`WasmTime`] measurement.
+    pub fn new() -> Self {
+        WasmTime {
+            wall_time: WallTime,
+        }
+    }
+}
+
+impl Default for WasmTime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DecimalByteMeasurement<WasmTime> {
+    /// Create a new [`DecimalByteWasmTime`] struct.
+    pub fn wasm_time() -> Self {
+        Self::from_time_measurement(WasmTime::new())
+    }
+}
+
+/// Point in time read by [`WasmTime`] at the start of a measurement.
+#[derive(Debug, Clone, Copy)]
+pub struct WasmInstant(clock::Instant);
+
+impl Measurement for WasmTime {
+    type Intermediate = WasmInstant;
+
+    type Value = Duration;
+
+    fn start(&self) -> Self::Intermediate {
+        WasmInstant(clock::now())
+    }
+
+    fn end(&self, i: Self::Intermediate) -> Self::Value {
+        clock::elapsed(i.0)
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        self.wall_time.add(v1, v2)
+    }
+
+    fn zero(&self) -> Self::Value {
+        self.wall_time.zero()
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        self.wall_time.to_f64(value)
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        self.wall_time.formatter()
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+mod clock {
+    use js_sys::{
+        wasm_bindgen::{JsCast, JsValue},
+        Function, Reflect,
+    };
+    use std::time::Duration;
+
+    /// Milliseconds since an arbitrary point in time.
+    pub(super) type Instant = f64;
+
+    thread_local! {
+        /// The `performance` object of the host and its `now` function, if it has them.
+        static PERFORMANCE: Option<(JsValue, Function)> = performance();
+    }
+
+    fn performance() -> Option<(JsValue, Function)> {
+        let performance =
+            Reflect::get(&js_sys::global(), &JsValue::from_str("performance")).ok()?;
+        let now = Reflect::get(&performance, &JsValue::from_str("now"))
+            .ok()?
+            .dyn_into::<Function>()
+            .ok()?;
+        Some((performance, now))
+    }
+
+    pub(super) fn now() -> Instant {
+        PERFORMANCE
+            .with(|performance| {
+                let (performance, now) = performance.as_ref()?;
+                now.call0(performance).ok()?.as_f64()
+            })
+            .unwrap_or_else(js_sys::Date::now)
+    }
+
+    pub(super) fn elapsed(start: Instant) -> Duration {
+        // The clock has a resolution of at best microseconds, and `Date.now()` can go back.
+        Duration::from_secs_f64((now() - start).max(0.0) / 1e3)
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+mod clock {
+    use std::time::Duration;
+
+    pub(super) type Instant = std::time::Instant;
+
+    pub(super) fn now() -> Instant {
+        Instant::now()
+    }
+
+    pub(super) fn elapsed(start: Instant) -> Duration {
+        start.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use criterion::Throughput;
+
+    #[test]
+    fn wasm_time_measures_elapsed_time() {
+        let measurement = WasmTime::new();
+        let start = measurement.start();
+        std::thread::sleep(Duration::from_millis(1));
+
+        assert!(measurement.to_f64(&measurement.end(start)) >= 1e6);
+    }
+
+    #[test]
+    fn wasm_time_uses_decimal_formatter() {
+        let measurement = DecimalByteMeasurement::wasm_time();
+        let mut values = [1_000.0];
+
+        let result = measurement.formatter().scale_throughputs(
+            1_000.0,
+            &Throughput::Bytes(2_000),
+            &mut values,
+        );
+
+        assert_eq!(result, "GB/s");
+        assert_eq!(values, [2.0]);
+    }
+}